@@ -0,0 +1,756 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::spl_token_2022::extension::transfer_fee::TransferFeeConfig;
+use anchor_spl::token_interface::spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensions};
+use anchor_spl::token_interface::{
+    self, transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+// Program ID - This will be updated automatically by the build process.
+declare_id!("5uc1b6X4qmMn2bJedFU5ZynLP4QZc6RVvEsGFLo8w7Ch");
+
+/// Number of seconds in one day, the unit of tier lock durations.
+pub const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Number of seconds in a (365 day) year, the period of APRs.
+pub const SECONDS_PER_YEAR: u64 = 365 * SECONDS_PER_DAY;
+
+/// Basis points denominator (10_000 bps = 100%).
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+#[program]
+pub mod staking {
+    use super::*;
+
+    /// Creates the staking pool for `mint`, with its stake vault and reward vault PDAs.
+    ///
+    /// Rejects Token-2022 mints with the transfer-fee extension: the stake vault would receive
+    /// less than each position records, and unstaking would pay the shortfall out of other
+    /// stakers' principal.
+    ///
+    /// Only the program's upgrade authority can call this function, once per mint.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing accounts required for initialization.
+    /// * `admin`: The wallet allowed to configure tiers.
+    pub fn initialize_pool(ctx: Context<InitializePool>, admin: Pubkey) -> Result<()> {
+        require!(
+            !has_transfer_fee(&ctx.accounts.mint.to_account_info())?,
+            StakingError::TransferFeeNotSupported
+        );
+
+        let pool = &mut ctx.accounts.pool;
+        pool.admin = admin;
+        pool.mint = ctx.accounts.mint.key();
+        pool.tiers = Vec::new();
+        pool.total_staked = 0;
+        pool.reserved_rewards = 0;
+        pool.bump = ctx.bumps.pool;
+        pool.stake_vault_bump = ctx.bumps.stake_vault;
+        pool.reward_vault_bump = ctx.bumps.reward_vault;
+
+        msg!("Staking pool initialized for mint: {} with admin: {}", pool.mint, admin);
+
+        Ok(())
+    }
+
+    /// Replaces the pool's staking tiers (lock duration to APR).
+    ///
+    /// Existing positions keep the lock and APR they were opened with.
+    ///
+    /// Only the pool admin (`StakingPool::admin`) can call this function.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing the pool and its admin.
+    /// * `tiers`: The tiers, by strictly increasing lock duration, e.g. 30 days at 250 bps up to
+    ///            240 days at 1_500 bps.
+    pub fn set_tiers(ctx: Context<SetTiers>, tiers: Vec<StakingTier>) -> Result<()> {
+        // --- Validation ---
+        require!(!tiers.is_empty() && tiers.len() <= StakingPool::MAX_TIERS, StakingError::InvalidTiers);
+        let mut last_lock_days = 0u16;
+        for t in &tiers {
+            require!(t.lock_days > last_lock_days, StakingError::InvalidTiers);
+            require!(t.apr_bps > 0 && t.apr_bps as u64 <= BPS_DENOMINATOR, StakingError::InvalidTiers);
+            last_lock_days = t.lock_days;
+        }
+
+        let pool = &mut ctx.accounts.pool;
+        pool.tiers = tiers;
+
+        msg!("Staking tiers updated: {}", pool.tiers.len());
+        emit!(TiersUpdated { pool: pool.key(), tiers: pool.tiers.clone(), timestamp: Clock::get()?.unix_timestamp });
+
+        Ok(())
+    }
+
+    /// Adds tokens to the reward vault. Anyone can fund rewards, e.g. from the Marketing or
+    /// Dev allocation.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing the funder's token account and the reward vault.
+    /// * `amount`: The number of tokens to add.
+    pub fn fund_rewards(ctx: Context<FundRewards>, amount: u64) -> Result<()> {
+        require!(amount > 0, StakingError::InvalidAmount);
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.funder_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.reward_vault.to_account_info(),
+            authority: ctx.accounts.funder.to_account_info(),
+        };
+        transfer_checked(
+            CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+            amount,
+            ctx.accounts.mint.decimals,
+        )?;
+        ctx.accounts.reward_vault.reload()?;
+
+        msg!("Reward vault funded with {} tokens", amount);
+        emit!(RewardsFunded {
+            pool: ctx.accounts.pool.key(),
+            funder: ctx.accounts.funder.key(),
+            amount,
+            reward_vault_balance: ctx.accounts.reward_vault.amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Stakes tokens in a tier, opening a new position.
+    ///
+    /// The owner may be a PDA signing through CPI (e.g. a `team` vesting account), in which
+    /// case a separate wallet pays the position's rent.
+    ///
+    /// The position's full reward (its APR over the lock duration) is reserved from the
+    /// reward vault up front, so staking fails if the unreserved rewards cannot cover it. This
+    /// keeps the rewards owed to all positions within what the reward vault holds.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing accounts required for staking.
+    /// * `position_id`: A staker-chosen ID, used in the position's PDA seeds, so a wallet can
+    ///                  hold several positions.
+    /// * `tier_index`: The index of the tier in `StakingPool::tiers`.
+    /// * `amount`: The number of tokens to stake.
+    pub fn stake(ctx: Context<Stake>, position_id: u32, tier_index: u8, amount: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let now = Clock::get()?.unix_timestamp;
+
+        // --- Validation ---
+        require!(amount > 0, StakingError::InvalidAmount);
+        let tier = *pool.tiers.get(tier_index as usize).ok_or(StakingError::InvalidTier)?;
+        let lock_seconds = tier.lock_days as u64 * SECONDS_PER_DAY;
+
+        // --- Reserve Rewards ---
+        let reward = reward_for(amount, tier.apr_bps, lock_seconds)?;
+        let available_rewards = ctx.accounts.reward_vault.amount.saturating_sub(pool.reserved_rewards);
+        require!(reward <= available_rewards, StakingError::InsufficientRewards);
+        pool.reserved_rewards = pool.reserved_rewards.checked_add(reward).ok_or(StakingError::MathOverflow)?;
+        pool.total_staked = pool.total_staked.checked_add(amount).ok_or(StakingError::MathOverflow)?;
+
+        // --- Transfer Stake ---
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.owner_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.stake_vault.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+        transfer_checked(
+            CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+            amount,
+            ctx.accounts.mint.decimals,
+        )?;
+
+        // --- Initialize Position ---
+        let position = &mut ctx.accounts.position;
+        position.owner = ctx.accounts.owner.key();
+        position.pool = pool.key();
+        position.position_id = position_id;
+        position.amount = amount;
+        position.apr_bps = tier.apr_bps;
+        position.start_time = now;
+        position.end_time = now.checked_add(lock_seconds as i64).ok_or(StakingError::MathOverflow)?;
+        position.total_reward = reward;
+        position.claimed_reward = 0;
+        position.payer = ctx.accounts.payer.key();
+        position.bump = ctx.bumps.position;
+
+        msg!("Staked {} tokens for {} days at {} bps (reward {})", amount, tier.lock_days, tier.apr_bps, reward);
+        emit!(Staked {
+            pool: pool.key(),
+            position: position.key(),
+            owner: position.owner,
+            amount,
+            lock_days: tier.lock_days,
+            apr_bps: tier.apr_bps,
+            end_time: position.end_time,
+            total_reward: reward,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Pays out the rewards a position has accrued so far. Rewards accrue linearly over the
+    /// lock and stop at its end.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing the position, its owner and the reward vault.
+    pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let position = &mut ctx.accounts.position;
+
+        let reward = position.accrued_reward(now).saturating_sub(position.claimed_reward);
+        require!(reward > 0, StakingError::NothingToClaim);
+        position.claimed_reward = position.claimed_reward.saturating_add(reward);
+
+        let pool = &mut ctx.accounts.pool;
+        pool.reserved_rewards = pool.reserved_rewards.saturating_sub(reward);
+        pay_from_vault(
+            &ctx.accounts.token_program,
+            &ctx.accounts.reward_vault,
+            &ctx.accounts.owner_token_account,
+            &ctx.accounts.mint,
+            pool,
+            reward,
+        )?;
+
+        let position = &ctx.accounts.position;
+        msg!("Claimed {} reward tokens ({} of {})", reward, position.claimed_reward, position.total_reward);
+        emit!(RewardsClaimed {
+            pool: position.pool,
+            position: position.key(),
+            owner: position.owner,
+            amount: reward,
+            claimed_reward: position.claimed_reward,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Closes a position once its lock has ended, returning the stake and any unclaimed reward.
+    /// The position account's rent is returned to the payer recorded at stake time.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing the position, its owner and the vaults.
+    pub fn unstake(ctx: Context<Unstake>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let position = &ctx.accounts.position;
+
+        // --- Validation ---
+        require!(now >= position.end_time, StakingError::StillLocked);
+
+        // --- Update State ---
+        let amount = position.amount;
+        let reward = position.total_reward.saturating_sub(position.claimed_reward);
+        let pool = &mut ctx.accounts.pool;
+        pool.total_staked = pool.total_staked.saturating_sub(amount);
+        pool.reserved_rewards = pool.reserved_rewards.saturating_sub(reward);
+
+        // --- Transfer Tokens ---
+        pay_from_vault(
+            &ctx.accounts.token_program,
+            &ctx.accounts.stake_vault,
+            &ctx.accounts.owner_token_account,
+            &ctx.accounts.mint,
+            pool,
+            amount,
+        )?;
+        if reward > 0 {
+            pay_from_vault(
+                &ctx.accounts.token_program,
+                &ctx.accounts.reward_vault,
+                &ctx.accounts.reward_token_account,
+                &ctx.accounts.mint,
+                pool,
+                reward,
+            )?;
+        }
+
+        // The position itself is closed by the `close = payer` constraint.
+        msg!("Unstaked {} tokens with {} reward tokens", amount, reward);
+        emit!(Unstaked {
+            pool: pool.key(),
+            position: ctx.accounts.position.key(),
+            owner: ctx.accounts.owner.key(),
+            amount,
+            reward,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+}
+
+/// Returns the reward for staking `amount` at `apr_bps` for `lock_seconds`, rounded down.
+fn reward_for(amount: u64, apr_bps: u16, lock_seconds: u64) -> Result<u64> {
+    let reward = (amount as u128) * (apr_bps as u128) * (lock_seconds as u128)
+        / (BPS_DENOMINATOR as u128 * SECONDS_PER_YEAR as u128);
+    u64::try_from(reward).map_err(|_| error!(StakingError::MathOverflow))
+}
+
+/// Returns whether the mint is a Token-2022 mint with the transfer-fee extension.
+fn has_transfer_fee(mint: &AccountInfo) -> Result<bool> {
+    if *mint.owner != token_interface::spl_token_2022::ID {
+        return Ok(false);
+    }
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<token_interface::spl_token_2022::state::Mint>::unpack(&data)?;
+    Ok(state.get_extension::<TransferFeeConfig>().is_ok())
+}
+
+/// Transfers `amount` tokens out of a pool vault with `transfer_checked`, signed by the pool PDA.
+fn pay_from_vault<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    pool: &Account<'info, StakingPool>,
+    amount: u64,
+) -> Result<()> {
+    let signer_seeds: &[&[&[u8]]] = &[&[b"pool", pool.mint.as_ref(), &[pool.bump]]];
+    let cpi_accounts = TransferChecked {
+        from: vault.to_account_info(),
+        mint: mint.to_account_info(),
+        to: to.to_account_info(),
+        authority: pool.to_account_info(),
+    };
+    transfer_checked(
+        CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer_seeds),
+        amount,
+        mint.decimals,
+    )
+}
+
+/// Defines the accounts required for the `initialize_pool` instruction.
+#[derive(Accounts)]
+pub struct InitializePool<'info> {
+    /// The program's upgrade authority, must be a signer. Pays for account creation.
+    #[account(mut)]
+    pub upgrade_authority: Signer<'info>,
+
+    /// The staking pool PDA. Initialized by this instruction.
+    /// Seeds: "pool", mint pubkey.
+    #[account(
+        init,
+        payer = upgrade_authority,
+        space = 8 + StakingPool::SIZE,
+        seeds = [b"pool", mint.key().as_ref()],
+        bump
+    )]
+    pub pool: Account<'info, StakingPool>,
+
+    /// The mint of the staked (and rewarded) token.
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// The token account holding staked tokens, owned by the pool PDA. Initialized by this instruction.
+    /// Seeds: "stake_vault", pool pubkey.
+    #[account(
+        init,
+        payer = upgrade_authority,
+        seeds = [b"stake_vault", pool.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = pool,
+        token::token_program = token_program
+    )]
+    pub stake_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// The token account holding rewards, owned by the pool PDA. Initialized by this instruction.
+    /// Seeds: "reward_vault", pool pubkey.
+    #[account(
+        init,
+        payer = upgrade_authority,
+        seeds = [b"reward_vault", pool.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = pool,
+        token::token_program = token_program
+    )]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// This program, used to look up its program data account.
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()) @ StakingError::Unauthorized)]
+    pub program: Program<'info, crate::program::Staking>,
+
+    /// The program data account, holding the upgrade authority.
+    #[account(constraint = program_data.upgrade_authority_address == Some(upgrade_authority.key()) @ StakingError::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+
+    /// The Solana System Program, required for creating accounts (`init`).
+    pub system_program: Program<'info, System>,
+
+    /// The token program owning `mint`: SPL Token or Token-2022.
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Defines the accounts required for the `set_tiers` instruction.
+#[derive(Accounts)]
+#[instruction(tiers: Vec<StakingTier>)]
+pub struct SetTiers<'info> {
+    /// The pool admin, must be a signer. Pays for any extra rent.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// The staking pool PDA. Reallocated to fit the tiers.
+    #[account(
+        mut,
+        seeds = [b"pool", pool.mint.as_ref()],
+        bump = pool.bump,
+        has_one = admin @ StakingError::Unauthorized,
+        realloc = 8 + StakingPool::SIZE,
+        realloc::payer = admin,
+        realloc::zero = false
+    )]
+    pub pool: Account<'info, StakingPool>,
+
+    /// The Solana System Program, required for the realloc rent transfer.
+    pub system_program: Program<'info, System>,
+}
+
+/// Defines the accounts required for the `fund_rewards` instruction.
+#[derive(Accounts)]
+pub struct FundRewards<'info> {
+    /// The funder, must be a signer to authorize the transfer.
+    pub funder: Signer<'info>,
+
+    /// The staking pool PDA.
+    #[account(seeds = [b"pool", pool.mint.as_ref()], bump = pool.bump)]
+    pub pool: Account<'info, StakingPool>,
+
+    /// The funder's token account, from which the rewards are transferred.
+    #[account(mut, constraint = funder_token_account.mint == pool.mint @ StakingError::InvalidMint)]
+    pub funder_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The pool's reward vault.
+    #[account(mut, seeds = [b"reward_vault", pool.key().as_ref()], bump = pool.reward_vault_bump)]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// The mint of the staked token, required by `transfer_checked`.
+    #[account(address = pool.mint @ StakingError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// The token program owning `mint`: SPL Token or Token-2022.
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Defines the accounts required for the `stake` instruction.
+#[derive(Accounts)]
+#[instruction(position_id: u32)]
+pub struct Stake<'info> {
+    /// Pays for the position account and receives its rent back on `unstake`.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The staker, must be a signer to authorize the transfer.
+    pub owner: Signer<'info>,
+
+    /// The staking pool PDA. Mutable because the staked and reserved totals increase.
+    #[account(mut, seeds = [b"pool", pool.mint.as_ref()], bump = pool.bump)]
+    pub pool: Account<'info, StakingPool>,
+
+    /// The position PDA. Initialized by this instruction.
+    /// Seeds: "position", pool pubkey, owner pubkey, position ID (u32, little endian).
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + StakePosition::SIZE,
+        seeds = [b"position", pool.key().as_ref(), owner.key().as_ref(), position_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, StakePosition>,
+
+    /// The staker's token account, from which the stake is transferred.
+    #[account(mut, constraint = owner_token_account.mint == pool.mint @ StakingError::InvalidMint)]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The pool's stake vault.
+    #[account(mut, seeds = [b"stake_vault", pool.key().as_ref()], bump = pool.stake_vault_bump)]
+    pub stake_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// The pool's reward vault, read to check that the position's reward can be reserved.
+    #[account(seeds = [b"reward_vault", pool.key().as_ref()], bump = pool.reward_vault_bump)]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// The mint of the staked token, required by `transfer_checked`.
+    #[account(address = pool.mint @ StakingError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// The Solana System Program, required for creating the position account.
+    pub system_program: Program<'info, System>,
+
+    /// The token program owning `mint`: SPL Token or Token-2022.
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Defines the accounts required for the `claim_rewards` instruction.
+#[derive(Accounts)]
+pub struct ClaimRewards<'info> {
+    /// The position's owner, must be a signer.
+    pub owner: Signer<'info>,
+
+    /// The staking pool PDA. Mutable because the reserved total decreases.
+    #[account(mut, seeds = [b"pool", pool.mint.as_ref()], bump = pool.bump)]
+    pub pool: Account<'info, StakingPool>,
+
+    /// The position claiming rewards.
+    #[account(mut, has_one = owner @ StakingError::Unauthorized, has_one = pool @ StakingError::InvalidPool)]
+    pub position: Account<'info, StakePosition>,
+
+    /// The owner's token account receiving the rewards.
+    #[account(mut, constraint = owner_token_account.mint == pool.mint @ StakingError::InvalidMint)]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The pool's reward vault.
+    #[account(mut, seeds = [b"reward_vault", pool.key().as_ref()], bump = pool.reward_vault_bump)]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// The mint of the staked token, required by `transfer_checked`.
+    #[account(address = pool.mint @ StakingError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// The token program owning `mint`: SPL Token or Token-2022.
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Defines the accounts required for the `unstake` instruction.
+#[derive(Accounts)]
+pub struct Unstake<'info> {
+    /// The position's owner, must be a signer.
+    pub owner: Signer<'info>,
+
+    /// The payer recorded at stake time, receiving the position account's rent.
+    /// CHECK: Only receives lamports; the address is checked against `position.payer`.
+    #[account(mut, address = position.payer @ StakingError::InvalidPayer)]
+    pub payer: UncheckedAccount<'info>,
+
+    /// The staking pool PDA. Mutable because the staked and reserved totals decrease.
+    #[account(mut, seeds = [b"pool", pool.mint.as_ref()], bump = pool.bump)]
+    pub pool: Account<'info, StakingPool>,
+
+    /// The position being closed.
+    #[account(
+        mut,
+        close = payer,
+        has_one = owner @ StakingError::Unauthorized,
+        has_one = pool @ StakingError::InvalidPool
+    )]
+    pub position: Account<'info, StakePosition>,
+
+    /// The owner's token account receiving the stake.
+    #[account(mut, constraint = owner_token_account.mint == pool.mint @ StakingError::InvalidMint)]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The token account receiving the unclaimed rewards. May equal `owner_token_account`.
+    #[account(mut, constraint = reward_token_account.mint == pool.mint @ StakingError::InvalidMint)]
+    pub reward_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The pool's stake vault.
+    #[account(mut, seeds = [b"stake_vault", pool.key().as_ref()], bump = pool.stake_vault_bump)]
+    pub stake_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// The pool's reward vault.
+    #[account(mut, seeds = [b"reward_vault", pool.key().as_ref()], bump = pool.reward_vault_bump)]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// The mint of the staked token, required by `transfer_checked`.
+    #[account(address = pool.mint @ StakingError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// The token program owning `mint`: SPL Token or Token-2022.
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// A staking pool for one mint. Owns the stake vault and the reward vault.
+/// Seeds: "pool", mint pubkey.
+#[account]
+pub struct StakingPool {
+    /// The wallet allowed to configure tiers.
+    pub admin: Pubkey,
+    /// The mint of the staked (and rewarded) token.
+    pub mint: Pubkey,
+    /// The available tiers, by strictly increasing lock duration.
+    pub tiers: Vec<StakingTier>,
+    /// Tokens currently staked across all positions.
+    pub total_staked: u64,
+    /// Rewards owed to open positions but not yet paid. Never exceeds the reward vault balance.
+    pub reserved_rewards: u64,
+    /// The bump seed used for the PDA derivation.
+    pub bump: u8,
+    /// The bump seed of the stake vault PDA.
+    pub stake_vault_bump: u8,
+    /// The bump seed of the reward vault PDA.
+    pub reward_vault_bump: u8,
+}
+
+impl StakingPool {
+    /// Maximum number of tiers.
+    pub const MAX_TIERS: usize = 8;
+
+    /// Size of the account data, excluding the 8 byte discriminator.
+    pub const SIZE: usize = 32 // admin: Pubkey
+        + 32 // mint: Pubkey
+        + (4 + Self::MAX_TIERS * StakingTier::SIZE) // tiers: Vec<StakingTier>
+        + 8 // total_staked: u64
+        + 8 // reserved_rewards: u64
+        + 1 // bump: u8
+        + 1 // stake_vault_bump: u8
+        + 1; // reward_vault_bump: u8
+}
+
+/// A staking tier: locking for `lock_days` earns `apr_bps` per year.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct StakingTier {
+    /// Lock duration in days.
+    pub lock_days: u16,
+    /// Annual percentage rate in basis points, e.g. 250 for 2.5%.
+    pub apr_bps: u16,
+}
+
+impl StakingTier {
+    /// Serialized size of a tier.
+    pub const SIZE: usize = 2 // lock_days: u16
+        + 2; // apr_bps: u16
+}
+
+/// A staked amount locked until `end_time`, earning `total_reward` linearly over the lock.
+/// Seeds: "position", pool pubkey, owner pubkey, position ID (u32, little endian).
+#[account]
+pub struct StakePosition {
+    /// The staker.
+    pub owner: Pubkey,
+    /// The pool the position belongs to.
+    pub pool: Pubkey,
+    /// The staker-chosen ID used in the PDA seeds.
+    pub position_id: u32,
+    /// Tokens staked.
+    pub amount: u64,
+    /// The APR the position was opened with, in basis points.
+    pub apr_bps: u16,
+    /// Unix timestamp the position was opened at.
+    pub start_time: i64,
+    /// Unix timestamp the lock ends at.
+    pub end_time: i64,
+    /// The reward earned over the whole lock, reserved from the reward vault at stake time.
+    pub total_reward: u64,
+    /// The reward paid out so far.
+    pub claimed_reward: u64,
+    /// The account that paid the rent at stake time. Receives the lamports on `unstake`.
+    pub payer: Pubkey,
+    /// The bump seed used for the PDA derivation.
+    pub bump: u8,
+}
+
+impl StakePosition {
+    /// Size of the account data, excluding the 8 byte discriminator.
+    pub const SIZE: usize = 32 // owner: Pubkey
+        + 32 // pool: Pubkey
+        + 4 // position_id: u32
+        + 8 // amount: u64
+        + 2 // apr_bps: u16
+        + 8 // start_time: i64
+        + 8 // end_time: i64
+        + 8 // total_reward: u64
+        + 8 // claimed_reward: u64
+        + 32 // payer: Pubkey
+        + 1; // bump: u8
+
+    /// Returns the reward accrued by time `now`: `total_reward` pro rata over the lock.
+    pub fn accrued_reward(&self, now: i64) -> u64 {
+        if now >= self.end_time {
+            return self.total_reward;
+        }
+        let elapsed = now.saturating_sub(self.start_time).max(0) as u128;
+        // The lock is at least one day, so the duration is non-zero.
+        let duration = (self.end_time - self.start_time) as u128;
+        ((self.total_reward as u128) * elapsed / duration) as u64
+    }
+}
+
+// --- Events ---
+
+/// Emitted by `set_tiers`.
+#[event]
+pub struct TiersUpdated {
+    pub pool: Pubkey,
+    pub tiers: Vec<StakingTier>,
+    pub timestamp: i64,
+}
+
+/// Emitted by `fund_rewards`.
+#[event]
+pub struct RewardsFunded {
+    pub pool: Pubkey,
+    pub funder: Pubkey,
+    pub amount: u64,
+    pub reward_vault_balance: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted by `stake`.
+#[event]
+pub struct Staked {
+    pub pool: Pubkey,
+    pub position: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub lock_days: u16,
+    pub apr_bps: u16,
+    pub end_time: i64,
+    /// The reward reserved for the position over its whole lock.
+    pub total_reward: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted by `claim_rewards`.
+#[event]
+pub struct RewardsClaimed {
+    pub pool: Pubkey,
+    pub position: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    /// Cumulative reward paid to the position, including this claim.
+    pub claimed_reward: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted by `unstake`.
+#[event]
+pub struct Unstaked {
+    pub pool: Pubkey,
+    pub position: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    /// The unclaimed reward paid out with the stake.
+    pub reward: u64,
+    pub timestamp: i64,
+}
+
+// --- Errors ---
+
+#[error_code]
+pub enum StakingError {
+    #[msg("Unauthorized: Signer is not allowed to perform this action.")]
+    Unauthorized,
+    #[msg("Invalid mint: Token account or mint does not match the pool mint.")]
+    InvalidMint,
+    #[msg("Invalid pool: The position does not belong to this pool.")]
+    InvalidPool,
+    #[msg("Invalid payer: The account does not match the position's payer.")]
+    InvalidPayer,
+    #[msg("Invalid tiers: Tiers need strictly increasing lock durations and APRs between 1 and 10000 bps.")]
+    InvalidTiers,
+    #[msg("Invalid tier: No tier exists at this index.")]
+    InvalidTier,
+    #[msg("Invalid amount: The amount must be greater than zero.")]
+    InvalidAmount,
+    #[msg("Insufficient rewards: The reward vault cannot cover this position's reward.")]
+    InsufficientRewards,
+    #[msg("Still locked: The position's lock has not ended.")]
+    StillLocked,
+    #[msg("Nothing to claim: No rewards have accrued since the last claim.")]
+    NothingToClaim,
+    #[msg("Math overflow: An amount does not fit in 64 bits.")]
+    MathOverflow,
+    #[msg("Transfer fee not supported: Staking pools do not support Token-2022 transfer-fee mints.")]
+    TransferFeeNotSupported,
+}
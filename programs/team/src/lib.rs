@@ -1,79 +1,475 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::system_program;
 use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::{Mint, Token, TokenAccount, Transfer, transfer};
-use std::str::FromStr;
+use anchor_spl::token_interface::spl_token_2022::extension::transfer_fee::{self, TransferFeeConfig};
+use anchor_spl::token_interface::spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensions};
+use anchor_spl::token_interface::{
+    self, close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+use staking::program::Staking;
+use staking::StakePosition;
+
+pub mod merkle;
 
 // Program ID - This will be updated automatically by the build process.
 declare_id!("TEAM556Swko2ytgQZoZ314X6XN5erfx42u53sJj63ts");
 
-/// Pubkey of the admin wallet authorized to initialize vesting schedules.
-/// NOTE: Set this to the deployer or governance wallet for production.
-pub const ADMIN_PUBKEY: &str = "5hPhdLYWdC1zGiryjXafRPrgQ8FpR79q8259mQZXE6zQ";
-
 #[program]
 pub mod team {
     use super::*;
 
+    /// Creates the program configuration PDA holding the admin key and the treasury wallet.
+    ///
+    /// Only the program's upgrade authority can call this function, once.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing accounts required for initialization.
+    /// * `admin`: The wallet allowed to perform admin actions.
+    /// * `treasury`: The wallet receiving revoked tokens, presale proceeds and payment fees.
+    pub fn initialize_config(ctx: Context<InitializeConfig>, admin: Pubkey, treasury: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.admin = admin;
+        config.pending_admin = None;
+        config.guardian = Pubkey::default();
+        config.treasury = treasury;
+        config.paused = false;
+        config.bump = ctx.bumps.config;
+
+        msg!("Program config initialized with admin: {}, treasury: {}", admin, treasury);
+
+        Ok(())
+    }
+
+    /// Proposes a new admin. The rotation takes effect once the new admin calls `accept_admin`.
+    /// Proposing again replaces any pending proposal.
+    ///
+    /// Only the current config admin can call this function.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing the program config and the current admin.
+    /// * `new_admin`: The wallet (e.g. a multisig) that will become admin once it accepts.
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.pending_admin = Some(new_admin);
+
+        msg!("Proposed admin rotation from {} to {}", config.admin, new_admin);
+
+        Ok(())
+    }
+
+    /// Accepts a pending admin rotation, making the signer the config admin.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing the program config and the proposed admin.
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require!(
+            config.pending_admin == Some(ctx.accounts.new_admin.key()),
+            VestingError::InvalidPendingAdmin
+        );
+
+        let previous_admin = config.admin;
+        config.admin = ctx.accounts.new_admin.key();
+        config.pending_admin = None;
+
+        msg!("Admin rotated from {} to {}", previous_admin, config.admin);
+
+        Ok(())
+    }
+
+    /// Sets the guardian, who can pause the program but cannot unpause it or move funds.
+    /// Pass `Pubkey::default()` to remove the guardian.
+    ///
+    /// Only the current config admin can call this function.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing the program config and the admin.
+    /// * `guardian`: The wallet allowed to pause the program.
+    pub fn set_guardian(ctx: Context<SetGuardian>, guardian: Pubkey) -> Result<()> {
+        ctx.accounts.config.guardian = guardian;
+
+        msg!("Guardian set to: {}", guardian);
+
+        Ok(())
+    }
+
+    /// Sets the treasury wallet. Revocations, presale withdrawals and merchant payment fees
+    /// must pay into this wallet (or its token accounts) from then on.
+    ///
+    /// Only the current config admin can call this function.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing the program config and the admin.
+    /// * `treasury`: The new treasury wallet.
+    pub fn set_treasury(ctx: Context<SetTreasury>, treasury: Pubkey) -> Result<()> {
+        ctx.accounts.config.treasury = treasury;
+
+        msg!("Treasury set to: {}", treasury);
+
+        Ok(())
+    }
+
+    /// Pauses the program. While paused, every vesting instruction (initialization, claims,
+    /// distributions, revocations, transfers and closing) and template change fails with
+    /// `ProgramPaused`. Admin and multisig governance instructions keep working so the program
+    /// can be unpaused.
+    ///
+    /// Callable by the guardian or the config admin.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing the program config and the signer.
+    pub fn pause(ctx: Context<Pause>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require!(!config.paused, VestingError::ProgramPaused);
+        config.paused = true;
+
+        msg!("Program paused by: {}", ctx.accounts.signer.key());
+
+        Ok(())
+    }
+
+    /// Unpauses the program.
+    ///
+    /// Only the current config admin can call this function.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing the program config and the admin.
+    pub fn unpause(ctx: Context<Unpause>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        require!(config.paused, VestingError::ProgramNotPaused);
+        config.paused = false;
+
+        msg!("Program unpaused");
+
+        Ok(())
+    }
+
+    /// Creates an M-of-N multisig owned by this program.
+    ///
+    /// The multisig acts through its signer PDA (seeds: "multisig_signer", multisig), which can
+    /// be made the config admin via `propose_admin` / `accept_admin`. Admin actions are then
+    /// created as proposals, approved by members, and executed once the threshold is met.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing accounts required for creation.
+    /// * `members`: The wallets allowed to propose and approve. Must be unique.
+    /// * `threshold`: The number of approvals required to execute a proposal.
+    pub fn create_multisig(ctx: Context<CreateMultisig>, members: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        Multisig::validate_members(&members, threshold)?;
+
+        let multisig = &mut ctx.accounts.multisig;
+        multisig.creator = ctx.accounts.creator.key();
+        multisig.members = members;
+        multisig.threshold = threshold;
+        multisig.owner_set_seqno = 0;
+        multisig.proposal_count = 0;
+        multisig.bump = ctx.bumps.multisig;
+        multisig.signer_bump = ctx.bumps.multisig_signer;
+
+        msg!("Multisig created: {} members, threshold {}", multisig.members.len(), threshold);
+
+        Ok(())
+    }
+
+    /// Replaces the members and threshold of a multisig.
+    ///
+    /// Must be signed by the multisig's signer PDA, i.e. executed through an approved proposal.
+    /// Pending proposals created under the previous member set can no longer be executed.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing the multisig and its signer PDA.
+    /// * `members`: The new member wallets. Must be unique.
+    /// * `threshold`: The new number of approvals required to execute a proposal.
+    pub fn set_multisig_members(ctx: Context<SetMultisigMembers>, members: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        Multisig::validate_members(&members, threshold)?;
+
+        let multisig = &mut ctx.accounts.multisig;
+        multisig.members = members;
+        multisig.threshold = threshold;
+        multisig.owner_set_seqno = multisig.owner_set_seqno.wrapping_add(1);
+
+        msg!("Multisig members updated: {} members, threshold {}", multisig.members.len(), threshold);
+
+        Ok(())
+    }
+
+    /// Creates a proposal to execute an instruction signed by the multisig's signer PDA.
+    /// The proposer must be a member, and their approval is recorded.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing accounts required for creating the proposal.
+    /// * `program_id`: The program the instruction targets (e.g. this program for admin actions).
+    /// * `accounts`: The instruction's account metas.
+    /// * `data`: The instruction data.
+    pub fn create_proposal(
+        ctx: Context<CreateProposal>,
+        program_id: Pubkey,
+        accounts: Vec<ProposalAccount>,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        let member_index = multisig.member_index(&ctx.accounts.proposer.key())?;
+
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.multisig = multisig.key();
+        proposal.proposer = ctx.accounts.proposer.key();
+        proposal.index = multisig.proposal_count;
+        proposal.program_id = program_id;
+        proposal.accounts = accounts;
+        proposal.data = data;
+        proposal.approvals = vec![false; multisig.members.len()];
+        proposal.approvals[member_index] = true;
+        proposal.executed = false;
+        proposal.owner_set_seqno = multisig.owner_set_seqno;
+        proposal.bump = ctx.bumps.proposal;
+
+        multisig.proposal_count = multisig.proposal_count.checked_add(1).ok_or(VestingError::TooManyProposals)?;
+
+        msg!("Proposal #{} created by {}", proposal.index, proposal.proposer);
+
+        Ok(())
+    }
+
+    /// Records a member's approval of a pending proposal.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing the multisig, the proposal and the approving member.
+    pub fn approve_proposal(ctx: Context<ApproveProposal>) -> Result<()> {
+        let multisig = &ctx.accounts.multisig;
+        let proposal = &mut ctx.accounts.proposal;
+        require!(!proposal.executed, VestingError::ProposalAlreadyExecuted);
+        require!(proposal.owner_set_seqno == multisig.owner_set_seqno, VestingError::StaleProposal);
+
+        let member_index = multisig.member_index(&ctx.accounts.member.key())?;
+        proposal.approvals[member_index] = true;
+
+        msg!("Proposal #{} approved by {}", proposal.index, ctx.accounts.member.key());
+
+        Ok(())
+    }
+
+    /// Executes a proposal once it has reached the multisig's approval threshold.
+    ///
+    /// The proposal's instruction is invoked with the multisig's signer PDA as a signer.
+    /// All accounts of the instruction, including the target program, must be passed as
+    /// remaining accounts. Anyone may execute an approved proposal.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing the multisig, the proposal and the signer PDA.
+    pub fn execute_proposal<'info>(ctx: Context<'_, '_, '_, 'info, ExecuteProposal<'info>>) -> Result<()> {
+        let multisig = &ctx.accounts.multisig;
+        let proposal = &mut ctx.accounts.proposal;
+
+        // --- Validation ---
+        require!(!proposal.executed, VestingError::ProposalAlreadyExecuted);
+        require!(proposal.owner_set_seqno == multisig.owner_set_seqno, VestingError::StaleProposal);
+        let approvals = proposal.approvals.iter().filter(|a| **a).count();
+        require!(approvals >= multisig.threshold as usize, VestingError::NotEnoughApprovals);
+
+        // Mark executed and persist it before invoking, so the proposal cannot be re-entered.
+        proposal.executed = true;
+        proposal.exit(ctx.program_id)?;
+
+        // --- Invoke the Proposed Instruction ---
+        let instruction = Instruction {
+            program_id: proposal.program_id,
+            accounts: proposal.accounts.iter().map(AccountMeta::from).collect(),
+            data: proposal.data.clone(),
+        };
+        let mut account_infos = ctx.remaining_accounts.to_vec();
+        account_infos.push(ctx.accounts.multisig_signer.to_account_info());
+        let multisig_key = multisig.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"multisig_signer",
+            multisig_key.as_ref(),
+            &[multisig.signer_bump],
+        ]];
+        invoke_signed(&instruction, &account_infos, signer_seeds)?;
+
+        msg!("Proposal #{} executed", proposal.index);
+
+        Ok(())
+    }
+
+    /// Creates the global launch configuration PDA with the launch time not yet set.
+    ///
+    /// Vesting schedules are expressed as offsets from the launch time, so vesting accounts
+    /// can be initialized before the launch date is announced.
+    ///
+    /// Only the admin stored in the program config (`Config::admin`) can call this function.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing accounts required for initialization.
+    pub fn initialize_launch_config(ctx: Context<InitializeLaunchConfig>) -> Result<()> {
+        let launch_config = &mut ctx.accounts.launch_config;
+        launch_config.launch_time = 0;
+        launch_config.bump = ctx.bumps.launch_config;
+
+        msg!("Launch config initialized");
+
+        Ok(())
+    }
+
+    /// Sets the launch time that all vesting schedule offsets are resolved against.
+    ///
+    /// The launch time can only be set once; afterwards it is frozen.
+    ///
+    /// Only the admin stored in the program config (`Config::admin`) can call this function.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing the launch configuration account.
+    /// * `launch_time`: The Unix timestamp (seconds since epoch) of the token launch.
+    pub fn set_launch_time(ctx: Context<SetLaunchTime>, launch_time: u64) -> Result<()> {
+        let launch_config = &mut ctx.accounts.launch_config;
+        require!(launch_config.launch_time == 0, VestingError::LaunchTimeAlreadySet);
+        require!(launch_time > 0, VestingError::InvalidLaunchTime);
+
+        launch_config.launch_time = launch_time;
+
+        msg!("Launch time set to: {}", launch_time);
+
+        Ok(())
+    }
+
+    /// Creates a schedule template that `initialize_vesting` can reference by ID.
+    ///
+    /// A template fixes the kind of schedule entries and, for cliff schedules, the share and
+    /// week offset of each cliff. A template without tranches accepts any schedule of its kind
+    /// (up to `VestingAccount::MAX_SCHEDULES` entries). Templates cannot be edited once created;
+    /// retire a template and create a new one instead.
+    ///
+    /// Only the admin stored in the program config (`Config::admin`) can call this function.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing accounts required for creating the template.
+    /// * `template_id`: The template's ID, used in its PDA seeds. IDs below
+    ///                  `BUILTIN_TEMPLATES.len()` are reserved for the built-in templates.
+    /// * `name`: A human-readable name, e.g. "Advisor" (at most `ScheduleTemplate::MAX_NAME_LEN` bytes).
+    /// * `kind`: Whether schedules built from this template are cliffs or linear segments.
+    /// * `tranches`: The cliffs of the template (share in bps, week after launch); empty for free-form.
+    pub fn create_template(
+        ctx: Context<CreateTemplate>,
+        template_id: u32,
+        name: String,
+        kind: ScheduleKind,
+        tranches: Vec<TrancheTemplate>,
+    ) -> Result<()> {
+        require!(template_id as usize >= BUILTIN_TEMPLATES.len(), VestingError::InvalidTemplate);
+        ScheduleTemplate::validate_definition(&name, kind, &tranches)?;
+
+        let template = &mut ctx.accounts.template;
+        template.template_id = template_id;
+        template.name = name;
+        template.kind = kind;
+        template.tranches = tranches;
+        template.retired = false;
+        template.bump = ctx.bumps.template;
+
+        msg!("Schedule template #{} ({}) created", template_id, template.name);
+
+        Ok(())
+    }
+
+    /// Creates one of the built-in schedule templates (`BUILTIN_TEMPLATES`) at its fixed ID.
+    ///
+    /// These are the Dev, Marketing, Presale1, Presale2 and Contributor schedules that used
+    /// to be hard-coded in the program.
+    ///
+    /// Only the admin stored in the program config (`Config::admin`) can call this function.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing accounts required for creating the template.
+    /// * `template_id`: The built-in template's ID (its index in `BUILTIN_TEMPLATES`).
+    pub fn create_builtin_template(ctx: Context<CreateBuiltinTemplate>, template_id: u32) -> Result<()> {
+        let builtin = BUILTIN_TEMPLATES.get(template_id as usize).ok_or(VestingError::InvalidTemplate)?;
+
+        let template = &mut ctx.accounts.template;
+        template.template_id = template_id;
+        template.name = builtin.name.to_string();
+        template.kind = builtin.kind;
+        template.tranches = builtin.tranches.to_vec();
+        template.retired = false;
+        template.bump = ctx.bumps.template;
+
+        msg!("Built-in schedule template #{} ({}) created", template_id, template.name);
+
+        Ok(())
+    }
+
+    /// Retires a schedule template so no new vesting accounts can reference it.
+    ///
+    /// Existing vesting accounts created from the template are unaffected.
+    ///
+    /// Only the admin stored in the program config (`Config::admin`) can call this function.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing the admin and the template.
+    pub fn retire_template(ctx: Context<RetireTemplate>) -> Result<()> {
+        let template = &mut ctx.accounts.template;
+        require!(!template.retired, VestingError::TemplateRetired);
+        template.retired = true;
+
+        msg!("Schedule template #{} ({}) retired", template.template_id, template.name);
+
+        Ok(())
+    }
+
     /// Initializes a new vesting account for a beneficiary.
     ///
     /// This function creates a vesting schedule, initializes a Program Derived Address (PDA)
     /// to hold the vesting state and tokens, and transfers the total amount of tokens
     /// from the admin's account to the PDA's token account.
     ///
-    /// Only the designated admin wallet (`ADMIN_PUBKEY`) can call this function.
+    /// Only the admin stored in the program config (`Config::admin`) can call this function.
     ///
     /// Args:
     /// * `ctx`: Context containing accounts required for initialization.
-    /// * `wallet_type`: The type of wallet being initialized (e.g., Dev, Marketing),
-    ///                  which dictates the expected schedule length.
+    /// * `template_id`: The ID of the schedule template the schedule must follow (length, shares,
+    ///                  offsets); see `create_template` and `BUILTIN_TEMPLATES`.
     /// * `total_amount`: The total number of tokens to be vested according to the schedule.
-    /// * `schedule`: A vector defining the vesting cliffs (release time and amount).
+    /// * `schedule`: A vector defining the vesting cliffs or linear segments (offsets from launch and amount).
+    /// * `revocable`: Whether the admin may later revoke the vesting and claw back unvested tokens.
     pub fn initialize_vesting(
         ctx: Context<InitializeVesting>,
-        wallet_type: WalletType,
+        template_id: u32,
         total_amount: u64,
         schedule: Vec<VestingSchedule>,
+        revocable: bool,
     ) -> Result<()> {
         // --- Authorization ---
-        // Ensure the instruction is signed by the designated admin wallet.
-        require_keys_eq!(ctx.accounts.admin.key(), Pubkey::from_str(ADMIN_PUBKEY).unwrap(), VestingError::Unauthorized);
+        // Ensure the instruction is signed by the admin stored in the program config.
+        require_keys_eq!(ctx.accounts.admin.key(), ctx.accounts.config.admin, VestingError::Unauthorized);
 
         // --- Schedule Validation ---
-        // 1. Check if the number of schedule entries matches the expected length for the given wallet type.
-        match wallet_type {
-            WalletType::Dev => {
-                // 5% at 2w, 15% at 24w, 30% at 30w, 50% at 36w
-                // Example: Dev wallets expect a 4-part schedule
-                require!(schedule.len() == 4, VestingError::InvalidSchedule);
-            }
-            WalletType::Marketing => {
-                // 10% at 2w, 15% at 6w, 25% at 10w, 50% at 14w
-                // Example: Marketing wallets expect a 4-part schedule
-                require!(schedule.len() == 4, VestingError::InvalidSchedule);
-            }
-            WalletType::Presale1 => {
-                // 50% at 4w, 50% at 8w
-                // Example: Presale1 wallets expect a 2-part schedule
-                require!(schedule.len() == 2, VestingError::InvalidSchedule);
-            }
-            WalletType::Presale2 => {
-                // 100% at 12w
-                // Example: Presale2 wallets expect a 1-part schedule
-                require!(schedule.len() == 1, VestingError::InvalidSchedule);
-            }
+        // 1. Check if the number of schedule entries matches the template.
+        //    Free-form templates accept any non-empty schedule up to `MAX_SCHEDULES`.
+        let template = &ctx.accounts.template;
+        if template.tranches.is_empty() {
+            require!(
+                !schedule.is_empty() && schedule.len() <= VestingAccount::MAX_SCHEDULES,
+                VestingError::InvalidSchedule
+            );
+        } else {
+            require!(schedule.len() == template.tranches.len(), VestingError::InvalidSchedule);
         }
 
         // 2. Perform detailed validation on schedule entries.
+        let schedule_kind = template.kind;
         let mut sum = 0u64; // Accumulator for total amount in schedule entries
-        let mut last_time = 0u64; // Tracks the release time of the previous entry
+        let mut last_offset = 0u64; // Tracks the release offset of the previous entry
         for s in &schedule {
-            // Ensure release times are strictly increasing.
-            require!(s.release_time > last_time, VestingError::InvalidSchedule);
-            last_time = s.release_time;
+            // Ensure release offsets are strictly increasing (and the first is after launch).
+            require!(s.release_offset > last_offset, VestingError::InvalidSchedule);
+            last_offset = s.release_offset;
 
             // Ensure each schedule amount is positive (greater than zero).
             require!(s.amount > 0, VestingError::InvalidSchedule);
 
+            // Ensure the entry's shape (cliff or linear segment) is allowed by the template.
+            s.validate_shape(schedule_kind)?;
+
             // Add the amount to the sum, checking for potential overflow.
             sum = sum.checked_add(s.amount).ok_or(VestingError::InvalidSchedule)?;
         }
@@ -81,42 +477,181 @@ pub mod team {
         // 3. Ensure the sum of amounts in the schedule matches the provided `total_amount`.
         require!(sum == total_amount, VestingError::InvalidSchedule);
 
+        // 4. Ensure each entry's share and offset match the template's tranches (if any).
+        template.validate_schedule(total_amount, &schedule)?;
+
+        // --- Assign Grant Index ---
+        // Each new vesting account for the beneficiary takes the next index from their counter.
+        let counter = &mut ctx.accounts.beneficiary_counter;
+        let grant_index = counter.grant_count;
+        counter.beneficiary = ctx.accounts.beneficiary.key();
+        counter.grant_count = grant_index.checked_add(1).ok_or(VestingError::TooManyGrants)?;
+        counter.bump = ctx.bumps.beneficiary_counter;
+
         // --- Initialize Vesting Account PDA ---
         // Get a mutable reference to the vesting account being initialized.
         let vesting = &mut ctx.accounts.vesting_account;
         // Store the beneficiary's public key (who can claim tokens).
         vesting.authority = ctx.accounts.beneficiary.key();
+        // Store the beneficiary's public key used in the PDA seeds; it never changes.
+        vesting.original_beneficiary = ctx.accounts.beneficiary.key();
+        // No authority transfer is pending.
+        vesting.pending_authority = None;
         // Store the mint of the token being vested.
         vesting.mint = ctx.accounts.mint.key();
         // Store the total amount intended for vesting.
         vesting.total_amount = total_amount;
         // Initialize claimed amount to zero.
         vesting.claimed_amount = 0;
+        vesting.staked_amount = 0;
         // Store the vesting schedule.
         vesting.schedule = schedule;
-        // Store the wallet type.
-        vesting.wallet_type = wallet_type;
+        // Store the template the schedule was validated against.
+        vesting.template_id = template_id;
+        // Store the kind of schedule (cliffs or linear segments).
+        vesting.schedule_kind = schedule_kind;
+        // Store whether the vesting can be revoked, and mark it active.
+        vesting.revocable = revocable;
+        vesting.terminated = false;
+        // Store the grant index used in the PDA seeds.
+        vesting.grant_index = grant_index;
+        // Store who paid the rent, so it can be returned when the account is closed.
+        vesting.payer = ctx.accounts.admin.key();
         // Store the bump seed found by Anchor for the vesting_account PDA.
         vesting.bump = ctx.bumps.vesting_account; // Use the bump provided by Anchor context
 
         // --- Fund the Vesting Token Account ---
+        // Under a transfer-fee mint the admin sends enough to cover the fee, so the vesting
+        // token account ends up holding exactly `total_amount`.
+        let funding_amount = gross_amount_for(&ctx.accounts.mint.to_account_info(), total_amount)?;
         // Prepare the arguments for the token transfer CPI.
-        let transfer_instruction = Transfer {
+        let transfer_instruction = TransferChecked {
             from: ctx.accounts.admin_token_account.to_account_info(), // Source: Admin's token account
+            mint: ctx.accounts.mint.to_account_info(), // Mint: checked against both token accounts
             to: ctx.accounts.vesting_token_account.to_account_info(), // Destination: PDA's token account
             authority: ctx.accounts.admin.to_account_info(), // Authority: Admin signer
         };
         // Create the CPI context.
         let cpi_ctx = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(), // Target program: SPL Token Program
+            ctx.accounts.token_program.to_account_info(), // Target program: SPL Token or Token-2022
             transfer_instruction,                       // Instruction arguments
         );
         // Execute the token transfer.
-        transfer(cpi_ctx, total_amount)?;
+        transfer_checked(cpi_ctx, funding_amount, ctx.accounts.mint.decimals)?;
+        // Ensure the vesting token account received exactly the vested amount.
+        ctx.accounts.vesting_token_account.reload()?;
+        require!(
+            ctx.accounts.vesting_token_account.amount == total_amount,
+            VestingError::UnexpectedTransferFee
+        );
+
+        // Log success
+        msg!("Vesting account initialized for beneficiary: {} (grant #{})", vesting.authority, grant_index);
+        msg!("Total amount vested: {} (funded with {})", total_amount, funding_amount);
+        emit!(VestingInitialized {
+            vesting_account: vesting.key(),
+            beneficiary: vesting.authority,
+            mint: vesting.mint,
+            template_id,
+            grant_index,
+            total_amount,
+            revocable,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Adds tokens and future-dated tranches to an existing vesting account.
+    ///
+    /// The new entries are appended after the last existing entry: each must start once the
+    /// previous entry has ended, so `total_amount` still equals the sum of the schedule. They
+    /// follow the account's schedule kind (cliff or linear) but not the template's tranches,
+    /// which only govern the initial grant. The account is reallocated to fit the longer
+    /// schedule, with the admin paying the extra rent.
+    ///
+    /// Only the admin stored in the program config (`Config::admin`) can call this function.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing accounts required for the top-up.
+    /// * `tranches`: The entries to append (offsets from launch and amount); their amounts are
+    ///               transferred from the admin's token account.
+    pub fn top_up_vesting(ctx: Context<TopUpVesting>, tranches: Vec<VestingSchedule>) -> Result<()> {
+        let vesting = &mut ctx.accounts.vesting_account;
+
+        // --- Validation ---
+        require!(!vesting.terminated, VestingError::VestingTerminated);
+        require!(!tranches.is_empty(), VestingError::InvalidSchedule);
+        require!(
+            vesting.schedule.len() + tranches.len() <= VestingAccount::MAX_SCHEDULES,
+            VestingError::InvalidSchedule
+        );
+
+        // Tranches must start after both the end of the previous entry and the current time.
+        // Release offsets still strictly increase, so a cliff cannot share the previous
+        // cliff's offset. Before launch every offset lies in the future.
+        let now = Clock::get()?.unix_timestamp as u64;
+        let launch_time = ctx.accounts.launch_config.launch_time;
+        let (mut last_release, mut last_end) =
+            vesting.schedule.last().map_or((0, 0), |s| (s.release_offset, s.end_offset));
+        let mut top_up_amount = 0u64;
+        for s in &tranches {
+            require!(
+                s.release_offset > last_release && s.release_offset >= last_end,
+                VestingError::InvalidSchedule
+            );
+            last_release = s.release_offset;
+            last_end = s.end_offset;
+            require!(
+                launch_time == 0 || launch_time.saturating_add(s.release_offset) > now,
+                VestingError::TrancheNotInFuture
+            );
+            require!(s.amount > 0, VestingError::InvalidSchedule);
+            s.validate_shape(vesting.schedule_kind)?;
+            top_up_amount = top_up_amount.checked_add(s.amount).ok_or(VestingError::InvalidSchedule)?;
+        }
+        let total_amount = vesting.total_amount.checked_add(top_up_amount).ok_or(VestingError::InvalidSchedule)?;
+
+        // --- Update State ---
+        let tranche_count = tranches.len() as u32;
+        vesting.schedule.extend(tranches);
+        vesting.total_amount = total_amount;
+
+        // --- Fund the Vesting Token Account ---
+        // As in `initialize_vesting`, the admin covers any transfer fee.
+        let funding_amount = gross_amount_for(&ctx.accounts.mint.to_account_info(), top_up_amount)?;
+        let balance_before = ctx.accounts.vesting_token_account.amount;
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.admin_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.vesting_token_account.to_account_info(),
+            authority: ctx.accounts.admin.to_account_info(),
+        };
+        transfer_checked(
+            CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+            funding_amount,
+            ctx.accounts.mint.decimals,
+        )?;
+        ctx.accounts.vesting_token_account.reload()?;
+        require!(
+            ctx.accounts.vesting_token_account.amount.saturating_sub(balance_before) == top_up_amount,
+            VestingError::UnexpectedTransferFee
+        );
 
         // Log success
-        msg!("Vesting account initialized for beneficiary: {}", vesting.authority);
-        msg!("Total amount vested: {}", total_amount);
+        let vesting = &ctx.accounts.vesting_account;
+        msg!("Topped up vesting for beneficiary: {} with {} tokens in {} tranches", vesting.authority, top_up_amount, tranche_count);
+        msg!("Total amount vested: {}", vesting.total_amount);
+        emit!(VestingToppedUp {
+            vesting_account: vesting.key(),
+            beneficiary: vesting.authority,
+            mint: vesting.mint,
+            template_id: vesting.template_id,
+            amount: top_up_amount,
+            tranche_count,
+            total_amount: vesting.total_amount,
+            timestamp: now as i64,
+        });
 
         Ok(())
     }
@@ -130,266 +665,4499 @@ pub mod team {
     /// Args:
     /// * `ctx`: Context containing accounts required for claiming.
     pub fn claim_unlocked(ctx: Context<ClaimUnlocked>) -> Result<()> {
+        process_claim(ctx, None)
+    }
+
+    /// Allows the beneficiary (`authority`) to claim only part of the tokens that have
+    /// become unlocked, leaving the rest under the vesting PDA.
+    ///
+    /// Uses the same accounts and claimable-amount math as `claim_unlocked`.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing accounts required for claiming.
+    /// * `amount`: The number of tokens to claim; must not exceed the claimable amount.
+    pub fn claim_amount(ctx: Context<ClaimUnlocked>, amount: u64) -> Result<()> {
+        process_claim(ctx, Some(amount))
+    }
+
+    /// Allows the admin to push tokens that have become unlocked to the beneficiary's
+    /// own token account, without requiring the beneficiary to sign.
+    ///
+    /// The claimable amount is computed exactly as in `claim_unlocked`. Tokens can only
+    /// be sent to a token account owned by the beneficiary (`vesting_account.authority`).
+    ///
+    /// Args:
+    /// * `ctx`: Context containing accounts required for the distribution.
+    pub fn admin_distribute_unlocked(ctx: Context<AdminDistribute>) -> Result<()> {
         // Get a mutable reference to the vesting account state.
         let vesting = &mut ctx.accounts.vesting_account;
         // Get the current time from the Solana clock sysvar.
         let now = Clock::get()?.unix_timestamp as u64;
 
-        // --- Validation ---
-        // 1. Mint Consistency: Ensure token accounts belong to the correct mint.
-        require_keys_eq!(ctx.accounts.vesting_token_account.mint, vesting.mint, VestingError::InvalidMint);
-        require_keys_eq!(ctx.accounts.destination_token_account.mint, vesting.mint, VestingError::InvalidMint);
-
         // --- Calculate Claimable Amount ---
-        // Determine the total amount unlocked based on the current time and schedule.
-        let mut unlocked_amount = 0u64;
-        for s in &vesting.schedule {
-            if now >= s.release_time {
-                // Add amount if release time is in the past or present.
-                // Use saturating_add to prevent overflow (though unlikely with u64 amounts).
-                unlocked_amount = unlocked_amount.saturating_add(s.amount);
-            }
-        }
-
-        // Calculate the amount actually claimable (unlocked minus already claimed).
-        // Use saturating_sub to prevent underflow if claimed_amount somehow exceeds unlocked.
-        let claimable_amount = unlocked_amount.saturating_sub(vesting.claimed_amount);
+        // Mint and owner checks are enforced by the `AdminDistribute` account constraints.
+        require!(!vesting.terminated, VestingError::VestingTerminated);
+        let launch_time = ctx.accounts.launch_config.launch_time()?;
+        let claimable_amount = vesting.claimable_amount(now, launch_time);
         require!(claimable_amount > 0, VestingError::NothingToClaim);
 
         // --- Update State ---
-        // Increase the claimed amount in the vesting account state.
-        // Use saturating_add for safety.
         vesting.claimed_amount = vesting.claimed_amount.saturating_add(claimable_amount);
 
         // --- Transfer Tokens ---
-        // Prepare arguments for the token transfer CPI.
-        let cpi_accounts = Transfer {
-            from: ctx.accounts.vesting_token_account.to_account_info(), // Source: PDA's token account
-            to: ctx.accounts.destination_token_account.to_account_info(), // Destination: Beneficiary's token account
-            authority: ctx.accounts.vesting_signer.to_account_info(), // Authority: The vesting PDA itself
-        };
-        // Get the token program account info.
-        let cpi_program = ctx.accounts.token_program.to_account_info();
-        // Define the PDA signer seeds required for the transfer CPI.
-        // Must match the seeds used in InitializeVesting.
+        // Transfer from the PDA's token account to the beneficiary's token account, signed by the PDA.
+        let grant_index = vesting.grant_index.to_le_bytes();
         let signer_seeds: &[&[&[u8]]] = &[&[
-            b"vesting",                  // Constant seed prefix
-            vesting.authority.as_ref(), // Beneficiary's key used as seed
-            &[vesting.bump], // Bump seed stored in the vesting account
+            b"vesting",
+            vesting.original_beneficiary.as_ref(),
+            &grant_index,
+            &[vesting.bump],
         ]];
-        // Execute the token transfer CPI, signed by the PDA.
-        transfer(
-            CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds),
+        let received_amount = transfer_from_vesting(
+            &ctx.accounts.token_program,
+            &ctx.accounts.vesting_token_account,
+            &mut ctx.accounts.destination_token_account,
+            &ctx.accounts.mint,
+            ctx.accounts.vesting_signer.to_account_info(),
+            signer_seeds,
             claimable_amount,
         )?;
 
-        // --- Post-Transfer Validation (Security Checks) ---
-        // 1. Verify the provided `vesting_signer` account is indeed the correct PDA.
-        // Recreate the expected PDA address using the same seeds.
-        let expected_signer = Pubkey::create_program_address(
-            &[b"vesting", vesting.authority.as_ref(), &[vesting.bump]],
-            ctx.program_id, // Use the current program's ID
-        ).map_err(|_| VestingError::InvalidPda)?; // Handle potential error during PDA creation
-        // Compare the expected PDA with the provided account key.
-        require_keys_eq!(ctx.accounts.vesting_signer.key(), expected_signer, VestingError::InvalidPda);
-
-        // 2. Verify the `vesting_token_account` is owned by the `vesting_signer` PDA.
-        // This ensures we are transferring from the correct token account.
-        require_keys_eq!(ctx.accounts.vesting_token_account.owner, ctx.accounts.vesting_signer.key(), VestingError::InvalidOwner);
-
         // Log success
-        msg!("Claimed {} tokens for beneficiary: {}", claimable_amount, vesting.authority);
-        msg!("Remaining claimable based on current time: {}", unlocked_amount.saturating_sub(vesting.claimed_amount)); // Show what *might* be claimable now
+        msg!("Admin distributed {} tokens to beneficiary: {} ({} received)", claimable_amount, vesting.authority, received_amount);
+        emit!(AdminDistributed {
+            vesting_account: vesting.key(),
+            admin: ctx.accounts.admin.key(),
+            beneficiary: vesting.authority,
+            mint: vesting.mint,
+            template_id: vesting.template_id,
+            amount: claimable_amount,
+            received_amount,
+            claimed_amount: vesting.claimed_amount,
+            timestamp: now as i64,
+        });
 
         Ok(())
     }
-}
 
-/// Defines the accounts required for the `initialize_vesting` instruction.
-#[derive(Accounts)]
-#[instruction(wallet_type: WalletType, total_amount: u64, schedule: Vec<VestingSchedule>)] // Make args available for constraints if needed
-pub struct InitializeVesting<'info> {
-    /// The admin account, must be a signer and match `ADMIN_PUBKEY`. Pays for account creation.
-    #[account(mut, address = Pubkey::from_str(ADMIN_PUBKEY).unwrap() @ VestingError::Unauthorized)]
-    pub admin: Signer<'info>,
+    /// Revokes a revocable vesting account.
+    ///
+    /// Anything already unlocked but not yet claimed is paid out to the beneficiary, the
+    /// unvested remainder is returned to the treasury (`Config::treasury`), and the account
+    /// is marked terminated so further claims fail.
+    ///
+    /// Only the admin stored in the program config (`Config::admin`) can call this function.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing accounts required for the revocation.
+    pub fn revoke_vesting(ctx: Context<RevokeVesting>) -> Result<()> {
+        let vesting = &mut ctx.accounts.vesting_account;
+        let now = Clock::get()?.unix_timestamp as u64;
 
-    /// The admin's SPL token account, from which tokens will be transferred. Must be mutable.
-    #[account(mut)]
-    pub admin_token_account: Account<'info, TokenAccount>,
+        // --- Validation ---
+        require!(vesting.revocable, VestingError::NotRevocable);
+        require!(!vesting.terminated, VestingError::VestingTerminated);
 
-    /// The beneficiary account (wallet address). Used as a seed for the PDA. Mutable for potential future use.
-    /// CHECK: This account's signature is not required, and no data is read from it besides its key.
-    /// It is marked mutable because the `init` constraint on `vesting_account` requires the payer (`admin`)
-    /// to be mutable, and implicitly, other accounts involved might need to be.
-    #[account(mut)]
-    pub beneficiary: AccountInfo<'info>,
+        // --- Split Balance ---
+        // Before launch nothing has unlocked, so everything is returned to the treasury.
+        let launch_time = ctx.accounts.launch_config.launch_time;
+        let unlocked_amount = if launch_time > 0 { vesting.unlocked_amount(now, launch_time) } else { 0 };
+        let claimable_amount = if launch_time > 0 { vesting.claimable_amount(now, launch_time) } else { 0 };
+        let unvested_amount = vesting.total_amount.saturating_sub(unlocked_amount);
 
-    /// The vesting account PDA. Initialized by this instruction.
-    /// Stores the vesting schedule, beneficiary, mint, amounts, and bump seed.
-    /// Seeds: "vesting", beneficiary pubkey.
-    #[account(
-        init, // Marks this account for creation
-        payer = admin, // `admin` pays for the account creation rent
-        space = 8 + VestingAccount::MAX_SIZE, // 8 byte discriminator + struct size
-        seeds = [b"vesting", beneficiary.key().as_ref()], // PDA seeds
-        bump // Ask Anchor to find and store the bump seed
-    )]
-    pub vesting_account: Account<'info, VestingAccount>,
+        // --- Update State ---
+        vesting.claimed_amount = vesting.claimed_amount.saturating_add(claimable_amount);
+        vesting.terminated = true;
 
-    /// The Mint account of the SPL token being vested. Used for type safety and ATA initialization.
-    pub mint: Account<'info, Mint>,
+        // --- Transfer Tokens ---
+        let grant_index = vesting.grant_index.to_le_bytes();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"vesting",
+            vesting.original_beneficiary.as_ref(),
+            &grant_index,
+            &[vesting.bump],
+        ]];
+        // 1. Pay out what has already unlocked to the beneficiary.
+        let mut received_amount = 0;
+        if claimable_amount > 0 {
+            received_amount = transfer_from_vesting(
+                &ctx.accounts.token_program,
+                &ctx.accounts.vesting_token_account,
+                &mut ctx.accounts.destination_token_account,
+                &ctx.accounts.mint,
+                ctx.accounts.vesting_signer.to_account_info(),
+                signer_seeds,
+                claimable_amount,
+            )?;
+        }
+        // 2. Return the unvested remainder to the treasury.
+        let mut returned_received_amount = 0;
+        if unvested_amount > 0 {
+            returned_received_amount = transfer_from_vesting(
+                &ctx.accounts.token_program,
+                &ctx.accounts.vesting_token_account,
+                &mut ctx.accounts.treasury_token_account,
+                &ctx.accounts.mint,
+                ctx.accounts.vesting_signer.to_account_info(),
+                signer_seeds,
+                unvested_amount,
+            )?;
+        }
 
-    /// The SPL token account associated with the `vesting_account` PDA. Initialized by this instruction.
-    /// This account will hold the tokens being vested.
-    /// Authority is automatically set to the `vesting_account` PDA itself via `associated_token::authority`.
-    #[account(
-        init, // Marks this account for creation
+        // Log success
+        msg!("Revoked vesting for beneficiary: {}", vesting.authority);
+        msg!("Paid out {} unlocked tokens, returned {} unvested tokens to treasury", claimable_amount, unvested_amount);
+        emit!(VestingRevoked {
+            vesting_account: vesting.key(),
+            beneficiary: vesting.authority,
+            mint: vesting.mint,
+            template_id: vesting.template_id,
+            paid_amount: claimable_amount,
+            received_amount,
+            returned_amount: unvested_amount,
+            returned_received_amount,
+            claimed_amount: vesting.claimed_amount,
+            timestamp: now as i64,
+        });
+
+        Ok(())
+    }
+
+    /// Proposes transferring a vesting position to a new wallet.
+    ///
+    /// The transfer takes effect once the new wallet calls `accept_authority_transfer`.
+    /// Proposing again replaces any pending proposal.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing the vesting account and its current authority.
+    /// * `new_authority`: The wallet that will be allowed to claim once it accepts.
+    pub fn propose_authority_transfer(ctx: Context<ProposeAuthorityTransfer>, new_authority: Pubkey) -> Result<()> {
+        let vesting = &mut ctx.accounts.vesting_account;
+        require_keys_neq!(new_authority, vesting.authority, VestingError::InvalidAuthority);
+
+        vesting.pending_authority = Some(new_authority);
+
+        msg!("Proposed authority transfer from {} to {}", vesting.authority, new_authority);
+        emit!(AuthorityTransferProposed {
+            vesting_account: vesting.key(),
+            authority: vesting.authority,
+            new_authority,
+            mint: vesting.mint,
+            template_id: vesting.template_id,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Accepts a pending transfer of a vesting position, making the signer its authority.
+    ///
+    /// The vesting PDA keeps its address: its seeds use the original beneficiary,
+    /// not the current authority.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing the vesting account and the new authority.
+    pub fn accept_authority_transfer(ctx: Context<AcceptAuthorityTransfer>) -> Result<()> {
+        let vesting = &mut ctx.accounts.vesting_account;
+        require!(
+            vesting.pending_authority == Some(ctx.accounts.new_authority.key()),
+            VestingError::InvalidPendingAuthority
+        );
+
+        let previous_authority = vesting.authority;
+        vesting.authority = ctx.accounts.new_authority.key();
+        vesting.pending_authority = None;
+
+        msg!("Vesting authority transferred from {} to {}", previous_authority, vesting.authority);
+        emit!(AuthorityTransferred {
+            vesting_account: vesting.key(),
+            previous_authority,
+            authority: vesting.authority,
+            mint: vesting.mint,
+            template_id: vesting.template_id,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Closes a vesting account whose schedule is exhausted, together with its token account.
+    ///
+    /// The schedule is exhausted once everything has been claimed or the vesting has been
+    /// revoked, and the token account must be empty. The rent of both accounts is returned
+    /// to the original payer recorded at init.
+    ///
+    /// Callable by the config admin (`Config::admin`) or the beneficiary (`authority`).
+    ///
+    /// Args:
+    /// * `ctx`: Context containing accounts required for closing.
+    pub fn close_vesting(ctx: Context<CloseVesting>) -> Result<()> {
+        let vesting = &ctx.accounts.vesting_account;
+
+        // --- Validation ---
+        require!(
+            vesting.terminated || vesting.claimed_amount == vesting.total_amount,
+            VestingError::ScheduleNotExhausted
+        );
+        require!(ctx.accounts.vesting_token_account.amount == 0, VestingError::VestingTokenAccountNotEmpty);
+
+        // --- Close the Vesting Token Account ---
+        // Token-2022 refuses to close an account holding withheld transfer fees, so sweep
+        // them to the mint first. Harvesting is permissionless and needs no signer.
+        if has_transfer_fee(&ctx.accounts.mint.to_account_info())? {
+            let harvest_ix = transfer_fee::instruction::harvest_withheld_tokens_to_mint(
+                ctx.accounts.token_program.key,
+                &ctx.accounts.mint.key(),
+                &[&ctx.accounts.vesting_token_account.key()],
+            )?;
+            anchor_lang::solana_program::program::invoke(
+                &harvest_ix,
+                &[
+                    ctx.accounts.mint.to_account_info(),
+                    ctx.accounts.vesting_token_account.to_account_info(),
+                ],
+            )?;
+        }
+
+        // Signed by the vesting PDA, which owns the token account.
+        let grant_index = vesting.grant_index.to_le_bytes();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"vesting",
+            vesting.original_beneficiary.as_ref(),
+            &grant_index,
+            &[vesting.bump],
+        ]];
+        let cpi_accounts = CloseAccount {
+            account: ctx.accounts.vesting_token_account.to_account_info(),
+            destination: ctx.accounts.payer.to_account_info(),
+            authority: ctx.accounts.vesting_account.to_account_info(),
+        };
+        close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        ))?;
+
+        // The vesting account itself is closed by the `close = payer` constraint.
+        msg!("Closed vesting account for beneficiary: {}", vesting.authority);
+        emit!(VestingClosed {
+            vesting_account: vesting.key(),
+            beneficiary: vesting.authority,
+            mint: vesting.mint,
+            template_id: vesting.template_id,
+            claimed_amount: vesting.claimed_amount,
+            terminated: vesting.terminated,
+            closed_by: ctx.accounts.closer.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Creates the presale configuration PDA: the token being sold, the accepted stablecoin,
+    /// and each phase's batch size, prices and vesting template.
+    ///
+    /// Sold tokens come from the presale's token vault, a token account of `mint` owned by the
+    /// presale PDA, which the admin funds separately. Proceeds accumulate in the presale's SOL
+    /// vault PDA and stablecoin vault until `withdraw_presale_proceeds` sends them to the treasury.
+    ///
+    /// Only the admin stored in the program config (`Config::admin`) can call this function.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing accounts required for initialization.
+    /// * `stable_mint`: The stablecoin accepted as payment, or `Pubkey::default()` to accept SOL only.
+    /// * `primary`: Batch size, prices and schedule template of the primary phase.
+    /// * `secondary`: Batch size, prices and schedule template of the secondary phase.
+    pub fn initialize_presale(
+        ctx: Context<InitializePresale>,
+        stable_mint: Pubkey,
+        primary: PresalePhaseConfig,
+        secondary: PresalePhaseConfig,
+    ) -> Result<()> {
+        let presale = &mut ctx.accounts.presale;
+        presale.mint = ctx.accounts.mint.key();
+        presale.configure(stable_mint, primary, secondary)?;
+        presale.bump = ctx.bumps.presale;
+        presale.sol_vault_bump = ctx.bumps.sol_vault;
+
+        msg!("Presale initialized for mint: {}", presale.mint);
+        emit!(PresaleConfigured {
+            mint: presale.mint,
+            stable_mint,
+            primary,
+            secondary,
+            admin: ctx.accounts.admin.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Updates the accepted stablecoin and each phase's batch size, prices and vesting template.
+    ///
+    /// SOL prices are fixed in lamports, so the admin refreshes them as the SOL price moves.
+    ///
+    /// Only the admin stored in the program config (`Config::admin`) can call this function.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing the presale PDA.
+    /// * `stable_mint`: The stablecoin accepted as payment, or `Pubkey::default()` to accept SOL only.
+    /// * `primary`: Batch size, prices and schedule template of the primary phase.
+    /// * `secondary`: Batch size, prices and schedule template of the secondary phase.
+    pub fn update_presale(
+        ctx: Context<UpdatePresale>,
+        stable_mint: Pubkey,
+        primary: PresalePhaseConfig,
+        secondary: PresalePhaseConfig,
+    ) -> Result<()> {
+        let presale = &mut ctx.accounts.presale;
+        presale.configure(stable_mint, primary, secondary)?;
+
+        msg!("Presale updated for mint: {}", presale.mint);
+        emit!(PresaleConfigured {
+            mint: presale.mint,
+            stable_mint,
+            primary,
+            secondary,
+            admin: ctx.accounts.admin.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Opens the sale window of a presale phase, from `start_time` for the phase's `duration`.
+    ///
+    /// The secondary phase can only be opened once the primary phase has sold out or closed.
+    /// A phase can be opened once; its window cannot be moved afterwards, only closed early.
+    ///
+    /// Only the admin stored in the program config (`Config::admin`) can call this function.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing the presale PDA.
+    /// * `phase`: The presale phase to open.
+    /// * `start_time`: Unix timestamp the sale opens at; may be in the future.
+    pub fn open_presale_phase(ctx: Context<UpdatePresale>, phase: PresalePhase, start_time: i64) -> Result<()> {
+        let presale = &mut ctx.accounts.presale;
+        let now = Clock::get()?.unix_timestamp;
+
+        // --- Validation ---
+        require!(presale.state(phase).start_time == 0, VestingError::PresalePhaseAlreadyOpen);
+        require!(start_time > 0, VestingError::InvalidPresaleWindow);
+        if phase == PresalePhase::Secondary {
+            require!(
+                presale.sold_out(PresalePhase::Primary) || presale.primary_state.closed(now),
+                VestingError::PrimaryPresaleActive
+            );
+        }
+        let end_time = start_time
+            .checked_add(presale.phase(phase).duration)
+            .ok_or(VestingError::InvalidPresaleWindow)?;
+
+        // --- Update State ---
+        let state = presale.state_mut(phase);
+        state.start_time = start_time;
+        state.end_time = end_time;
+
+        msg!("Presale {:?} phase opens at {} and closes at {}", phase, start_time, end_time);
+        emit!(PresalePhaseOpened {
+            phase,
+            start_time,
+            end_time,
+            cap: presale.phase(phase).cap,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Closes the sale window of an open presale phase now, ahead of its scheduled end.
+    ///
+    /// Closing the primary phase allows the secondary phase to be opened.
+    ///
+    /// Only the admin stored in the program config (`Config::admin`) can call this function.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing the presale PDA.
+    /// * `phase`: The presale phase to close.
+    pub fn close_presale_phase(ctx: Context<UpdatePresale>, phase: PresalePhase) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let state = ctx.accounts.presale.state_mut(phase);
+
+        // --- Validation ---
+        require!(state.start_time > 0, VestingError::PresalePhaseNotOpen);
+        require!(!state.closed(now), VestingError::PresaleEnded);
+
+        // --- Update State ---
+        state.end_time = now;
+
+        msg!("Presale {:?} phase closed with {} tokens sold", phase, state.sold);
+        emit!(PresalePhaseClosed { phase, sold: state.sold, timestamp: now });
+
+        Ok(())
+    }
+
+    /// Buys one batch of a presale phase and opens the buyer's vesting account for it.
+    ///
+    /// The buyer pays the phase's price in SOL, or in the configured stablecoin when the
+    /// optional stablecoin accounts are passed. In the same instruction the batch's tokens move
+    /// from the presale's token vault into a new, non-revocable vesting account following the
+    /// phase's schedule template (Presale1 or Presale2). A purchase receipt PDA per wallet and
+    /// phase limits each wallet to one batch per phase: a second purchase fails because the
+    /// receipt already exists.
+    ///
+    /// The phase must be open (see `open_presale_phase`), and the batch must fit under the
+    /// phase's cap.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing accounts required for the purchase.
+    /// * `phase`: The presale phase to buy from.
+    pub fn buy_presale_batch(ctx: Context<BuyPresaleBatch>, phase: PresalePhase) -> Result<()> {
+        let phase_config = *ctx.accounts.presale.phase(phase);
+        let tokens = phase_config.tokens_per_batch;
+        let now = Clock::get()?.unix_timestamp;
+
+        // --- Sale Window and Cap ---
+        let state = ctx.accounts.presale.state_mut(phase);
+        require!(state.start_time > 0, VestingError::PresalePhaseNotOpen);
+        require!(now >= state.start_time, VestingError::PresaleNotStarted);
+        require!(now < state.end_time, VestingError::PresaleEnded);
+        let sold = state.sold.checked_add(tokens).ok_or(VestingError::PresaleCapReached)?;
+        require!(sold <= phase_config.cap, VestingError::PresaleCapReached);
+        state.sold = sold;
+
+        // --- Take Payment ---
+        // Either all stablecoin accounts are passed (stablecoin payment) or none (SOL payment).
+        let accounts = &ctx.accounts;
+        let (payment_mint, paid_amount) = match (
+            &accounts.stable_mint,
+            &accounts.buyer_stable_account,
+            &accounts.stable_vault,
+            &accounts.stable_token_program,
+        ) {
+            (Some(stable_mint), Some(buyer_stable_account), Some(stable_vault), Some(stable_token_program)) => {
+                require_keys_neq!(accounts.presale.stable_mint, Pubkey::default(), VestingError::StablePaymentsDisabled);
+                let cpi_accounts = TransferChecked {
+                    from: buyer_stable_account.to_account_info(),
+                    mint: stable_mint.to_account_info(),
+                    to: stable_vault.to_account_info(),
+                    authority: accounts.buyer.to_account_info(),
+                };
+                transfer_checked(
+                    CpiContext::new(stable_token_program.to_account_info(), cpi_accounts),
+                    phase_config.price_stable,
+                    stable_mint.decimals,
+                )?;
+                (stable_mint.key(), phase_config.price_stable)
+            }
+            (None, None, None, None) => {
+                let cpi_accounts = system_program::Transfer {
+                    from: accounts.buyer.to_account_info(),
+                    to: accounts.sol_vault.to_account_info(),
+                };
+                system_program::transfer(
+                    CpiContext::new(accounts.system_program.to_account_info(), cpi_accounts),
+                    phase_config.price_lamports,
+                )?;
+                (Pubkey::default(), phase_config.price_lamports)
+            }
+            _ => return err!(VestingError::InvalidPaymentAccounts),
+        };
+
+        // --- Record Purchase Receipt ---
+        let receipt = &mut ctx.accounts.receipt;
+        receipt.buyer = ctx.accounts.buyer.key();
+        receipt.phase = phase;
+        receipt.vesting_account = ctx.accounts.vesting_account.key();
+        receipt.payment_mint = payment_mint;
+        receipt.paid_amount = paid_amount;
+        receipt.tokens = tokens;
+        receipt.timestamp = now;
+        receipt.bump = ctx.bumps.receipt;
+
+        // --- Initialize Vesting Account PDA ---
+        // The schedule is built from the phase's template, so it always matches it.
+        let buyer = ctx.accounts.buyer.key();
+        let grant_index = ctx.accounts.beneficiary_counter.next_grant(buyer, ctx.bumps.beneficiary_counter)?;
+        ctx.accounts.vesting_account.init_from_template(
+            buyer,
+            ctx.accounts.mint.key(),
+            &ctx.accounts.template,
+            tokens,
+            grant_index,
+            ctx.bumps.vesting_account,
+        )?;
+
+        // --- Fund the Vesting Token Account ---
+        // Signed by the presale PDA, which owns the token vault. Under a transfer-fee mint the
+        // vault covers the fee, so the vesting token account holds exactly `tokens`.
+        let funding_amount = gross_amount_for(&ctx.accounts.mint.to_account_info(), tokens)?;
+        require!(ctx.accounts.token_vault.amount >= funding_amount, VestingError::PresaleInventoryExhausted);
+        let signer_seeds: &[&[&[u8]]] = &[&[b"presale", &[ctx.accounts.presale.bump]]];
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.token_vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.vesting_token_account.to_account_info(),
+            authority: ctx.accounts.presale.to_account_info(),
+        };
+        transfer_checked(
+            CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds),
+            funding_amount,
+            ctx.accounts.mint.decimals,
+        )?;
+        ctx.accounts.vesting_token_account.reload()?;
+        require!(ctx.accounts.vesting_token_account.amount == tokens, VestingError::UnexpectedTransferFee);
+
+        // Log success
+        let vesting = &ctx.accounts.vesting_account;
+        msg!("Presale batch bought by: {} ({:?} phase, grant #{})", vesting.authority, phase, grant_index);
+        msg!("Paid {} of {}, vesting {} tokens", paid_amount, payment_mint, tokens);
+        emit!(VestingInitialized {
+            vesting_account: vesting.key(),
+            beneficiary: vesting.authority,
+            mint: vesting.mint,
+            template_id: vesting.template_id,
+            grant_index,
+            total_amount: tokens,
+            revocable: false,
+            timestamp: now,
+        });
+        emit!(PresaleBatchPurchased {
+            buyer: vesting.authority,
+            phase,
+            vesting_account: vesting.key(),
+            mint: vesting.mint,
+            template_id: vesting.template_id,
+            tokens,
+            payment_mint,
+            paid_amount,
+            phase_sold: ctx.accounts.presale.state(phase).sold,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraws the presale proceeds to the treasury (`Config::treasury`): the whole SOL vault
+    /// and, when the optional stablecoin accounts are passed, the whole stablecoin vault.
+    ///
+    /// Only the admin stored in the program config (`Config::admin`) can call this function.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing the vaults and the treasury accounts.
+    pub fn withdraw_presale_proceeds(ctx: Context<WithdrawPresaleProceeds>) -> Result<()> {
+        // --- Withdraw SOL ---
+        // The SOL vault holds no data, so it can be emptied entirely.
+        let lamports = ctx.accounts.sol_vault.lamports();
+        if lamports > 0 {
+            let signer_seeds: &[&[&[u8]]] = &[&[b"presale_vault", &[ctx.accounts.presale.sol_vault_bump]]];
+            let cpi_accounts = system_program::Transfer {
+                from: ctx.accounts.sol_vault.to_account_info(),
+                to: ctx.accounts.treasury.to_account_info(),
+            };
+            system_program::transfer(
+                CpiContext::new_with_signer(ctx.accounts.system_program.to_account_info(), cpi_accounts, signer_seeds),
+                lamports,
+            )?;
+        }
+
+        // --- Withdraw Stablecoin ---
+        let accounts = &ctx.accounts;
+        let stable_amount = match (
+            &accounts.stable_mint,
+            &accounts.stable_vault,
+            &accounts.treasury_stable_account,
+            &accounts.stable_token_program,
+        ) {
+            (Some(stable_mint), Some(stable_vault), Some(treasury_stable_account), Some(stable_token_program)) => {
+                let amount = stable_vault.amount;
+                if amount > 0 {
+                    let signer_seeds: &[&[&[u8]]] = &[&[b"presale", &[accounts.presale.bump]]];
+                    let cpi_accounts = TransferChecked {
+                        from: stable_vault.to_account_info(),
+                        mint: stable_mint.to_account_info(),
+                        to: treasury_stable_account.to_account_info(),
+                        authority: accounts.presale.to_account_info(),
+                    };
+                    transfer_checked(
+                        CpiContext::new_with_signer(stable_token_program.to_account_info(), cpi_accounts, signer_seeds),
+                        amount,
+                        stable_mint.decimals,
+                    )?;
+                }
+                amount
+            }
+            (None, None, None, None) => 0,
+            _ => return err!(VestingError::InvalidPaymentAccounts),
+        };
+
+        msg!("Withdrew presale proceeds to treasury: {} lamports, {} stablecoin", lamports, stable_amount);
+        emit!(PresaleProceedsWithdrawn {
+            lamports,
+            stable_mint: accounts.presale.stable_mint,
+            stable_amount,
+            admin: accounts.admin.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Creates a Merkle distributor of presale allocations and funds its vault.
+    ///
+    /// Instead of one `initialize_vesting` per buyer, the admin publishes the root of a Merkle
+    /// tree of (wallet, amount, template) leaves, built off-chain by `tools/merkle`. Each wallet
+    /// then proves its leaf once, either to open its own vesting account
+    /// (`claim_distributor_vesting`) or, once its schedule has fully unlocked, to receive the
+    /// tokens directly (`claim_distributor_allocation`). A bitmap in the distributor records
+    /// claimed leaves. The vault is funded with `total_amount` from the admin's token account.
+    /// Transfer-fee mints are not supported.
+    ///
+    /// Only the admin stored in the program config (`Config::admin`) can call this function.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing accounts required for the distributor.
+    /// * `distributor_id`: The distributor's ID, used in its PDA seeds.
+    /// * `root`: The Merkle root of the allocation leaves (see `merkle::leaf_hash`).
+    /// * `num_leaves`: The number of leaves, which sizes the claim bitmap.
+    /// * `total_amount`: The sum of the leaves' amounts, transferred into the vault.
+    pub fn create_distributor(
+        ctx: Context<CreateDistributor>,
+        distributor_id: u32,
+        root: [u8; 32],
+        num_leaves: u32,
+        total_amount: u64,
+    ) -> Result<()> {
+        // --- Validation ---
+        require!(
+            num_leaves > 0 && num_leaves as usize <= Distributor::MAX_LEAVES && total_amount > 0,
+            VestingError::InvalidDistributor
+        );
+        require!(
+            !has_transfer_fee(&ctx.accounts.mint.to_account_info())?,
+            VestingError::TransferFeeNotSupported
+        );
+
+        // --- Initialize Distributor ---
+        let distributor = &mut ctx.accounts.distributor;
+        distributor.distributor_id = distributor_id;
+        distributor.mint = ctx.accounts.mint.key();
+        distributor.root = root;
+        distributor.num_leaves = num_leaves;
+        distributor.total_amount = total_amount;
+        distributor.claimed_amount = 0;
+        distributor.claimed_bitmap = vec![0; Distributor::bitmap_len(num_leaves)];
+        distributor.bump = ctx.bumps.distributor;
+
+        // --- Fund the Vault ---
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.admin_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.admin.to_account_info(),
+        };
+        transfer_checked(
+            CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+            total_amount,
+            ctx.accounts.mint.decimals,
+        )?;
+
+        msg!("Distributor #{} created with {} leaves and {} tokens", distributor_id, num_leaves, total_amount);
+        emit!(DistributorCreated {
+            distributor: distributor.key(),
+            distributor_id,
+            mint: distributor.mint,
+            root,
+            num_leaves,
+            total_amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Opens the claimant's vesting account for their distributor allocation.
+    ///
+    /// The claimant proves the leaf (index, claimant wallet, amount, template ID) against the
+    /// distributor's root. The leaf is marked claimed, and a new non-revocable vesting account
+    /// following the template's cliffs is created and funded from the distributor's vault.
+    /// The claimant pays the rent.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing accounts required for the claim.
+    /// * `index`: The leaf's index in the tree.
+    /// * `amount`: The leaf's token amount.
+    /// * `template_id`: The leaf's schedule template; must be a cliff template.
+    /// * `proof`: The sibling hashes from the leaf up to the root.
+    pub fn claim_distributor_vesting(
+        ctx: Context<ClaimDistributorVesting>,
+        index: u32,
+        amount: u64,
+        template_id: u32,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let claimant = ctx.accounts.claimant.key();
+
+        // --- Verify and Mark the Leaf ---
+        ctx.accounts.distributor.claim_leaf(index, &claimant, amount, template_id, &proof)?;
+
+        // --- Initialize Vesting Account PDA ---
+        let grant_index = ctx.accounts.beneficiary_counter.next_grant(claimant, ctx.bumps.beneficiary_counter)?;
+        ctx.accounts.vesting_account.init_from_template(
+            claimant,
+            ctx.accounts.mint.key(),
+            &ctx.accounts.template,
+            amount,
+            grant_index,
+            ctx.bumps.vesting_account,
+        )?;
+
+        // --- Fund the Vesting Token Account ---
+        // Signed by the distributor PDA, which owns the vault.
+        let distributor_id = ctx.accounts.distributor.distributor_id.to_le_bytes();
+        let signer_seeds: &[&[&[u8]]] = &[&[b"distributor", &distributor_id, &[ctx.accounts.distributor.bump]]];
+        let received_amount = transfer_from_vesting(
+            &ctx.accounts.token_program,
+            &ctx.accounts.vault,
+            &mut ctx.accounts.vesting_token_account,
+            &ctx.accounts.mint,
+            ctx.accounts.distributor.to_account_info(),
+            signer_seeds,
+            amount,
+        )?;
+
+        // Log success
+        let vesting = &ctx.accounts.vesting_account;
+        let now = Clock::get()?.unix_timestamp;
+        msg!("Distributor allocation #{} opened as vesting for: {} (grant #{})", index, claimant, grant_index);
+        emit!(VestingInitialized {
+            vesting_account: vesting.key(),
+            beneficiary: claimant,
+            mint: vesting.mint,
+            template_id,
+            grant_index,
+            total_amount: amount,
+            revocable: false,
+            timestamp: now,
+        });
+        emit!(DistributorClaimed {
+            distributor: ctx.accounts.distributor.key(),
+            index,
+            claimant,
+            amount,
+            template_id,
+            vesting_account: Some(vesting.key()),
+            received_amount,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Pays the claimant's distributor allocation directly from the vault, without opening a
+    /// vesting account.
+    ///
+    /// The leaf is proven and marked claimed as in `claim_distributor_vesting`. Since the
+    /// bitmap allows a single claim per leaf, the allocation's schedule (its template's cliffs
+    /// from launch) must have fully unlocked; until then use `claim_distributor_vesting`.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing accounts required for the claim.
+    /// * `index`: The leaf's index in the tree.
+    /// * `amount`: The leaf's token amount.
+    /// * `template_id`: The leaf's schedule template.
+    /// * `proof`: The sibling hashes from the leaf up to the root.
+    pub fn claim_distributor_allocation(
+        ctx: Context<ClaimDistributorAllocation>,
+        index: u32,
+        amount: u64,
+        template_id: u32,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let claimant = ctx.accounts.claimant.key();
+        let now = Clock::get()?.unix_timestamp;
+
+        // --- Validation ---
+        // The whole allocation must have unlocked under the template's schedule.
+        let launch_time = ctx.accounts.launch_config.launch_time()?;
+        let unlocked_amount = ctx
+            .accounts
+            .template
+            .build_schedule(amount)?
+            .iter()
+            .fold(0u64, |acc, s| acc.saturating_add(s.unlocked_amount(now as u64, launch_time)));
+        require!(unlocked_amount == amount, VestingError::AllocationStillVesting);
+
+        // --- Verify and Mark the Leaf ---
+        ctx.accounts.distributor.claim_leaf(index, &claimant, amount, template_id, &proof)?;
+
+        // --- Transfer Tokens ---
+        let distributor_id = ctx.accounts.distributor.distributor_id.to_le_bytes();
+        let signer_seeds: &[&[&[u8]]] = &[&[b"distributor", &distributor_id, &[ctx.accounts.distributor.bump]]];
+        let received_amount = transfer_from_vesting(
+            &ctx.accounts.token_program,
+            &ctx.accounts.vault,
+            &mut ctx.accounts.destination_token_account,
+            &ctx.accounts.mint,
+            ctx.accounts.distributor.to_account_info(),
+            signer_seeds,
+            amount,
+        )?;
+
+        msg!("Distributor allocation #{} of {} tokens claimed by: {}", index, amount, claimant);
+        emit!(DistributorClaimed {
+            distributor: ctx.accounts.distributor.key(),
+            index,
+            claimant,
+            amount,
+            template_id,
+            vesting_account: None,
+            received_amount,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Stakes unclaimed tokens of a vesting account in the staking program, without unlocking
+    /// them. The vesting PDA owns the staking position, and the schedule keeps running.
+    ///
+    /// The staked amount is recorded in `VestingAccount::staked_amount` and cannot be claimed
+    /// until `unstake_vesting` returns it. Revocable grants cannot be staked, so a revocation
+    /// never finds tokens locked in a position, and neither can grants of Token-2022
+    /// transfer-fee mints, whose stake would arrive short of the recorded amount.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing the vesting account, its beneficiary and the staking accounts.
+    /// * `position_id`: The position's ID among the vesting PDA's staking positions.
+    /// * `tier_index`: The index of the staking tier in `StakingPool::tiers`.
+    /// * `amount`: The number of tokens to stake.
+    pub fn stake_vesting(ctx: Context<StakeVesting>, position_id: u32, tier_index: u8, amount: u64) -> Result<()> {
+        let vesting = &ctx.accounts.vesting_account;
+
+        // --- Validation ---
+        require!(!vesting.terminated, VestingError::VestingTerminated);
+        require!(!vesting.revocable, VestingError::RevocableVestingNotStakeable);
+        require!(
+            !has_transfer_fee(&ctx.accounts.mint.to_account_info())?,
+            VestingError::TransferFeeNotSupported
+        );
+        require!(amount > 0 && amount <= vesting.available_amount(), VestingError::InvalidStakeAmount);
+
+        // --- Stake ---
+        // The vesting PDA signs as the position owner; the beneficiary pays the position's rent.
+        let grant_index = vesting.grant_index.to_le_bytes();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"vesting",
+            vesting.original_beneficiary.as_ref(),
+            &grant_index,
+            &[vesting.bump],
+        ]];
+        let cpi_accounts = staking::cpi::accounts::Stake {
+            payer: ctx.accounts.authority.to_account_info(),
+            owner: ctx.accounts.vesting_account.to_account_info(),
+            pool: ctx.accounts.pool.to_account_info(),
+            position: ctx.accounts.position.to_account_info(),
+            owner_token_account: ctx.accounts.vesting_token_account.to_account_info(),
+            stake_vault: ctx.accounts.stake_vault.to_account_info(),
+            reward_vault: ctx.accounts.reward_vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+        };
+        staking::cpi::stake(
+            CpiContext::new_with_signer(ctx.accounts.staking_program.to_account_info(), cpi_accounts, signer_seeds),
+            position_id,
+            tier_index,
+            amount,
+        )?;
+
+        // --- Update State ---
+        let vesting = &mut ctx.accounts.vesting_account;
+        vesting.staked_amount = vesting.staked_amount.checked_add(amount).ok_or(VestingError::InvalidStakeAmount)?;
+
+        msg!("Staked {} vesting tokens for beneficiary: {} ({} staked)", amount, vesting.authority, vesting.staked_amount);
+        emit!(VestingStaked {
+            vesting_account: vesting.key(),
+            beneficiary: vesting.authority,
+            position: ctx.accounts.position.key(),
+            amount,
+            staked_amount: vesting.staked_amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Claims the rewards a vesting account's staking position has accrued so far, paying
+    /// them to the beneficiary. Rewards are not subject to the vesting schedule.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing the vesting account, its beneficiary and the staking accounts.
+    pub fn claim_vesting_stake_rewards(ctx: Context<ClaimVestingStakeRewards>) -> Result<()> {
+        let vesting = &ctx.accounts.vesting_account;
+        let claimed_before = ctx.accounts.position.claimed_reward;
+
+        let grant_index = vesting.grant_index.to_le_bytes();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"vesting",
+            vesting.original_beneficiary.as_ref(),
+            &grant_index,
+            &[vesting.bump],
+        ]];
+        let cpi_accounts = staking::cpi::accounts::ClaimRewards {
+            owner: ctx.accounts.vesting_account.to_account_info(),
+            pool: ctx.accounts.pool.to_account_info(),
+            position: ctx.accounts.position.to_account_info(),
+            owner_token_account: ctx.accounts.destination_token_account.to_account_info(),
+            reward_vault: ctx.accounts.reward_vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+        };
+        staking::cpi::claim_rewards(CpiContext::new_with_signer(
+            ctx.accounts.staking_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        ))?;
+
+        ctx.accounts.position.reload()?;
+        let reward = ctx.accounts.position.claimed_reward.saturating_sub(claimed_before);
+        msg!("Claimed {} staking reward tokens for beneficiary: {}", reward, vesting.authority);
+        emit!(VestingStakeRewardsClaimed {
+            vesting_account: vesting.key(),
+            beneficiary: vesting.authority,
+            position: ctx.accounts.position.key(),
+            reward,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Closes a vesting account's staking position once its lock has ended. The stake returns
+    /// to the vesting token account, where it is claimable under the schedule again, and any
+    /// unclaimed reward is paid to the beneficiary.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing the vesting account, its beneficiary and the staking accounts.
+    pub fn unstake_vesting(ctx: Context<UnstakeVesting>) -> Result<()> {
+        let vesting = &ctx.accounts.vesting_account;
+        let amount = ctx.accounts.position.amount;
+        let reward = ctx.accounts.position.total_reward.saturating_sub(ctx.accounts.position.claimed_reward);
+
+        let grant_index = vesting.grant_index.to_le_bytes();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"vesting",
+            vesting.original_beneficiary.as_ref(),
+            &grant_index,
+            &[vesting.bump],
+        ]];
+        let cpi_accounts = staking::cpi::accounts::Unstake {
+            owner: ctx.accounts.vesting_account.to_account_info(),
+            payer: ctx.accounts.position_payer.to_account_info(),
+            pool: ctx.accounts.pool.to_account_info(),
+            position: ctx.accounts.position.to_account_info(),
+            owner_token_account: ctx.accounts.vesting_token_account.to_account_info(),
+            reward_token_account: ctx.accounts.destination_token_account.to_account_info(),
+            stake_vault: ctx.accounts.stake_vault.to_account_info(),
+            reward_vault: ctx.accounts.reward_vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+        };
+        staking::cpi::unstake(CpiContext::new_with_signer(
+            ctx.accounts.staking_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        ))?;
+
+        // --- Update State ---
+        let vesting = &mut ctx.accounts.vesting_account;
+        vesting.staked_amount = vesting.staked_amount.saturating_sub(amount);
+
+        msg!("Unstaked {} vesting tokens for beneficiary: {} ({} reward)", amount, vesting.authority, reward);
+        emit!(VestingUnstaked {
+            vesting_account: vesting.key(),
+            beneficiary: vesting.authority,
+            position: ctx.accounts.position.key(),
+            amount,
+            reward,
+            staked_amount: vesting.staked_amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Creates the staking reserve: a vault PDA holding the tokens set aside for staking
+    /// rewards, funded from the admin's token account, with a deadline nine months out.
+    ///
+    /// Before the deadline the admin can move the reserve into the staking reward pool
+    /// (`fund_staking_from_reserve`). Once it passes, anyone can release the reserve to the
+    /// community account recorded here (`release_staking_reserve`). Transfer-fee mints are not
+    /// supported.
+    ///
+    /// Only the admin stored in the program config (`Config::admin`) can call this function, once.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing accounts required for the reserve.
+    /// * `amount`: The number of tokens to set aside, e.g. 5% of the Marketing allocation.
+    pub fn initialize_staking_reserve(ctx: Context<InitializeStakingReserve>, amount: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+
+        // --- Validation ---
+        require!(amount > 0, VestingError::InvalidStakingReserve);
+        require!(
+            !has_transfer_fee(&ctx.accounts.mint.to_account_info())?,
+            VestingError::TransferFeeNotSupported
+        );
+
+        // --- Initialize Reserve ---
+        let reserve = &mut ctx.accounts.staking_reserve;
+        reserve.mint = ctx.accounts.mint.key();
+        reserve.amount = amount;
+        reserve.deadline = now.checked_add(StakingReserve::DEADLINE_PERIOD).ok_or(VestingError::InvalidStakingReserve)?;
+        reserve.release_account = ctx.accounts.release_account.key();
+        reserve.status = StakingReserveStatus::Funded;
+        reserve.bump = ctx.bumps.staking_reserve;
+
+        // --- Fund the Vault ---
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.admin_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.admin.to_account_info(),
+        };
+        transfer_checked(
+            CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+            amount,
+            ctx.accounts.mint.decimals,
+        )?;
+
+        msg!("Staking reserve funded with {} tokens, deadline {}", amount, reserve.deadline);
+        emit!(StakingReserveInitialized {
+            staking_reserve: reserve.key(),
+            mint: reserve.mint,
+            amount,
+            deadline: reserve.deadline,
+            release_account: reserve.release_account,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Moves the whole staking reserve into the staking program's reward vault. Only possible
+    /// before the reserve's deadline.
+    ///
+    /// Only the admin stored in the program config (`Config::admin`) can call this function.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing the reserve and the staking pool accounts.
+    pub fn fund_staking_from_reserve(ctx: Context<FundStakingFromReserve>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let reserve = &ctx.accounts.staking_reserve;
+
+        // --- Validation ---
+        require!(reserve.status == StakingReserveStatus::Funded, VestingError::StakingReserveSettled);
+        require!(now < reserve.deadline, VestingError::StakingReserveDeadlinePassed);
+        let amount = ctx.accounts.vault.amount;
+
+        // --- Fund Rewards ---
+        // The reserve PDA signs as the funder of the staking program's reward vault.
+        let signer_seeds: &[&[&[u8]]] = &[&[b"staking_reserve", &[reserve.bump]]];
+        let cpi_accounts = staking::cpi::accounts::FundRewards {
+            funder: ctx.accounts.staking_reserve.to_account_info(),
+            pool: ctx.accounts.pool.to_account_info(),
+            funder_token_account: ctx.accounts.vault.to_account_info(),
+            reward_vault: ctx.accounts.reward_vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+        };
+        staking::cpi::fund_rewards(
+            CpiContext::new_with_signer(ctx.accounts.staking_program.to_account_info(), cpi_accounts, signer_seeds),
+            amount,
+        )?;
+
+        // --- Update State ---
+        let reserve = &mut ctx.accounts.staking_reserve;
+        reserve.status = StakingReserveStatus::MovedToStaking;
+
+        msg!("Moved {} reserve tokens to the staking reward pool", amount);
+        emit!(StakingReserveSettled {
+            staking_reserve: reserve.key(),
+            status: reserve.status,
+            amount,
+            destination: ctx.accounts.reward_vault.key(),
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Releases the staking reserve to its community account once the deadline has passed
+    /// without the reserve being moved to staking.
+    ///
+    /// Anyone can call this function, so the reversion does not depend on the admin. It does
+    /// respect the emergency pause: while the program is paused it fails with `ProgramPaused`,
+    /// so the guardian can hold back the release for as long as the pause lasts. It can be
+    /// called again once the program is unpaused.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing the reserve, its vault and the community account.
+    pub fn release_staking_reserve(ctx: Context<ReleaseStakingReserve>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let reserve = &ctx.accounts.staking_reserve;
+
+        // --- Validation ---
+        require!(reserve.status == StakingReserveStatus::Funded, VestingError::StakingReserveSettled);
+        require!(now >= reserve.deadline, VestingError::StakingReserveDeadlineNotReached);
+        let amount = ctx.accounts.vault.amount;
+
+        // --- Transfer Tokens ---
+        let signer_seeds: &[&[&[u8]]] = &[&[b"staking_reserve", &[reserve.bump]]];
+        transfer_from_vesting(
+            &ctx.accounts.token_program,
+            &ctx.accounts.vault,
+            &mut ctx.accounts.release_account,
+            &ctx.accounts.mint,
+            ctx.accounts.staking_reserve.to_account_info(),
+            signer_seeds,
+            amount,
+        )?;
+
+        // --- Update State ---
+        let reserve = &mut ctx.accounts.staking_reserve;
+        reserve.status = StakingReserveStatus::Released;
+
+        msg!("Released {} reserve tokens to community account: {}", amount, reserve.release_account);
+        emit!(StakingReserveSettled {
+            staking_reserve: reserve.key(),
+            status: reserve.status,
+            amount,
+            destination: reserve.release_account,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Creates the merchant payment configuration PDA: the token accepted by `pay_merchant`,
+    /// the protocol fee tiers sent to the treasury (`Config::treasury`) on each payment, and the
+    /// KYC attestor allowed to verify merchants.
+    ///
+    /// Every fee tier starts at `fee_bps`; `update_payment_fee` sets tiers individually. Rejects
+    /// transfer-fee mints, under which the merchant and the treasury would receive less than the
+    /// split of the payment.
+    ///
+    /// Only the admin stored in the program config (`Config::admin`) can call this function, once.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing accounts required for initialization.
+    /// * `fee_bps`: The protocol fee in basis points, at most `PaymentConfig::MAX_FEE_BPS`.
+    /// * `attestor`: The wallet allowed to set merchants' KYC status.
+    pub fn initialize_payment_config(ctx: Context<InitializePaymentConfig>, fee_bps: u16, attestor: Pubkey) -> Result<()> {
+        require!(fee_bps <= PaymentConfig::MAX_FEE_BPS, VestingError::InvalidPaymentFee);
+        require!(
+            !has_transfer_fee(&ctx.accounts.mint.to_account_info())?,
+            VestingError::TransferFeeNotSupported
+        );
+
+        let payment_config = &mut ctx.accounts.payment_config;
+        payment_config.mint = ctx.accounts.mint.key();
+        payment_config.fee_tiers = [fee_bps; PaymentConfig::NUM_FEE_TIERS];
+        payment_config.attestor = attestor;
+        payment_config.bump = ctx.bumps.payment_config;
+
+        msg!("Payment config initialized for mint: {} with a fee of {} bps", payment_config.mint, fee_bps);
+        msg!("KYC attestor: {}", attestor);
+        for tier in 0..PaymentConfig::NUM_FEE_TIERS as u8 {
+            emit!(PaymentFeeUpdated { mint: payment_config.mint, tier, fee_bps, timestamp: Clock::get()?.unix_timestamp });
+        }
+        emit!(KycAttestorUpdated { attestor, timestamp: Clock::get()?.unix_timestamp });
+
+        Ok(())
+    }
+
+    /// Updates the protocol fee of one fee tier. Merchants on the tier pay the new fee from
+    /// their next payment on.
+    ///
+    /// Only the admin stored in the program config (`Config::admin`) can call this function.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing the payment config and the admin.
+    /// * `tier`: The fee tier, below `PaymentConfig::NUM_FEE_TIERS`.
+    /// * `fee_bps`: The protocol fee in basis points, at most `PaymentConfig::MAX_FEE_BPS`.
+    pub fn update_payment_fee(ctx: Context<UpdatePaymentConfig>, tier: u8, fee_bps: u16) -> Result<()> {
+        require!((tier as usize) < PaymentConfig::NUM_FEE_TIERS, VestingError::InvalidFeeTier);
+        require!(fee_bps <= PaymentConfig::MAX_FEE_BPS, VestingError::InvalidPaymentFee);
+
+        let payment_config = &mut ctx.accounts.payment_config;
+        payment_config.fee_tiers[tier as usize] = fee_bps;
+
+        msg!("Payment fee of tier {} updated to {} bps", tier, fee_bps);
+        emit!(PaymentFeeUpdated { mint: payment_config.mint, tier, fee_bps, timestamp: Clock::get()?.unix_timestamp });
+
+        Ok(())
+    }
+
+    /// Designates the wallet allowed to set merchants' KYC status.
+    ///
+    /// Only the admin stored in the program config (`Config::admin`) can call this function.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing the payment config and the admin.
+    /// * `attestor`: The new KYC attestor.
+    pub fn set_kyc_attestor(ctx: Context<UpdatePaymentConfig>, attestor: Pubkey) -> Result<()> {
+        ctx.accounts.payment_config.attestor = attestor;
+
+        msg!("KYC attestor set to: {}", attestor);
+        emit!(KycAttestorUpdated { attestor, timestamp: Clock::get()?.unix_timestamp });
+
+        Ok(())
+    }
+
+    /// Registers the signer as a merchant settling into a token account of the payment mint.
+    ///
+    /// The merchant starts unverified on fee tier 0, so it cannot receive `pay_merchant`
+    /// payments until the KYC attestor verifies it. Fails with `ProgramPaused` while the program
+    /// is paused.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing the merchant owner and the settlement token account.
+    /// * `name_hash`: The SHA-256 hash of the merchant's display name.
+    pub fn register_merchant(ctx: Context<RegisterMerchant>, name_hash: [u8; 32]) -> Result<()> {
+        let merchant = &mut ctx.accounts.merchant;
+        merchant.owner = ctx.accounts.owner.key();
+        merchant.settlement_account = ctx.accounts.settlement_account.key();
+        merchant.name_hash = name_hash;
+        merchant.fee_tier = 0;
+        merchant.kyc_status = KycStatus::Unverified;
+        merchant.suspended = false;
+        merchant.bump = ctx.bumps.merchant;
+
+        msg!("Merchant registered: {} settling to {}", merchant.owner, merchant.settlement_account);
+        emit!(MerchantRegistered {
+            merchant: merchant.key(),
+            owner: merchant.owner,
+            settlement_account: merchant.settlement_account,
+            name_hash,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Records the outcome of a merchant's KYC check.
+    ///
+    /// Only the KYC attestor stored in the payment config (`PaymentConfig::attestor`) can call
+    /// this function.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing the merchant and the attestor.
+    /// * `kyc_status`: The merchant's new KYC status.
+    pub fn set_merchant_kyc_status(ctx: Context<SetMerchantKycStatus>, kyc_status: KycStatus) -> Result<()> {
+        let merchant = &mut ctx.accounts.merchant;
+        merchant.kyc_status = kyc_status;
+
+        msg!("Merchant {} KYC status set to {:?}", merchant.owner, kyc_status);
+        emit!(MerchantKycUpdated {
+            merchant: merchant.key(),
+            owner: merchant.owner,
+            kyc_status,
+            attestor: ctx.accounts.attestor.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Moves a merchant to another fee tier.
+    ///
+    /// Only the admin stored in the program config (`Config::admin`) can call this function.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing the merchant and the admin.
+    /// * `fee_tier`: The fee tier, below `PaymentConfig::NUM_FEE_TIERS`.
+    pub fn set_merchant_fee_tier(ctx: Context<UpdateMerchant>, fee_tier: u8) -> Result<()> {
+        require!((fee_tier as usize) < PaymentConfig::NUM_FEE_TIERS, VestingError::InvalidFeeTier);
+
+        let merchant = &mut ctx.accounts.merchant;
+        merchant.fee_tier = fee_tier;
+
+        msg!("Merchant {} moved to fee tier {}", merchant.owner, fee_tier);
+        emit!(MerchantFeeTierUpdated {
+            merchant: merchant.key(),
+            owner: merchant.owner,
+            fee_tier,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Suspends a merchant: payments to it are rejected until it is reinstated. Its KYC
+    /// status is kept.
+    ///
+    /// Only the admin stored in the program config (`Config::admin`) can call this function.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing the merchant and the admin.
+    pub fn suspend_merchant(ctx: Context<UpdateMerchant>) -> Result<()> {
+        let merchant = &mut ctx.accounts.merchant;
+        require!(!merchant.suspended, VestingError::MerchantSuspended);
+        merchant.suspended = true;
+
+        msg!("Merchant suspended: {}", merchant.owner);
+        emit!(MerchantSuspensionUpdated {
+            merchant: merchant.key(),
+            owner: merchant.owner,
+            suspended: true,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Reinstates a suspended merchant.
+    ///
+    /// Only the admin stored in the program config (`Config::admin`) can call this function.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing the merchant and the admin.
+    pub fn reinstate_merchant(ctx: Context<UpdateMerchant>) -> Result<()> {
+        let merchant = &mut ctx.accounts.merchant;
+        require!(merchant.suspended, VestingError::MerchantNotSuspended);
+        merchant.suspended = false;
+
+        msg!("Merchant reinstated: {}", merchant.owner);
+        emit!(MerchantSuspensionUpdated {
+            merchant: merchant.key(),
+            owner: merchant.owner,
+            suspended: false,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Pays a registered merchant: `amount` tokens leave the buyer's token account, the
+    /// protocol fee (the merchant's fee tier in `PaymentConfig::fee_tiers`, applied to
+    /// `amount` and rounded down) goes to the treasury and the rest to the merchant's
+    /// settlement account. The merchant must be KYC-verified and not suspended.
+    ///
+    /// Compatible with Solana Pay transaction requests: the `reference` account is included in
+    /// the instruction so the payment can be found with `getSignaturesForAddress(reference)`,
+    /// and the `MerchantPaid` event carries the reference and order ID for verification.
+    ///
+    /// Args:
+    /// * `ctx`: Context containing the buyer, the merchant and the token accounts.
+    /// * `amount`: The number of tokens paid, including the protocol fee.
+    /// * `order_id`: The merchant's or POS's order ID (at most `PaymentConfig::MAX_ORDER_ID_LEN` bytes).
+    pub fn pay_merchant(ctx: Context<PayMerchant>, amount: u64, order_id: String) -> Result<()> {
+        // --- Validation ---
+        require!(amount > 0, VestingError::InvalidPaymentAmount);
+        require!(order_id.len() <= PaymentConfig::MAX_ORDER_ID_LEN, VestingError::OrderIdTooLong);
+        let merchant = &ctx.accounts.merchant;
+        require!(merchant.kyc_status == KycStatus::Verified, VestingError::MerchantNotVerified);
+        require!(!merchant.suspended, VestingError::MerchantSuspended);
+
+        // --- Split Payment ---
+        let fee = ctx.accounts.payment_config.fee_for(merchant.fee_tier, amount)?;
+        let merchant_amount = amount - fee;
+
+        // --- Transfer Tokens ---
+        let decimals = ctx.accounts.mint.decimals;
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.buyer_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.settlement_account.to_account_info(),
+            authority: ctx.accounts.buyer.to_account_info(),
+        };
+        transfer_checked(
+            CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+            merchant_amount,
+            decimals,
+        )?;
+        if fee > 0 {
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.buyer_token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.treasury_token_account.to_account_info(),
+                authority: ctx.accounts.buyer.to_account_info(),
+            };
+            transfer_checked(
+                CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+                fee,
+                decimals,
+            )?;
+        }
+
+        msg!("Paid {} tokens to merchant: {} ({} fee), order {}", amount, ctx.accounts.merchant.owner, fee, order_id);
+        emit!(MerchantPaid {
+            merchant: ctx.accounts.merchant.key(),
+            buyer: ctx.accounts.buyer.key(),
+            reference: ctx.accounts.reference.key(),
+            order_id,
+            amount,
+            fee,
+            merchant_amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
+
+/// Shared implementation of `claim_unlocked` and `claim_amount`.
+///
+/// Transfers `requested_amount` (or, if `None`, everything currently claimable) from the
+/// vesting PDA's token account to the beneficiary's destination token account.
+fn process_claim(ctx: Context<ClaimUnlocked>, requested_amount: Option<u64>) -> Result<()> {
+    // Get a mutable reference to the vesting account state.
+    let vesting = &mut ctx.accounts.vesting_account;
+    // Get the current time from the Solana clock sysvar.
+    let now = Clock::get()?.unix_timestamp as u64;
+
+    // --- Validation ---
+    // 1. Mint Consistency: Ensure token accounts belong to the correct mint.
+    require_keys_eq!(ctx.accounts.vesting_token_account.mint, vesting.mint, VestingError::InvalidMint);
+    require_keys_eq!(ctx.accounts.destination_token_account.mint, vesting.mint, VestingError::InvalidMint);
+
+    // 2. Termination: Revoked vesting accounts cannot be claimed from.
+    require!(!vesting.terminated, VestingError::VestingTerminated);
+
+    // 3. Launch: Schedules are relative to the launch time, which must be announced.
+    let launch_time = ctx.accounts.launch_config.launch_time()?;
+
+    // --- Calculate Claimable Amount ---
+    // Determine the amount unlocked based on the current time and schedule, minus what was already claimed.
+    let claimable_amount = vesting.claimable_amount(now, launch_time);
+    require!(claimable_amount > 0, VestingError::NothingToClaim);
+
+    // Claim everything claimable, or only the requested part of it.
+    let claim_amount = match requested_amount {
+        Some(amount) => {
+            require!(amount > 0, VestingError::InvalidClaimAmount);
+            require!(amount <= claimable_amount, VestingError::ClaimAmountExceedsClaimable);
+            amount
+        }
+        None => claimable_amount,
+    };
+
+    // --- Update State ---
+    // Increase the claimed amount in the vesting account state.
+    // Use saturating_add for safety.
+    vesting.claimed_amount = vesting.claimed_amount.saturating_add(claim_amount);
+
+    // --- Transfer Tokens ---
+    // Define the PDA signer seeds required for the transfer CPI.
+    // Must match the seeds used in InitializeVesting.
+    let grant_index = vesting.grant_index.to_le_bytes();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"vesting",                  // Constant seed prefix
+        vesting.original_beneficiary.as_ref(), // Original beneficiary's key used as seed
+        &grant_index, // Beneficiary's grant index used as seed
+        &[vesting.bump], // Bump seed stored in the vesting account
+    ]];
+    // Execute the token transfer CPI, signed by the PDA.
+    // Under a transfer-fee mint the beneficiary receives less than `claim_amount`.
+    let received_amount = transfer_from_vesting(
+        &ctx.accounts.token_program,
+        &ctx.accounts.vesting_token_account,
+        &mut ctx.accounts.destination_token_account,
+        &ctx.accounts.mint,
+        ctx.accounts.vesting_signer.to_account_info(),
+        signer_seeds,
+        claim_amount,
+    )?;
+
+    // --- Post-Transfer Validation (Security Checks) ---
+    // 1. Verify the provided `vesting_signer` account is indeed the correct PDA.
+    // Recreate the expected PDA address using the same seeds.
+    let expected_signer = Pubkey::create_program_address(
+        &[b"vesting", vesting.original_beneficiary.as_ref(), &grant_index, &[vesting.bump]],
+        ctx.program_id, // Use the current program's ID
+    ).map_err(|_| VestingError::InvalidPda)?; // Handle potential error during PDA creation
+    // Compare the expected PDA with the provided account key.
+    require_keys_eq!(ctx.accounts.vesting_signer.key(), expected_signer, VestingError::InvalidPda);
+
+    // 2. Verify the `vesting_token_account` is owned by the `vesting_signer` PDA.
+    // This ensures we are transferring from the correct token account.
+    require_keys_eq!(ctx.accounts.vesting_token_account.owner, ctx.accounts.vesting_signer.key(), VestingError::InvalidOwner);
+
+    // Log success
+    msg!("Claimed {} tokens for beneficiary: {} ({} received)", claim_amount, vesting.authority, received_amount);
+    msg!("Remaining claimable based on current time: {}", claimable_amount - claim_amount); // Show what *might* be claimable now
+    emit!(TokensClaimed {
+        vesting_account: vesting.key(),
+        beneficiary: vesting.authority,
+        mint: vesting.mint,
+        template_id: vesting.template_id,
+        amount: claim_amount,
+        received_amount,
+        claimed_amount: vesting.claimed_amount,
+        timestamp: now as i64,
+    });
+
+    Ok(())
+}
+
+/// Transfers `amount` tokens out of a PDA-owned token account (a vesting token account or
+/// a distributor vault) with `transfer_checked`, signed by the owning PDA.
+///
+/// Returns the amount actually credited to `to`. This equals `amount` unless the mint is a
+/// Token-2022 mint with the transfer-fee extension, in which case the fee is withheld.
+fn transfer_from_vesting<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    from: &InterfaceAccount<'info, TokenAccount>,
+    to: &mut InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    authority: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    amount: u64,
+) -> Result<u64> {
+    let balance_before = to.amount;
+    let cpi_accounts = TransferChecked {
+        from: from.to_account_info(),
+        mint: mint.to_account_info(),
+        to: to.to_account_info(),
+        authority,
+    };
+    transfer_checked(
+        CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer_seeds),
+        amount,
+        mint.decimals,
+    )?;
+    to.reload()?;
+    Ok(to.amount.saturating_sub(balance_before))
+}
+
+/// Returns whether the mint is a Token-2022 mint with the transfer-fee extension.
+fn has_transfer_fee(mint: &AccountInfo) -> Result<bool> {
+    if *mint.owner != token_interface::spl_token_2022::ID {
+        return Ok(false);
+    }
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<token_interface::spl_token_2022::state::Mint>::unpack(&data)?;
+    Ok(state.get_extension::<TransferFeeConfig>().is_ok())
+}
+
+/// Returns the amount to transfer so that exactly `net_amount` arrives, given the mint's
+/// transfer fee for the current epoch. Mints without a transfer fee return `net_amount`.
+fn gross_amount_for(mint: &AccountInfo, net_amount: u64) -> Result<u64> {
+    if !has_transfer_fee(mint)? {
+        return Ok(net_amount);
+    }
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<token_interface::spl_token_2022::state::Mint>::unpack(&data)?;
+    let fee_config = state.get_extension::<TransferFeeConfig>()?;
+    let epoch = Clock::get()?.epoch;
+    let net_of = |gross: u64| -> Result<u64> {
+        let fee = fee_config
+            .calculate_epoch_fee(epoch, gross)
+            .ok_or(VestingError::UnexpectedTransferFee)?;
+        Ok(gross.saturating_sub(fee))
+    };
+
+    // The inverse fee can be off by one due to rounding; step to the smallest gross amount
+    // whose net is exactly `net_amount`. The net grows by at most one per unit of gross.
+    let inverse_fee = fee_config
+        .calculate_inverse_epoch_fee(epoch, net_amount)
+        .ok_or(VestingError::UnexpectedTransferFee)?;
+    let mut gross = net_amount.checked_add(inverse_fee).ok_or(VestingError::UnexpectedTransferFee)?;
+    while gross > net_amount && net_of(gross - 1)? >= net_amount {
+        gross -= 1;
+    }
+    while net_of(gross)? < net_amount {
+        gross = gross.checked_add(1).ok_or(VestingError::UnexpectedTransferFee)?;
+    }
+    Ok(gross)
+}
+
+/// Defines the accounts required for the `initialize_config` instruction.
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    /// The program's upgrade authority, must be a signer. Pays for account creation.
+    #[account(mut)]
+    pub upgrade_authority: Signer<'info>,
+
+    /// The program config PDA. Initialized by this instruction.
+    /// Seeds: "config".
+    #[account(
+        init,
+        payer = upgrade_authority,
+        space = 8 + Config::SIZE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// This program, used to look up its program data account.
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()) @ VestingError::Unauthorized)]
+    pub program: Program<'info, crate::program::Team>,
+
+    /// The program data account, holding the upgrade authority.
+    #[account(constraint = program_data.upgrade_authority_address == Some(upgrade_authority.key()) @ VestingError::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+
+    /// The Solana System Program, required for creating accounts (`init`).
+    pub system_program: Program<'info, System>,
+}
+
+/// Defines the accounts required for the `propose_admin` instruction.
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    /// The current admin, must be a signer and match `config.admin`.
+    pub admin: Signer<'info>,
+
+    /// The program config PDA. Mutable because the pending admin is set.
+    #[account(mut, seeds = [b"config"], bump = config.bump, has_one = admin @ VestingError::Unauthorized)]
+    pub config: Account<'info, Config>,
+}
+
+/// Defines the accounts required for the `accept_admin` instruction.
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    /// The proposed new admin, must be a signer to accept the rotation.
+    pub new_admin: Signer<'info>,
+
+    /// The program config PDA. Mutable because the admin is replaced.
+    #[account(mut, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
+
+/// Defines the accounts required for the `set_guardian` instruction.
+#[derive(Accounts)]
+pub struct SetGuardian<'info> {
+    /// The current admin, must be a signer and match `config.admin`.
+    pub admin: Signer<'info>,
+
+    /// The program config PDA. Mutable because the guardian is set.
+    #[account(mut, seeds = [b"config"], bump = config.bump, has_one = admin @ VestingError::Unauthorized)]
+    pub config: Account<'info, Config>,
+}
+
+/// Defines the accounts required for the `set_treasury` instruction.
+#[derive(Accounts)]
+pub struct SetTreasury<'info> {
+    /// The current admin, must be a signer and match `config.admin`.
+    pub admin: Signer<'info>,
+
+    /// The program config PDA. Mutable because the treasury is set.
+    #[account(mut, seeds = [b"config"], bump = config.bump, has_one = admin @ VestingError::Unauthorized)]
+    pub config: Account<'info, Config>,
+}
+
+/// Defines the accounts required for the `pause` instruction.
+#[derive(Accounts)]
+pub struct Pause<'info> {
+    /// The guardian or the admin, must be a signer.
+    #[account(constraint = signer.key() == config.guardian || signer.key() == config.admin @ VestingError::Unauthorized)]
+    pub signer: Signer<'info>,
+
+    /// The program config PDA. Mutable because the paused flag is set.
+    #[account(mut, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
+
+/// Defines the accounts required for the `unpause` instruction.
+#[derive(Accounts)]
+pub struct Unpause<'info> {
+    /// The current admin, must be a signer and match `config.admin`.
+    pub admin: Signer<'info>,
+
+    /// The program config PDA. Mutable because the paused flag is cleared.
+    #[account(mut, seeds = [b"config"], bump = config.bump, has_one = admin @ VestingError::Unauthorized)]
+    pub config: Account<'info, Config>,
+}
+
+/// Defines the accounts required for the `create_multisig` instruction.
+#[derive(Accounts)]
+pub struct CreateMultisig<'info> {
+    /// The wallet creating the multisig. Used as a seed for the PDA. Pays for account creation.
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// The multisig PDA. Initialized by this instruction, sized for `Multisig::MAX_MEMBERS`.
+    /// Seeds: "multisig", creator pubkey.
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + Multisig::SIZE,
+        seeds = [b"multisig", creator.key().as_ref()],
+        bump
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    /// The multisig's signer PDA, which signs executed proposals. Holds no data.
+    /// CHECK: Only its address (derived from the seeds) and bump are used.
+    #[account(seeds = [b"multisig_signer", multisig.key().as_ref()], bump)]
+    pub multisig_signer: UncheckedAccount<'info>,
+
+    /// The Solana System Program, required for creating accounts (`init`).
+    pub system_program: Program<'info, System>,
+}
+
+/// Defines the accounts required for the `set_multisig_members` instruction.
+#[derive(Accounts)]
+pub struct SetMultisigMembers<'info> {
+    /// The multisig being updated.
+    #[account(mut)]
+    pub multisig: Account<'info, Multisig>,
+
+    /// The multisig's signer PDA, must sign (through `execute_proposal`).
+    #[account(seeds = [b"multisig_signer", multisig.key().as_ref()], bump = multisig.signer_bump)]
+    pub multisig_signer: Signer<'info>,
+}
+
+/// Defines the accounts required for the `create_proposal` instruction.
+#[derive(Accounts)]
+#[instruction(program_id: Pubkey, accounts: Vec<ProposalAccount>, data: Vec<u8>)]
+pub struct CreateProposal<'info> {
+    /// The multisig the proposal belongs to. Mutable because the proposal counter is incremented.
+    #[account(mut)]
+    pub multisig: Account<'info, Multisig>,
+
+    /// The proposal PDA. Initialized by this instruction, sized for its instruction.
+    /// Seeds: "proposal", multisig pubkey, proposal index (u64, little endian).
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + Proposal::space(accounts.len(), data.len(), multisig.members.len()),
+        seeds = [b"proposal", multisig.key().as_ref(), multisig.proposal_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    /// The proposing member, must be a signer. Pays for account creation.
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    /// The Solana System Program, required for creating accounts (`init`).
+    pub system_program: Program<'info, System>,
+}
+
+/// Defines the accounts required for the `approve_proposal` instruction.
+#[derive(Accounts)]
+pub struct ApproveProposal<'info> {
+    /// The multisig the proposal belongs to.
+    pub multisig: Account<'info, Multisig>,
+
+    /// The proposal being approved. Mutable because the approval is recorded.
+    #[account(mut, has_one = multisig @ VestingError::InvalidProposal)]
+    pub proposal: Account<'info, Proposal>,
+
+    /// The approving member, must be a signer.
+    pub member: Signer<'info>,
+}
+
+/// Defines the accounts required for the `execute_proposal` instruction.
+/// The proposed instruction's accounts (and target program) follow as remaining accounts.
+#[derive(Accounts)]
+pub struct ExecuteProposal<'info> {
+    /// The multisig the proposal belongs to.
+    pub multisig: Account<'info, Multisig>,
+
+    /// The proposal being executed. Mutable because it is marked executed.
+    #[account(mut, has_one = multisig @ VestingError::InvalidProposal)]
+    pub proposal: Account<'info, Proposal>,
+
+    /// The multisig's signer PDA, signing the proposed instruction.
+    /// CHECK: Only its address (derived from the seeds) is used, as a signer of the CPI.
+    #[account(mut, seeds = [b"multisig_signer", multisig.key().as_ref()], bump = multisig.signer_bump)]
+    pub multisig_signer: UncheckedAccount<'info>,
+}
+
+/// Defines the accounts required for the `initialize_launch_config` instruction.
+#[derive(Accounts)]
+pub struct InitializeLaunchConfig<'info> {
+    /// The admin account, must be a signer and match `config.admin`. Pays for account creation.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// The program config PDA holding the admin key. The program must not be paused.
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ VestingError::Unauthorized,
+        constraint = !config.paused @ VestingError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    /// The global launch configuration PDA. Initialized by this instruction.
+    /// Seeds: "launch_config".
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + LaunchConfig::SIZE,
+        seeds = [b"launch_config"],
+        bump
+    )]
+    pub launch_config: Account<'info, LaunchConfig>,
+
+    /// The Solana System Program, required for creating accounts (`init`).
+    pub system_program: Program<'info, System>,
+}
+
+/// Defines the accounts required for the `set_launch_time` instruction.
+#[derive(Accounts)]
+pub struct SetLaunchTime<'info> {
+    /// The admin account, must be a signer and match `config.admin`.
+    pub admin: Signer<'info>,
+
+    /// The program config PDA holding the admin key. The program must not be paused.
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ VestingError::Unauthorized,
+        constraint = !config.paused @ VestingError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    /// The global launch configuration PDA. Mutable because the launch time is set.
+    #[account(mut, seeds = [b"launch_config"], bump = launch_config.bump)]
+    pub launch_config: Account<'info, LaunchConfig>,
+}
+
+/// Defines the accounts required for the `create_template` instruction.
+#[derive(Accounts)]
+#[instruction(template_id: u32, name: String, kind: ScheduleKind, tranches: Vec<TrancheTemplate>)]
+pub struct CreateTemplate<'info> {
+    /// The admin account, must be a signer and match `config.admin`. Pays for account creation.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// The program config PDA holding the admin key. The program must not be paused.
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ VestingError::Unauthorized,
+        constraint = !config.paused @ VestingError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    /// The template PDA. Initialized by this instruction.
+    /// Seeds: "template", template ID (u32, little endian).
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + ScheduleTemplate::space(tranches.len()),
+        seeds = [b"template", template_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub template: Account<'info, ScheduleTemplate>,
+
+    /// The Solana System Program, required for creating the template account.
+    pub system_program: Program<'info, System>,
+}
+
+/// Defines the accounts required for the `create_builtin_template` instruction.
+#[derive(Accounts)]
+#[instruction(template_id: u32)]
+pub struct CreateBuiltinTemplate<'info> {
+    /// The admin account, must be a signer and match `config.admin`. Pays for account creation.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// The program config PDA holding the admin key. The program must not be paused.
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ VestingError::Unauthorized,
+        constraint = !config.paused @ VestingError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    /// The template PDA. Initialized by this instruction.
+    /// Seeds: "template", template ID (u32, little endian).
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + ScheduleTemplate::space(
+            BUILTIN_TEMPLATES.get(template_id as usize).map_or(0, |t| t.tranches.len())
+        ),
+        seeds = [b"template", template_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub template: Account<'info, ScheduleTemplate>,
+
+    /// The Solana System Program, required for creating the template account.
+    pub system_program: Program<'info, System>,
+}
+
+/// Defines the accounts required for the `retire_template` instruction.
+#[derive(Accounts)]
+pub struct RetireTemplate<'info> {
+    /// The admin account, must be a signer and match `config.admin`.
+    pub admin: Signer<'info>,
+
+    /// The program config PDA holding the admin key. The program must not be paused.
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ VestingError::Unauthorized,
+        constraint = !config.paused @ VestingError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    /// The template being retired.
+    #[account(mut, seeds = [b"template", template.template_id.to_le_bytes().as_ref()], bump = template.bump)]
+    pub template: Account<'info, ScheduleTemplate>,
+}
+
+/// Defines the accounts required for the `initialize_vesting` instruction.
+#[derive(Accounts)]
+#[instruction(template_id: u32, total_amount: u64, schedule: Vec<VestingSchedule>, revocable: bool)] // Make args available for constraints if needed
+pub struct InitializeVesting<'info> {
+    /// The admin account, must be a signer and match `config.admin`. Pays for account creation.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// The program config PDA holding the admin key. The program must not be paused.
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ VestingError::Unauthorized,
+        constraint = !config.paused @ VestingError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    /// The admin's SPL token account, from which tokens will be transferred. Must be mutable.
+    #[account(mut)]
+    pub admin_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The schedule template the schedule must follow. Must not be retired.
+    #[account(
+        seeds = [b"template", template_id.to_le_bytes().as_ref()],
+        bump = template.bump,
+        constraint = !template.retired @ VestingError::TemplateRetired
+    )]
+    pub template: Account<'info, ScheduleTemplate>,
+
+    /// The beneficiary account (wallet address). Used as a seed for the PDA. Mutable for potential future use.
+    /// CHECK: This account's signature is not required, and no data is read from it besides its key.
+    /// It is marked mutable because the `init` constraint on `vesting_account` requires the payer (`admin`)
+    /// to be mutable, and implicitly, other accounts involved might need to be.
+    #[account(mut)]
+    pub beneficiary: AccountInfo<'info>,
+
+    /// The beneficiary's grant counter PDA. Created on the beneficiary's first grant.
+    /// Its current `grant_count` is the index of the vesting account being initialized.
+    /// Seeds: "counter", beneficiary pubkey.
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + BeneficiaryCounter::SIZE,
+        seeds = [b"counter", beneficiary.key().as_ref()],
+        bump
+    )]
+    pub beneficiary_counter: Account<'info, BeneficiaryCounter>,
+
+    /// The vesting account PDA. Initialized by this instruction.
+    /// Stores the vesting schedule, beneficiary, mint, amounts, and bump seed.
+    /// Seeds: "vesting", beneficiary pubkey, grant index (u32, little endian).
+    #[account(
+        init, // Marks this account for creation
+        payer = admin, // `admin` pays for the account creation rent
+        space = 8 + VestingAccount::space(schedule.len()), // 8 byte discriminator + struct size for this schedule
+        seeds = [b"vesting", beneficiary.key().as_ref(), beneficiary_counter.grant_count.to_le_bytes().as_ref()], // PDA seeds
+        bump // Ask Anchor to find and store the bump seed
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    /// The Mint account of the SPL token being vested. Used for type safety and ATA initialization.
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// The SPL token account associated with the `vesting_account` PDA. Initialized by this instruction.
+    /// This account will hold the tokens being vested.
+    /// Authority is automatically set to the `vesting_account` PDA itself via `associated_token::authority`.
+    #[account(
+        init, // Marks this account for creation
         payer = admin, // `admin` pays for the account creation rent
         associated_token::mint = mint,
-        associated_token::authority = vesting_account // Sets the ATA owner/authority to the vesting PDA
+        associated_token::authority = vesting_account, // Sets the ATA owner/authority to the vesting PDA
+        associated_token::token_program = token_program // Derives the ATA under the mint's token program
+    )]
+    pub vesting_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The Solana System Program, required for creating accounts (`init`).
+    pub system_program: Program<'info, System>,
+
+    /// The token program owning `mint`: SPL Token or Token-2022. Required for token operations (transfer, ATA init).
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// The SPL Associated Token Account Program, required for initializing the `vesting_token_account`.
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Defines the accounts required for the `top_up_vesting` instruction.
+#[derive(Accounts)]
+#[instruction(tranches: Vec<VestingSchedule>)]
+pub struct TopUpVesting<'info> {
+    /// The admin account, must be a signer and match `config.admin`. Pays for the extra rent.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// The program config PDA holding the admin key. The program must not be paused.
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ VestingError::Unauthorized,
+        constraint = !config.paused @ VestingError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    /// The vesting account PDA being topped up. Reallocated to fit the appended tranches.
+    #[account(
+        mut,
+        realloc = 8 + VestingAccount::space(vesting_account.schedule.len() + tranches.len()),
+        realloc::payer = admin,
+        realloc::zero = false
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    /// The admin's token account, from which the top-up is transferred.
+    #[account(mut, constraint = admin_token_account.mint == vesting_account.mint @ VestingError::InvalidMint)]
+    pub admin_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The PDA's token account holding the vested tokens. Its balance increases.
+    #[account(mut,
+        constraint = vesting_token_account.mint == vesting_account.mint @ VestingError::InvalidMint,
+        constraint = vesting_token_account.owner == vesting_account.key() @ VestingError::InvalidOwner
+    )]
+    pub vesting_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The mint of the vested token, required by `transfer_checked`.
+    #[account(address = vesting_account.mint @ VestingError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// The global launch configuration PDA, used to check that tranches are future-dated.
+    #[account(seeds = [b"launch_config"], bump = launch_config.bump)]
+    pub launch_config: Account<'info, LaunchConfig>,
+
+    /// The token program owning `mint`: SPL Token or Token-2022.
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// The Solana System Program, required for the realloc rent transfer.
+    pub system_program: Program<'info, System>,
+}
+
+/// Defines the accounts required for the `claim_unlocked` instruction.
+#[derive(Accounts)]
+pub struct ClaimUnlocked<'info> {
+    /// The vesting account PDA containing the schedule and state. Mutable because `claimed_amount` is updated.
+    /// `has_one = authority` constraint ensures the `authority` signer matches the one stored in the account.
+    #[account(mut, has_one = authority @ VestingError::InvalidAuthority)]
+    pub vesting_account: Account<'info, VestingAccount>,
+    /// The beneficiary of the vesting schedule, must be a signer to authorize the claim.
+    pub authority: Signer<'info>,
+    /// The PDA's SPL token account holding the vested tokens. Mutable because its balance decreases.
+    /// constraint = vesting_token_account.mint == vesting_account.mint,
+    /// constraint = vesting_token_account.owner == vesting_signer.key() // Checked manually in handler
+    #[account(mut)]
+    pub vesting_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// The beneficiary's SPL token account where claimed tokens will be deposited. Mutable because its balance increases.
+    /// constraint = destination_token_account.mint == vesting_account.mint @ VestingError::InvalidMint
+    #[account(mut)]
+    pub destination_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// The vesting account PDA, required as the authority for the token transfer CPI.
+    /// CHECK: This account is not deserialized. Its key is checked against the expected PDA
+    /// derived from seeds (`vesting_account.authority`, `vesting_account.bump`) within the handler logic.
+    #[account(
+        seeds = [b"vesting", vesting_account.original_beneficiary.as_ref(), vesting_account.grant_index.to_le_bytes().as_ref()], // Ensure seeds match for Anchor's internal checks if needed
+        bump = vesting_account.bump
+    )]
+    /// CHECK: PDA signer for vesting account
+    pub vesting_signer: UncheckedAccount<'info>,
+    /// The global launch configuration PDA, used to resolve schedule offsets.
+    #[account(seeds = [b"launch_config"], bump = launch_config.bump)]
+    pub launch_config: Account<'info, LaunchConfig>,
+    /// The program config PDA. The program must not be paused.
+    #[account(seeds = [b"config"], bump = config.bump, constraint = !config.paused @ VestingError::ProgramPaused)]
+    pub config: Account<'info, Config>,
+    /// The mint of the vested token, required by `transfer_checked`.
+    #[account(address = vesting_account.mint @ VestingError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    /// The token program owning `mint`: SPL Token or Token-2022.
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Defines the accounts required for the `admin_distribute_unlocked` instruction.
+/// Similar to ClaimUnlocked but doesn't require the authority (beneficiary) to sign.
+/// Instead, it requires the admin to sign.
+#[derive(Accounts)]
+pub struct AdminDistribute<'info> {
+    /// The admin account (must match `config.admin`)
+    pub admin: Signer<'info>,
+
+    /// The program config PDA holding the admin key. The program must not be paused.
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ VestingError::Unauthorized,
+        constraint = !config.paused @ VestingError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+    
+    /// The vesting account PDA containing the schedule and state. Mutable because `claimed_amount` is updated.
+    #[account(mut)]
+    pub vesting_account: Account<'info, VestingAccount>,
+    
+    /// The beneficiary who will receive the tokens
+    /// CHECK: This account is only used for reference and verification
+    #[account(constraint = authority.key() == vesting_account.authority @ VestingError::InvalidAuthority)]
+    pub authority: UncheckedAccount<'info>,
+    
+    /// The PDA's SPL token account holding the vested tokens. Mutable because its balance decreases.
+    #[account(mut,
+        constraint = vesting_token_account.mint == vesting_account.mint @ VestingError::InvalidMint,
+        constraint = vesting_token_account.owner == vesting_signer.key() @ VestingError::InvalidOwner
+    )]
+    pub vesting_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    /// The beneficiary's SPL token account where claimed tokens will be deposited. Mutable because its balance increases.
+    #[account(mut,
+        constraint = destination_token_account.mint == vesting_account.mint @ VestingError::InvalidMint,
+        constraint = destination_token_account.owner == vesting_account.authority @ VestingError::InvalidAuthority
+    )]
+    pub destination_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    /// The vesting account PDA, required as the authority for the token transfer CPI.
+    #[account(
+        seeds = [b"vesting", vesting_account.original_beneficiary.as_ref(), vesting_account.grant_index.to_le_bytes().as_ref()],
+        bump = vesting_account.bump
+    )]
+    /// CHECK: PDA signer for vesting account
+    pub vesting_signer: UncheckedAccount<'info>,
+    
+    /// The global launch configuration PDA, used to resolve schedule offsets.
+    #[account(seeds = [b"launch_config"], bump = launch_config.bump)]
+    pub launch_config: Account<'info, LaunchConfig>,
+    
+    /// The mint of the vested token, required by `transfer_checked`.
+    #[account(address = vesting_account.mint @ VestingError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    /// The token program owning `mint`: SPL Token or Token-2022.
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Defines the accounts required for the `revoke_vesting` instruction.
+#[derive(Accounts)]
+pub struct RevokeVesting<'info> {
+    /// The admin account (must match `config.admin`)
+    pub admin: Signer<'info>,
+
+    /// The program config PDA holding the admin key. The program must not be paused.
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ VestingError::Unauthorized,
+        constraint = !config.paused @ VestingError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    /// The vesting account PDA being revoked. Mutable because it is marked terminated.
+    #[account(mut)]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    /// The PDA's SPL token account holding the vested tokens. Emptied by this instruction.
+    #[account(mut,
+        constraint = vesting_token_account.mint == vesting_account.mint @ VestingError::InvalidMint,
+        constraint = vesting_token_account.owner == vesting_signer.key() @ VestingError::InvalidOwner
+    )]
+    pub vesting_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The beneficiary's SPL token account receiving anything already unlocked.
+    #[account(mut,
+        constraint = destination_token_account.mint == vesting_account.mint @ VestingError::InvalidMint,
+        constraint = destination_token_account.owner == vesting_account.authority @ VestingError::InvalidAuthority
+    )]
+    pub destination_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The treasury's SPL token account receiving the unvested remainder.
+    #[account(mut,
+        constraint = treasury_token_account.mint == vesting_account.mint @ VestingError::InvalidMint,
+        constraint = treasury_token_account.owner == config.treasury @ VestingError::InvalidTreasury
+    )]
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The vesting account PDA, required as the authority for the token transfer CPIs.
+    #[account(
+        seeds = [b"vesting", vesting_account.original_beneficiary.as_ref(), vesting_account.grant_index.to_le_bytes().as_ref()],
+        bump = vesting_account.bump
+    )]
+    /// CHECK: PDA signer for vesting account
+    pub vesting_signer: UncheckedAccount<'info>,
+
+    /// The global launch configuration PDA, used to resolve schedule offsets.
+    #[account(seeds = [b"launch_config"], bump = launch_config.bump)]
+    pub launch_config: Account<'info, LaunchConfig>,
+
+    /// The mint of the vested token, required by `transfer_checked`.
+    #[account(address = vesting_account.mint @ VestingError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// The token program owning `mint`: SPL Token or Token-2022.
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Defines the accounts required for the `propose_authority_transfer` instruction.
+#[derive(Accounts)]
+pub struct ProposeAuthorityTransfer<'info> {
+    /// The vesting account whose position is being transferred.
+    #[account(mut, has_one = authority @ VestingError::InvalidAuthority)]
+    pub vesting_account: Account<'info, VestingAccount>,
+    /// The current beneficiary, must be a signer to authorize the proposal.
+    pub authority: Signer<'info>,
+    /// The program config PDA. The program must not be paused.
+    #[account(seeds = [b"config"], bump = config.bump, constraint = !config.paused @ VestingError::ProgramPaused)]
+    pub config: Account<'info, Config>,
+}
+
+/// Defines the accounts required for the `accept_authority_transfer` instruction.
+#[derive(Accounts)]
+pub struct AcceptAuthorityTransfer<'info> {
+    /// The vesting account whose position is being transferred.
+    #[account(mut)]
+    pub vesting_account: Account<'info, VestingAccount>,
+    /// The proposed new beneficiary, must be a signer to accept the transfer.
+    pub new_authority: Signer<'info>,
+    /// The program config PDA. The program must not be paused.
+    #[account(seeds = [b"config"], bump = config.bump, constraint = !config.paused @ VestingError::ProgramPaused)]
+    pub config: Account<'info, Config>,
+}
+
+/// Defines the accounts required for the `close_vesting` instruction.
+#[derive(Accounts)]
+pub struct CloseVesting<'info> {
+    /// The admin or the beneficiary, must be a signer to authorize closing.
+    #[account(
+        constraint = closer.key() == vesting_account.authority
+            || closer.key() == config.admin @ VestingError::Unauthorized
+    )]
+    pub closer: Signer<'info>,
+
+    /// The program config PDA holding the admin key. The program must not be paused.
+    #[account(seeds = [b"config"], bump = config.bump, constraint = !config.paused @ VestingError::ProgramPaused)]
+    pub config: Account<'info, Config>,
+
+    /// The vesting account PDA being closed. Its lamports are returned to `payer`.
+    #[account(mut, close = payer)]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    /// The PDA's SPL token account. Must be empty; closed by this instruction.
+    #[account(mut,
+        constraint = vesting_token_account.mint == vesting_account.mint @ VestingError::InvalidMint,
+        constraint = vesting_token_account.owner == vesting_account.key() @ VestingError::InvalidOwner
+    )]
+    pub vesting_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The account that paid the rent at init, receiving the lamports of both closed accounts.
+    /// CHECK: Only receives lamports; its key is checked against `vesting_account.payer`.
+    #[account(mut, address = vesting_account.payer @ VestingError::InvalidPayer)]
+    pub payer: UncheckedAccount<'info>,
+
+    /// The mint of the vested token. Mutable because withheld transfer fees are harvested to it.
+    #[account(mut, address = vesting_account.mint @ VestingError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// The token program owning `mint`: SPL Token or Token-2022. Required for the close account CPI.
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Defines the accounts required for the `initialize_presale` instruction.
+#[derive(Accounts)]
+pub struct InitializePresale<'info> {
+    /// The admin account, must be a signer and match `config.admin`. Pays for account creation.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// The program config PDA holding the admin key. The program must not be paused.
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ VestingError::Unauthorized,
+        constraint = !config.paused @ VestingError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    /// The presale configuration PDA. Initialized by this instruction.
+    /// Seeds: "presale".
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Presale::SIZE,
+        seeds = [b"presale"],
+        bump
+    )]
+    pub presale: Account<'info, Presale>,
+
+    /// The mint of the token being sold.
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// The PDA collecting SOL payments. Only its bump is recorded here.
+    /// Seeds: "presale_vault".
+    #[account(seeds = [b"presale_vault"], bump)]
+    pub sol_vault: SystemAccount<'info>,
+
+    /// The Solana System Program, required for creating accounts (`init`).
+    pub system_program: Program<'info, System>,
+}
+
+/// Defines the accounts required for the `update_presale`, `open_presale_phase` and
+/// `close_presale_phase` instructions.
+#[derive(Accounts)]
+pub struct UpdatePresale<'info> {
+    /// The admin account, must be a signer and match `config.admin`.
+    pub admin: Signer<'info>,
+
+    /// The program config PDA holding the admin key. The program must not be paused.
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ VestingError::Unauthorized,
+        constraint = !config.paused @ VestingError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    /// The presale configuration PDA being updated.
+    #[account(mut, seeds = [b"presale"], bump = presale.bump)]
+    pub presale: Account<'info, Presale>,
+}
+
+/// Defines the accounts required for the `buy_presale_batch` instruction.
+/// The four stablecoin accounts are passed together to pay in stablecoin, or omitted to pay in SOL.
+#[derive(Accounts)]
+#[instruction(phase: PresalePhase)]
+pub struct BuyPresaleBatch<'info> {
+    /// The buyer, must be a signer. Pays the price and the rent of the created accounts,
+    /// and becomes the beneficiary of the vesting account.
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    /// The program config PDA. The program must not be paused.
+    #[account(seeds = [b"config"], bump = config.bump, constraint = !config.paused @ VestingError::ProgramPaused)]
+    pub config: Box<Account<'info, Config>>,
+
+    /// The presale configuration PDA. Mutable because the phase's sold amount increases;
+    /// its bump signs the token vault transfer.
+    #[account(mut, seeds = [b"presale"], bump = presale.bump)]
+    pub presale: Box<Account<'info, Presale>>,
+
+    /// The phase's schedule template. Must not be retired.
+    #[account(
+        seeds = [b"template", presale.phase(phase).template_id.to_le_bytes().as_ref()],
+        bump = template.bump,
+        constraint = !template.retired @ VestingError::TemplateRetired
+    )]
+    pub template: Box<Account<'info, ScheduleTemplate>>,
+
+    /// The buyer's purchase receipt for this phase. Initialized by this instruction, so each
+    /// wallet can buy only one batch per phase.
+    /// Seeds: "receipt", phase (u8), buyer pubkey.
+    #[account(
+        init,
+        payer = buyer,
+        space = 8 + PresaleReceipt::SIZE,
+        seeds = [b"receipt", phase.seed().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub receipt: Box<Account<'info, PresaleReceipt>>,
+
+    /// The buyer's grant counter PDA. Created on the buyer's first grant.
+    /// Seeds: "counter", buyer pubkey.
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = 8 + BeneficiaryCounter::SIZE,
+        seeds = [b"counter", buyer.key().as_ref()],
+        bump
+    )]
+    pub beneficiary_counter: Box<Account<'info, BeneficiaryCounter>>,
+
+    /// The vesting account PDA. Initialized by this instruction with the template's schedule.
+    /// Seeds: "vesting", buyer pubkey, grant index (u32, little endian).
+    #[account(
+        init,
+        payer = buyer,
+        space = 8 + VestingAccount::space(template.tranches.len()),
+        seeds = [b"vesting", buyer.key().as_ref(), beneficiary_counter.grant_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub vesting_account: Box<Account<'info, VestingAccount>>,
+
+    /// The mint of the token being sold.
+    #[account(address = presale.mint @ VestingError::InvalidMint)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The presale's token account holding the tokens for sale, owned by the presale PDA.
+    #[account(mut,
+        constraint = token_vault.mint == presale.mint @ VestingError::InvalidMint,
+        constraint = token_vault.owner == presale.key() @ VestingError::InvalidOwner
+    )]
+    pub token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The token account associated with the `vesting_account` PDA. Initialized by this instruction.
+    #[account(
+        init,
+        payer = buyer,
+        associated_token::mint = mint,
+        associated_token::authority = vesting_account,
+        associated_token::token_program = token_program
+    )]
+    pub vesting_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The PDA collecting SOL payments.
+    #[account(mut, seeds = [b"presale_vault"], bump = presale.sol_vault_bump)]
+    pub sol_vault: SystemAccount<'info>,
+
+    /// The stablecoin mint, required by `transfer_checked`. Only for stablecoin payments.
+    #[account(address = presale.stable_mint @ VestingError::InvalidMint)]
+    pub stable_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// The buyer's stablecoin account paying the price. Only for stablecoin payments.
+    #[account(mut, constraint = buyer_stable_account.mint == presale.stable_mint @ VestingError::InvalidMint)]
+    pub buyer_stable_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// The presale's stablecoin account collecting payments, owned by the presale PDA.
+    /// Only for stablecoin payments.
+    #[account(mut,
+        constraint = stable_vault.mint == presale.stable_mint @ VestingError::InvalidMint,
+        constraint = stable_vault.owner == presale.key() @ VestingError::InvalidOwner
+    )]
+    pub stable_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// The token program owning `stable_mint`. Only for stablecoin payments.
+    pub stable_token_program: Option<Interface<'info, TokenInterface>>,
+
+    /// The Solana System Program, required for SOL payments and creating accounts (`init`).
+    pub system_program: Program<'info, System>,
+
+    /// The token program owning `mint`: SPL Token or Token-2022.
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// The SPL Associated Token Account Program, required for initializing the `vesting_token_account`.
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Defines the accounts required for the `withdraw_presale_proceeds` instruction.
+/// The four stablecoin accounts are passed together to also withdraw stablecoin, or omitted.
+#[derive(Accounts)]
+pub struct WithdrawPresaleProceeds<'info> {
+    /// The admin account, must be a signer and match `config.admin`.
+    pub admin: Signer<'info>,
+
+    /// The program config PDA holding the admin key. The program must not be paused.
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ VestingError::Unauthorized,
+        constraint = !config.paused @ VestingError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    /// The presale configuration PDA; its bump signs the stablecoin vault transfer.
+    #[account(seeds = [b"presale"], bump = presale.bump)]
+    pub presale: Account<'info, Presale>,
+
+    /// The PDA collecting SOL payments. Emptied by this instruction.
+    #[account(mut, seeds = [b"presale_vault"], bump = presale.sol_vault_bump)]
+    pub sol_vault: SystemAccount<'info>,
+
+    /// The treasury wallet receiving the SOL.
+    #[account(mut, address = config.treasury @ VestingError::InvalidTreasury)]
+    pub treasury: SystemAccount<'info>,
+
+    /// The stablecoin mint, required by `transfer_checked`.
+    #[account(address = presale.stable_mint @ VestingError::InvalidMint)]
+    pub stable_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// The presale's stablecoin account collecting payments. Emptied by this instruction.
+    #[account(mut,
+        constraint = stable_vault.mint == presale.stable_mint @ VestingError::InvalidMint,
+        constraint = stable_vault.owner == presale.key() @ VestingError::InvalidOwner
+    )]
+    pub stable_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The treasury's stablecoin account receiving the stablecoin.
+    #[account(mut,
+        constraint = treasury_stable_account.mint == presale.stable_mint @ VestingError::InvalidMint,
+        constraint = treasury_stable_account.owner == treasury.key() @ VestingError::InvalidTreasury
+    )]
+    pub treasury_stable_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The token program owning `stable_mint`.
+    pub stable_token_program: Option<Interface<'info, TokenInterface>>,
+
+    /// The Solana System Program, required for the SOL transfer.
+    pub system_program: Program<'info, System>,
+}
+
+/// Defines the accounts required for the `create_distributor` instruction.
+#[derive(Accounts)]
+#[instruction(distributor_id: u32, root: [u8; 32], num_leaves: u32)]
+pub struct CreateDistributor<'info> {
+    /// The admin account, must be a signer and match `config.admin`. Pays for account creation.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// The program config PDA holding the admin key. The program must not be paused.
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ VestingError::Unauthorized,
+        constraint = !config.paused @ VestingError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    /// The distributor PDA. Initialized by this instruction with an empty claim bitmap.
+    /// Seeds: "distributor", distributor ID (u32, little endian).
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Distributor::space(num_leaves),
+        seeds = [b"distributor", distributor_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub distributor: Box<Account<'info, Distributor>>,
+
+    /// The admin's token account, from which the vault is funded.
+    #[account(mut, constraint = admin_token_account.mint == mint.key() @ VestingError::InvalidMint)]
+    pub admin_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of the distributed token.
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The distributor's vault, its associated token account. Initialized by this instruction.
+    #[account(
+        init,
+        payer = admin,
+        associated_token::mint = mint,
+        associated_token::authority = distributor,
+        associated_token::token_program = token_program
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The Solana System Program, required for creating accounts (`init`).
+    pub system_program: Program<'info, System>,
+
+    /// The token program owning `mint`: SPL Token or Token-2022.
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// The SPL Associated Token Account Program, required for initializing the vault.
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Defines the accounts required for the `claim_distributor_vesting` instruction.
+#[derive(Accounts)]
+#[instruction(index: u32, amount: u64, template_id: u32)]
+pub struct ClaimDistributorVesting<'info> {
+    /// The wallet in the leaf, must be a signer. Pays the rent of the created accounts and
+    /// becomes the beneficiary of the vesting account.
+    #[account(mut)]
+    pub claimant: Signer<'info>,
+
+    /// The program config PDA. The program must not be paused.
+    #[account(seeds = [b"config"], bump = config.bump, constraint = !config.paused @ VestingError::ProgramPaused)]
+    pub config: Box<Account<'info, Config>>,
+
+    /// The distributor PDA. Mutable because the leaf is marked claimed.
+    #[account(mut, seeds = [b"distributor", distributor.distributor_id.to_le_bytes().as_ref()], bump = distributor.bump)]
+    pub distributor: Box<Account<'info, Distributor>>,
+
+    /// The leaf's schedule template. Retired templates still honour published allocations.
+    #[account(seeds = [b"template", template_id.to_le_bytes().as_ref()], bump = template.bump)]
+    pub template: Box<Account<'info, ScheduleTemplate>>,
+
+    /// The claimant's grant counter PDA. Created on the claimant's first grant.
+    /// Seeds: "counter", claimant pubkey.
+    #[account(
+        init_if_needed,
+        payer = claimant,
+        space = 8 + BeneficiaryCounter::SIZE,
+        seeds = [b"counter", claimant.key().as_ref()],
+        bump
+    )]
+    pub beneficiary_counter: Box<Account<'info, BeneficiaryCounter>>,
+
+    /// The vesting account PDA. Initialized by this instruction with the template's schedule.
+    /// Seeds: "vesting", claimant pubkey, grant index (u32, little endian).
+    #[account(
+        init,
+        payer = claimant,
+        space = 8 + VestingAccount::space(template.tranches.len()),
+        seeds = [b"vesting", claimant.key().as_ref(), beneficiary_counter.grant_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub vesting_account: Box<Account<'info, VestingAccount>>,
+
+    /// The mint of the distributed token.
+    #[account(address = distributor.mint @ VestingError::InvalidMint)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The distributor's vault holding the unclaimed allocations.
+    #[account(mut,
+        associated_token::mint = mint,
+        associated_token::authority = distributor,
+        associated_token::token_program = token_program
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The token account associated with the `vesting_account` PDA. Initialized by this instruction.
+    #[account(
+        init,
+        payer = claimant,
+        associated_token::mint = mint,
+        associated_token::authority = vesting_account,
+        associated_token::token_program = token_program
+    )]
+    pub vesting_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The Solana System Program, required for creating accounts (`init`).
+    pub system_program: Program<'info, System>,
+
+    /// The token program owning `mint`: SPL Token or Token-2022.
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// The SPL Associated Token Account Program, required for initializing the `vesting_token_account`.
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Defines the accounts required for the `claim_distributor_allocation` instruction.
+#[derive(Accounts)]
+#[instruction(index: u32, amount: u64, template_id: u32)]
+pub struct ClaimDistributorAllocation<'info> {
+    /// The wallet in the leaf, must be a signer.
+    pub claimant: Signer<'info>,
+
+    /// The program config PDA. The program must not be paused.
+    #[account(seeds = [b"config"], bump = config.bump, constraint = !config.paused @ VestingError::ProgramPaused)]
+    pub config: Box<Account<'info, Config>>,
+
+    /// The distributor PDA. Mutable because the leaf is marked claimed.
+    #[account(mut, seeds = [b"distributor", distributor.distributor_id.to_le_bytes().as_ref()], bump = distributor.bump)]
+    pub distributor: Box<Account<'info, Distributor>>,
+
+    /// The leaf's schedule template, used to check that the allocation has fully unlocked.
+    #[account(seeds = [b"template", template_id.to_le_bytes().as_ref()], bump = template.bump)]
+    pub template: Box<Account<'info, ScheduleTemplate>>,
+
+    /// The global launch configuration PDA, used to resolve schedule offsets.
+    #[account(seeds = [b"launch_config"], bump = launch_config.bump)]
+    pub launch_config: Box<Account<'info, LaunchConfig>>,
+
+    /// The mint of the distributed token.
+    #[account(address = distributor.mint @ VestingError::InvalidMint)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The distributor's vault holding the unclaimed allocations.
+    #[account(mut,
+        associated_token::mint = mint,
+        associated_token::authority = distributor,
+        associated_token::token_program = token_program
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The claimant's token account receiving the allocation.
+    #[account(mut,
+        constraint = destination_token_account.mint == distributor.mint @ VestingError::InvalidMint,
+        constraint = destination_token_account.owner == claimant.key() @ VestingError::InvalidOwner
+    )]
+    pub destination_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The token program owning `mint`: SPL Token or Token-2022.
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Defines the accounts required for the `stake_vesting` instruction.
+#[derive(Accounts)]
+pub struct StakeVesting<'info> {
+    /// The beneficiary, must be a signer. Pays for the staking position account.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// The vesting account PDA, signing as the position owner. Mutable because
+    /// `staked_amount` is updated.
+    #[account(mut, has_one = authority @ VestingError::InvalidAuthority)]
+    pub vesting_account: Box<Account<'info, VestingAccount>>,
+
+    /// The vesting PDA's token account, from which the stake is transferred.
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = vesting_account,
+        associated_token::token_program = token_program
+    )]
+    pub vesting_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The program config PDA. The program must not be paused.
+    #[account(seeds = [b"config"], bump = config.bump, constraint = !config.paused @ VestingError::ProgramPaused)]
+    pub config: Box<Account<'info, Config>>,
+
+    /// The staking pool of the vested mint. Checked by the staking program.
+    /// CHECK: Validated by the staking program during the CPI.
+    #[account(mut)]
+    pub pool: UncheckedAccount<'info>,
+
+    /// The staking position PDA, initialized by the staking program.
+    /// CHECK: Validated by the staking program during the CPI.
+    #[account(mut)]
+    pub position: UncheckedAccount<'info>,
+
+    /// The pool's stake vault.
+    /// CHECK: Validated by the staking program during the CPI.
+    #[account(mut)]
+    pub stake_vault: UncheckedAccount<'info>,
+
+    /// The pool's reward vault.
+    /// CHECK: Validated by the staking program during the CPI.
+    pub reward_vault: UncheckedAccount<'info>,
+
+    /// The mint of the vested token, required by `transfer_checked`.
+    #[account(address = vesting_account.mint @ VestingError::InvalidMint)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The staking program.
+    pub staking_program: Program<'info, Staking>,
+
+    /// The Solana System Program, required for creating the position account.
+    pub system_program: Program<'info, System>,
+
+    /// The token program owning `mint`: SPL Token or Token-2022.
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Defines the accounts required for the `claim_vesting_stake_rewards` instruction.
+#[derive(Accounts)]
+pub struct ClaimVestingStakeRewards<'info> {
+    /// The beneficiary, must be a signer.
+    pub authority: Signer<'info>,
+
+    /// The vesting account PDA owning the position.
+    #[account(has_one = authority @ VestingError::InvalidAuthority)]
+    pub vesting_account: Box<Account<'info, VestingAccount>>,
+
+    /// The program config PDA. The program must not be paused.
+    #[account(seeds = [b"config"], bump = config.bump, constraint = !config.paused @ VestingError::ProgramPaused)]
+    pub config: Box<Account<'info, Config>>,
+
+    /// The staking position, owned by the vesting PDA.
+    #[account(mut, constraint = position.owner == vesting_account.key() @ VestingError::InvalidOwner)]
+    pub position: Box<Account<'info, StakePosition>>,
+
+    /// The staking pool of the vested mint.
+    /// CHECK: Validated by the staking program during the CPI.
+    #[account(mut)]
+    pub pool: UncheckedAccount<'info>,
+
+    /// The beneficiary's token account receiving the rewards.
+    #[account(mut, constraint = destination_token_account.mint == vesting_account.mint @ VestingError::InvalidMint)]
+    pub destination_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The pool's reward vault.
+    /// CHECK: Validated by the staking program during the CPI.
+    #[account(mut)]
+    pub reward_vault: UncheckedAccount<'info>,
+
+    /// The mint of the vested token, required by `transfer_checked`.
+    #[account(address = vesting_account.mint @ VestingError::InvalidMint)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The staking program.
+    pub staking_program: Program<'info, Staking>,
+
+    /// The token program owning `mint`: SPL Token or Token-2022.
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Defines the accounts required for the `unstake_vesting` instruction.
+#[derive(Accounts)]
+pub struct UnstakeVesting<'info> {
+    /// The beneficiary, must be a signer.
+    pub authority: Signer<'info>,
+
+    /// The vesting account PDA owning the position. Mutable because `staked_amount` is updated.
+    #[account(mut, has_one = authority @ VestingError::InvalidAuthority)]
+    pub vesting_account: Box<Account<'info, VestingAccount>>,
+
+    /// The vesting PDA's token account, receiving the stake back.
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = vesting_account,
+        associated_token::token_program = token_program
     )]
-    pub vesting_token_account: Account<'info, TokenAccount>,
+    pub vesting_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The program config PDA. The program must not be paused.
+    #[account(seeds = [b"config"], bump = config.bump, constraint = !config.paused @ VestingError::ProgramPaused)]
+    pub config: Box<Account<'info, Config>>,
+
+    /// The staking position, owned by the vesting PDA. Closed by the staking program.
+    #[account(mut, constraint = position.owner == vesting_account.key() @ VestingError::InvalidOwner)]
+    pub position: Box<Account<'info, StakePosition>>,
+
+    /// The wallet that paid for the position, receiving its rent.
+    /// CHECK: Validated against `position.payer` by the staking program during the CPI.
+    #[account(mut)]
+    pub position_payer: UncheckedAccount<'info>,
+
+    /// The staking pool of the vested mint.
+    /// CHECK: Validated by the staking program during the CPI.
+    #[account(mut)]
+    pub pool: UncheckedAccount<'info>,
+
+    /// The beneficiary's token account receiving the unclaimed rewards.
+    #[account(mut, constraint = destination_token_account.mint == vesting_account.mint @ VestingError::InvalidMint)]
+    pub destination_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The pool's stake vault.
+    /// CHECK: Validated by the staking program during the CPI.
+    #[account(mut)]
+    pub stake_vault: UncheckedAccount<'info>,
+
+    /// The pool's reward vault.
+    /// CHECK: Validated by the staking program during the CPI.
+    #[account(mut)]
+    pub reward_vault: UncheckedAccount<'info>,
+
+    /// The mint of the vested token, required by `transfer_checked`.
+    #[account(address = vesting_account.mint @ VestingError::InvalidMint)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The staking program.
+    pub staking_program: Program<'info, Staking>,
+
+    /// The token program owning `mint`: SPL Token or Token-2022.
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Defines the accounts required for the `initialize_staking_reserve` instruction.
+#[derive(Accounts)]
+pub struct InitializeStakingReserve<'info> {
+    /// The admin account, must be a signer and match `config.admin`. Pays for account creation.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// The program config PDA holding the admin key. The program must not be paused.
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ VestingError::Unauthorized,
+        constraint = !config.paused @ VestingError::ProgramPaused
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    /// The staking reserve PDA. Initialized by this instruction.
+    /// Seeds: "staking_reserve".
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + StakingReserve::SIZE,
+        seeds = [b"staking_reserve"],
+        bump
+    )]
+    pub staking_reserve: Box<Account<'info, StakingReserve>>,
+
+    /// The admin's token account, from which the vault is funded.
+    #[account(mut, constraint = admin_token_account.mint == mint.key() @ VestingError::InvalidMint)]
+    pub admin_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The community/treasury token account receiving the reserve if staking is not live by
+    /// the deadline.
+    #[account(constraint = release_account.mint == mint.key() @ VestingError::InvalidMint)]
+    pub release_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of the reserved token.
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The reserve's vault, its associated token account. Initialized by this instruction.
+    #[account(
+        init,
+        payer = admin,
+        associated_token::mint = mint,
+        associated_token::authority = staking_reserve,
+        associated_token::token_program = token_program
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The Solana System Program, required for creating accounts (`init`).
+    pub system_program: Program<'info, System>,
+
+    /// The token program owning `mint`: SPL Token or Token-2022.
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// The SPL Associated Token Account Program, required for initializing the vault.
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Defines the accounts required for the `fund_staking_from_reserve` instruction.
+#[derive(Accounts)]
+pub struct FundStakingFromReserve<'info> {
+    /// The admin account, must be a signer and match `config.admin`.
+    pub admin: Signer<'info>,
+
+    /// The program config PDA holding the admin key. The program must not be paused.
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ VestingError::Unauthorized,
+        constraint = !config.paused @ VestingError::ProgramPaused
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    /// The staking reserve PDA, signing as the funder. Mutable because its status is updated.
+    #[account(mut, seeds = [b"staking_reserve"], bump = staking_reserve.bump)]
+    pub staking_reserve: Box<Account<'info, StakingReserve>>,
+
+    /// The reserve's vault.
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = staking_reserve,
+        associated_token::token_program = token_program
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The staking pool of the reserved mint.
+    /// CHECK: Validated by the staking program during the CPI.
+    pub pool: UncheckedAccount<'info>,
+
+    /// The pool's reward vault, receiving the reserve.
+    /// CHECK: Validated by the staking program during the CPI.
+    #[account(mut)]
+    pub reward_vault: UncheckedAccount<'info>,
+
+    /// The mint of the reserved token, required by `transfer_checked`.
+    #[account(address = staking_reserve.mint @ VestingError::InvalidMint)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The staking program.
+    pub staking_program: Program<'info, Staking>,
+
+    /// The token program owning `mint`: SPL Token or Token-2022.
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Defines the accounts required for the `release_staking_reserve` instruction.
+#[derive(Accounts)]
+pub struct ReleaseStakingReserve<'info> {
+    /// The program config PDA. The program must not be paused.
+    #[account(seeds = [b"config"], bump = config.bump, constraint = !config.paused @ VestingError::ProgramPaused)]
+    pub config: Box<Account<'info, Config>>,
+
+    /// The staking reserve PDA, signing for its vault. Mutable because its status is updated.
+    #[account(mut, seeds = [b"staking_reserve"], bump = staking_reserve.bump)]
+    pub staking_reserve: Box<Account<'info, StakingReserve>>,
+
+    /// The reserve's vault.
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = staking_reserve,
+        associated_token::token_program = token_program
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The community/treasury token account recorded at init.
+    #[account(mut, address = staking_reserve.release_account @ VestingError::InvalidReleaseAccount)]
+    pub release_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of the reserved token, required by `transfer_checked`.
+    #[account(address = staking_reserve.mint @ VestingError::InvalidMint)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The token program owning `mint`: SPL Token or Token-2022.
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Defines the accounts required for the `initialize_payment_config` instruction.
+#[derive(Accounts)]
+pub struct InitializePaymentConfig<'info> {
+    /// The admin account, must be a signer and match `config.admin`. Pays for account creation.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// The program config PDA holding the admin key. The program must not be paused.
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ VestingError::Unauthorized,
+        constraint = !config.paused @ VestingError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    /// The payment config PDA. Initialized by this instruction.
+    /// Seeds: "payment_config".
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + PaymentConfig::SIZE,
+        seeds = [b"payment_config"],
+        bump
+    )]
+    pub payment_config: Account<'info, PaymentConfig>,
+
+    /// The mint accepted as payment.
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// The Solana System Program, required for creating the payment config account.
+    pub system_program: Program<'info, System>,
+}
+
+/// Defines the accounts required for the `update_payment_fee` and `set_kyc_attestor` instructions.
+#[derive(Accounts)]
+pub struct UpdatePaymentConfig<'info> {
+    /// The admin account, must be a signer and match `config.admin`.
+    pub admin: Signer<'info>,
+
+    /// The program config PDA holding the admin key. The program must not be paused.
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ VestingError::Unauthorized,
+        constraint = !config.paused @ VestingError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    /// The payment config PDA.
+    #[account(mut, seeds = [b"payment_config"], bump = payment_config.bump)]
+    pub payment_config: Account<'info, PaymentConfig>,
+}
+
+/// Defines the accounts required for the `register_merchant` instruction.
+#[derive(Accounts)]
+pub struct RegisterMerchant<'info> {
+    /// The merchant's owner, must be a signer. Pays for the merchant account.
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The program config PDA. The program must not be paused.
+    #[account(seeds = [b"config"], bump = config.bump, constraint = !config.paused @ VestingError::ProgramPaused)]
+    pub config: Box<Account<'info, Config>>,
+
+    /// The merchant PDA. Initialized by this instruction.
+    /// Seeds: "merchant", owner pubkey.
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Merchant::SIZE,
+        seeds = [b"merchant", owner.key().as_ref()],
+        bump
+    )]
+    pub merchant: Account<'info, Merchant>,
+
+    /// The payment config PDA, fixing the payment mint.
+    #[account(seeds = [b"payment_config"], bump = payment_config.bump)]
+    pub payment_config: Account<'info, PaymentConfig>,
+
+    /// The token account payments settle into.
+    #[account(constraint = settlement_account.mint == payment_config.mint @ VestingError::InvalidMint)]
+    pub settlement_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The Solana System Program, required for creating the merchant account.
+    pub system_program: Program<'info, System>,
+}
+
+/// Defines the accounts required for the `set_merchant_kyc_status` instruction.
+#[derive(Accounts)]
+pub struct SetMerchantKycStatus<'info> {
+    /// The KYC attestor, must be a signer and match `payment_config.attestor`.
+    pub attestor: Signer<'info>,
+
+    /// The program config PDA. The program must not be paused.
+    #[account(seeds = [b"config"], bump = config.bump, constraint = !config.paused @ VestingError::ProgramPaused)]
+    pub config: Account<'info, Config>,
+
+    /// The payment config PDA holding the attestor key.
+    #[account(seeds = [b"payment_config"], bump = payment_config.bump, has_one = attestor @ VestingError::Unauthorized)]
+    pub payment_config: Account<'info, PaymentConfig>,
+
+    /// The merchant whose KYC status is set.
+    #[account(mut, seeds = [b"merchant", merchant.owner.as_ref()], bump = merchant.bump)]
+    pub merchant: Account<'info, Merchant>,
+}
+
+/// Defines the accounts required for the `set_merchant_fee_tier`, `suspend_merchant` and
+/// `reinstate_merchant` instructions.
+#[derive(Accounts)]
+pub struct UpdateMerchant<'info> {
+    /// The admin account, must be a signer and match `config.admin`.
+    pub admin: Signer<'info>,
+
+    /// The program config PDA holding the admin key. The program must not be paused.
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ VestingError::Unauthorized,
+        constraint = !config.paused @ VestingError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    /// The merchant being updated.
+    #[account(mut, seeds = [b"merchant", merchant.owner.as_ref()], bump = merchant.bump)]
+    pub merchant: Account<'info, Merchant>,
+}
+
+/// Defines the accounts required for the `pay_merchant` instruction.
+#[derive(Accounts)]
+pub struct PayMerchant<'info> {
+    /// The buyer, must be a signer to authorize the transfers.
+    pub buyer: Signer<'info>,
+
+    /// The program config PDA. The program must not be paused.
+    #[account(seeds = [b"config"], bump = config.bump, constraint = !config.paused @ VestingError::ProgramPaused)]
+    pub config: Box<Account<'info, Config>>,
+
+    /// The payment config PDA holding the payment mint and protocol fee.
+    #[account(seeds = [b"payment_config"], bump = payment_config.bump)]
+    pub payment_config: Box<Account<'info, PaymentConfig>>,
+
+    /// The merchant being paid.
+    #[account(seeds = [b"merchant", merchant.owner.as_ref()], bump = merchant.bump)]
+    pub merchant: Box<Account<'info, Merchant>>,
+
+    /// The buyer's token account, from which the payment is transferred.
+    #[account(mut, constraint = buyer_token_account.mint == payment_config.mint @ VestingError::InvalidMint)]
+    pub buyer_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The merchant's settlement token account, receiving the payment minus the fee.
+    #[account(mut, address = merchant.settlement_account @ VestingError::InvalidSettlementAccount)]
+    pub settlement_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The treasury's token account, receiving the protocol fee.
+    #[account(mut,
+        constraint = treasury_token_account.mint == payment_config.mint @ VestingError::InvalidMint,
+        constraint = treasury_token_account.owner == config.treasury @ VestingError::InvalidTreasury
+    )]
+    pub treasury_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The Solana Pay reference, a unique pubkey per payment request.
+    /// CHECK: Never read or written; only included so the payment can be looked up by it.
+    pub reference: UncheckedAccount<'info>,
+
+    /// The payment mint, required by `transfer_checked`.
+    #[account(address = payment_config.mint @ VestingError::InvalidMint)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The token program owning `mint`: SPL Token or Token-2022.
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Represents the state of a vesting schedule stored in the PDA.
+#[account]
+pub struct VestingAccount {
+    /// The public key of the beneficiary who can claim the tokens. Used in `has_one`.
+    /// Can change through `propose_authority_transfer` / `accept_authority_transfer`.
+    pub authority: Pubkey,
+    /// The beneficiary the account was initialized for. Used in PDA seeds; never changes.
+    pub original_beneficiary: Pubkey,
+    /// The wallet proposed as the new `authority`, if a transfer is pending.
+    pub pending_authority: Option<Pubkey>,
+    /// The mint address of the SPL token being vested.
+    pub mint: Pubkey,
+    /// The total amount of tokens locked in the schedule, including top-ups.
+    pub total_amount: u64,
+    /// The amount of tokens already claimed by the beneficiary.
+    pub claimed_amount: u64,
+    /// The amount of unclaimed tokens committed to staking positions through `stake_vesting`.
+    /// These sit in the staking program's stake vault instead of the vesting token account,
+    /// so they cannot be claimed until `unstake_vesting` returns them.
+    pub staked_amount: u64,
+    /// The vector containing individual vesting cliffs or linear segments (offsets and amount).
+    pub schedule: Vec<VestingSchedule>,
+    /// The ID of the schedule template the initial schedule was validated against.
+    pub template_id: u32,
+    /// Whether the schedule entries are step cliffs or linear segments. Dictated by the template.
+    pub schedule_kind: ScheduleKind,
+    /// Whether the admin may revoke the vesting and claw back unvested tokens. Set at init.
+    pub revocable: bool,
+    /// Whether the vesting has been revoked. Terminated accounts cannot be claimed from.
+    pub terminated: bool,
+    /// Index of this grant among the beneficiary's vesting accounts. Used in PDA seeds.
+    pub grant_index: u32,
+    /// The account that paid the rent at init. Receives the lamports when the account is closed.
+    pub payer: Pubkey,
+    /// The bump seed used for the PDA derivation. Required for CPI signing.
+    pub bump: u8,
+}
+
+impl VestingAccount {
+    /// Maximum number of schedule entries allowed, e.g. 48 monthly tranches (four years).
+    ///
+    /// Claims walk the whole schedule, so the bound keeps `claim_unlocked` well inside the
+    /// default 200k compute-unit budget; `tests/scheduleLength.ts` measures a claim over this
+    /// many entries. A single `initialize_vesting` transaction fits at most 22 entries (legacy
+    /// transaction size limit); `top_up_vesting` appends the rest.
+    pub const MAX_SCHEDULES: usize = 48;
+
+    /// Size of the account data for a schedule of `schedule_len` entries (excluding the discriminator).
+    pub fn space(schedule_len: usize) -> usize {
+        32 // authority: Pubkey
+            + 32 // original_beneficiary: Pubkey
+            + (1 + 32) // pending_authority: Option<Pubkey>
+            + 32 // mint: Pubkey
+            + 8  // total_amount: u64
+            + 8  // claimed_amount: u64
+            + 8  // staked_amount: u64
+            + (4 + schedule_len * VestingSchedule::SIZE) // schedule: Vec<VestingSchedule> (4 bytes for len + size per item)
+            + 4 // template_id: u32
+            + 1 // schedule_kind: ScheduleKind
+            + 1 // revocable: bool
+            + 1 // terminated: bool
+            + 4 // grant_index: u32
+            + 32 // payer: Pubkey
+            + 1 // bump: u8
+    }
+
+    /// Initializes a non-revocable grant of `total_amount` tokens whose cliff schedule is
+    /// built from `template`. Used when the beneficiary, rather than the admin, opens the
+    /// grant (presale purchases and distributor claims), so the beneficiary is also the payer.
+    pub fn init_from_template(
+        &mut self,
+        beneficiary: Pubkey,
+        mint: Pubkey,
+        template: &ScheduleTemplate,
+        total_amount: u64,
+        grant_index: u32,
+        bump: u8,
+    ) -> Result<()> {
+        self.authority = beneficiary;
+        self.original_beneficiary = beneficiary;
+        self.pending_authority = None;
+        self.mint = mint;
+        self.total_amount = total_amount;
+        self.claimed_amount = 0;
+        self.staked_amount = 0;
+        self.schedule = template.build_schedule(total_amount)?;
+        self.template_id = template.template_id;
+        self.schedule_kind = template.kind;
+        self.revocable = false;
+        self.terminated = false;
+        self.grant_index = grant_index;
+        self.payer = beneficiary;
+        self.bump = bump;
+        Ok(())
+    }
+
+    /// Returns the total amount unlocked by the schedule at time `now`,
+    /// i.e. the sum of the amounts unlocked by each cliff or linear segment.
+    pub fn unlocked_amount(&self, now: u64, launch_time: u64) -> u64 {
+        self.schedule
+            .iter()
+            // Use saturating_add to prevent overflow (though unlikely with u64 amounts).
+            .fold(0u64, |acc, s| acc.saturating_add(s.unlocked_amount(now, launch_time)))
+    }
+
+    /// Returns the amount unlocked at time `now` but not yet claimed, capped at the tokens
+    /// not committed to staking (`available_amount`).
+    pub fn claimable_amount(&self, now: u64, launch_time: u64) -> u64 {
+        // Use saturating_sub to prevent underflow if claimed_amount somehow exceeds unlocked.
+        self.unlocked_amount(now, launch_time)
+            .saturating_sub(self.claimed_amount)
+            .min(self.available_amount())
+    }
+
+    /// Returns the unclaimed tokens still held by the vesting token account, i.e. neither
+    /// claimed nor staked.
+    pub fn available_amount(&self) -> u64 {
+        self.total_amount.saturating_sub(self.claimed_amount).saturating_sub(self.staked_amount)
+    }
+}
+
+/// Program configuration holding the admin key and treasury wallet, replacing hard-coded
+/// pubkeys, and the emergency pause switch.
+#[account]
+pub struct Config {
+    /// The wallet allowed to perform admin actions.
+    pub admin: Pubkey,
+    /// The wallet proposed as the new admin, if a rotation is pending.
+    pub pending_admin: Option<Pubkey>,
+    /// The wallet allowed to pause the program (but not unpause it or move funds).
+    /// `Pubkey::default()` when no guardian is set; the admin can always pause.
+    pub guardian: Pubkey,
+    /// The wallet receiving revoked tokens, presale proceeds and merchant payment fees.
+    pub treasury: Pubkey,
+    /// Whether the program is paused. While paused, vesting instructions fail with `ProgramPaused`.
+    pub paused: bool,
+    /// The bump seed used for the PDA derivation.
+    pub bump: u8,
+}
+
+impl Config {
+    /// Size of the account data, excluding the 8 byte discriminator.
+    pub const SIZE: usize = 32 // admin: Pubkey
+        + (1 + 32) // pending_admin: Option<Pubkey>
+        + 32 // guardian: Pubkey
+        + 32 // treasury: Pubkey
+        + 1 // paused: bool
+        + 1; // bump: u8
+}
+
+/// An M-of-N multisig whose signer PDA can act as the program admin.
+#[account]
+pub struct Multisig {
+    /// The wallet that created the multisig. Used in PDA seeds.
+    pub creator: Pubkey,
+    /// The wallets allowed to propose and approve.
+    pub members: Vec<Pubkey>,
+    /// The number of approvals required to execute a proposal.
+    pub threshold: u8,
+    /// Incremented whenever the members change; proposals from an older member set are stale.
+    pub owner_set_seqno: u32,
+    /// Number of proposals created; the next proposal's index. Used in proposal PDA seeds.
+    pub proposal_count: u64,
+    /// The bump seed used for the multisig PDA derivation.
+    pub bump: u8,
+    /// The bump seed of the multisig's signer PDA. Required for CPI signing.
+    pub signer_bump: u8,
+}
+
+impl Multisig {
+    /// Maximum number of members a multisig can have.
+    pub const MAX_MEMBERS: usize = 10;
+    /// Size of the account data, excluding the 8 byte discriminator.
+    pub const SIZE: usize = 32 // creator: Pubkey
+        + (4 + Self::MAX_MEMBERS * 32) // members: Vec<Pubkey>
+        + 1 // threshold: u8
+        + 4 // owner_set_seqno: u32
+        + 8 // proposal_count: u64
+        + 1 // bump: u8
+        + 1; // signer_bump: u8
+
+    /// Verifies that `members` is a non-empty set of unique wallets that fits in the account,
+    /// and that `threshold` is between 1 and the number of members.
+    pub fn validate_members(members: &[Pubkey], threshold: u8) -> Result<()> {
+        require!(
+            !members.is_empty() && members.len() <= Self::MAX_MEMBERS,
+            VestingError::InvalidMultisigMembers
+        );
+        for (i, member) in members.iter().enumerate() {
+            require!(!members[..i].contains(member), VestingError::InvalidMultisigMembers);
+        }
+        require!(
+            threshold > 0 && threshold as usize <= members.len(),
+            VestingError::InvalidMultisigThreshold
+        );
+        Ok(())
+    }
+
+    /// Returns the index of `member` in the member list, failing if it is not a member.
+    pub fn member_index(&self, member: &Pubkey) -> Result<usize> {
+        self.members
+            .iter()
+            .position(|m| m == member)
+            .ok_or_else(|| error!(VestingError::NotMultisigMember))
+    }
+}
+
+/// A pending instruction to be signed by a multisig's signer PDA once approved.
+#[account]
+pub struct Proposal {
+    /// The multisig the proposal belongs to.
+    pub multisig: Pubkey,
+    /// The member who created the proposal.
+    pub proposer: Pubkey,
+    /// Index of the proposal within the multisig. Used in PDA seeds.
+    pub index: u64,
+    /// The program the instruction targets.
+    pub program_id: Pubkey,
+    /// The instruction's account metas.
+    pub accounts: Vec<ProposalAccount>,
+    /// The instruction data.
+    pub data: Vec<u8>,
+    /// Approval flag per member, in the order of `Multisig::members`.
+    pub approvals: Vec<bool>,
+    /// Whether the proposal has been executed.
+    pub executed: bool,
+    /// The multisig's `owner_set_seqno` when the proposal was created.
+    pub owner_set_seqno: u32,
+    /// The bump seed used for the PDA derivation.
+    pub bump: u8,
+}
+
+impl Proposal {
+    /// Size of the account data for an instruction with `num_accounts` accounts and
+    /// `data_len` bytes of data, in a multisig of `num_members` members (excluding the discriminator).
+    pub fn space(num_accounts: usize, data_len: usize, num_members: usize) -> usize {
+        32 // multisig: Pubkey
+            + 32 // proposer: Pubkey
+            + 8 // index: u64
+            + 32 // program_id: Pubkey
+            + (4 + num_accounts * ProposalAccount::SIZE) // accounts: Vec<ProposalAccount>
+            + (4 + data_len) // data: Vec<u8>
+            + (4 + num_members) // approvals: Vec<bool>
+            + 1 // executed: bool
+            + 4 // owner_set_seqno: u32
+            + 1 // bump: u8
+    }
+}
+
+/// An account meta of a proposed instruction.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ProposalAccount {
+    /// The account's address.
+    pub pubkey: Pubkey,
+    /// Whether the account must sign (e.g. the multisig's signer PDA).
+    pub is_signer: bool,
+    /// Whether the account is writable.
+    pub is_writable: bool,
+}
+
+impl ProposalAccount {
+    /// Size of one ProposalAccount struct in bytes.
+    pub const SIZE: usize = 32 + 1 + 1; // pubkey: Pubkey + is_signer: bool + is_writable: bool
+}
+
+impl From<&ProposalAccount> for AccountMeta {
+    fn from(account: &ProposalAccount) -> Self {
+        AccountMeta {
+            pubkey: account.pubkey,
+            is_signer: account.is_signer,
+            is_writable: account.is_writable,
+        }
+    }
+}
+
+/// Per-beneficiary counter of vesting accounts, so a wallet can hold several grants.
+#[account]
+pub struct BeneficiaryCounter {
+    /// The beneficiary whose grants are counted.
+    pub beneficiary: Pubkey,
+    /// Number of vesting accounts initialized for the beneficiary; the next grant's index.
+    pub grant_count: u32,
+    /// The bump seed used for the PDA derivation.
+    pub bump: u8,
+}
+
+impl BeneficiaryCounter {
+    /// Size of the account data, excluding the 8 byte discriminator.
+    pub const SIZE: usize = 32 // beneficiary: Pubkey
+        + 4 // grant_count: u32
+        + 1; // bump: u8
+
+    /// Assigns the next grant index to a new vesting account of `beneficiary` and advances
+    /// the counter. `bump` is the counter's PDA bump, stored on first use.
+    pub fn next_grant(&mut self, beneficiary: Pubkey, bump: u8) -> Result<u32> {
+        let grant_index = self.grant_count;
+        self.beneficiary = beneficiary;
+        self.grant_count = grant_index.checked_add(1).ok_or(VestingError::TooManyGrants)?;
+        self.bump = bump;
+        Ok(grant_index)
+    }
+}
+
+/// Global launch configuration. Vesting schedule offsets are resolved against `launch_time`.
+#[account]
+pub struct LaunchConfig {
+    /// The Unix timestamp of the token launch. Zero until set by the admin; frozen once set.
+    pub launch_time: u64,
+    /// The bump seed used for the PDA derivation.
+    pub bump: u8,
+}
+
+impl LaunchConfig {
+    /// Size of the account data, excluding the 8 byte discriminator.
+    pub const SIZE: usize = 8 // launch_time: u64
+        + 1; // bump: u8
+
+    /// Returns the launch time, failing if it has not been announced yet.
+    pub fn launch_time(&self) -> Result<u64> {
+        require!(self.launch_time > 0, VestingError::LaunchTimeNotSet);
+        Ok(self.launch_time)
+    }
+}
+
+/// A schedule template stored on-chain. `initialize_vesting` validates schedules against it.
+/// Seeds: "template", template ID (u32, little endian).
+#[account]
+pub struct ScheduleTemplate {
+    /// The template's ID, used in its PDA seeds and stored on vesting accounts.
+    pub template_id: u32,
+    /// Human-readable name, e.g. "Dev" or "Advisor".
+    pub name: String,
+    /// Whether schedules built from this template are cliffs or linear segments.
+    pub kind: ScheduleKind,
+    /// The cliffs each schedule must match (share and week). Empty for free-form templates.
+    pub tranches: Vec<TrancheTemplate>,
+    /// Whether the template is retired. Retired templates cannot be used for new grants.
+    pub retired: bool,
+    /// The bump seed used for the PDA derivation.
+    pub bump: u8,
+}
+
+impl ScheduleTemplate {
+    /// Maximum length of a template name, in bytes.
+    pub const MAX_NAME_LEN: usize = 32;
+
+    /// Size of the account data for a template of `num_tranches` tranches (excluding the discriminator).
+    pub fn space(num_tranches: usize) -> usize {
+        4 // template_id: u32
+            + (4 + Self::MAX_NAME_LEN) // name: String
+            + 1 // kind: ScheduleKind
+            + (4 + num_tranches * TrancheTemplate::SIZE) // tranches: Vec<TrancheTemplate>
+            + 1 // retired: bool
+            + 1 // bump: u8
+    }
+
+    /// Verifies a template definition: a non-empty name, and either no tranches (free-form)
+    /// or cliffs with positive shares summing to 100% at strictly increasing weeks, up to
+    /// `TrancheTemplate::MAX_WEEK`.
+    pub fn validate_definition(name: &str, kind: ScheduleKind, tranches: &[TrancheTemplate]) -> Result<()> {
+        require!(!name.is_empty() && name.len() <= Self::MAX_NAME_LEN, VestingError::InvalidTemplateName);
+        if tranches.is_empty() {
+            return Ok(());
+        }
+        // Tranches describe cliffs; linear templates are free-form.
+        require!(kind == ScheduleKind::Cliff, VestingError::InvalidTemplate);
+        require!(tranches.len() <= VestingAccount::MAX_SCHEDULES, VestingError::InvalidTemplate);
+
+        let mut total_bps = 0u64;
+        let mut last_week = 0u64;
+        for t in tranches {
+            require!(
+                t.bps > 0 && t.week > last_week && t.week <= TrancheTemplate::MAX_WEEK,
+                VestingError::InvalidTemplate
+            );
+            last_week = t.week;
+            total_bps += t.bps as u64;
+        }
+        require!(total_bps == BPS_DENOMINATOR, VestingError::InvalidTemplate);
+        Ok(())
+    }
+
+    /// Verifies that each cliff in `schedule` matches this template's tranches.
+    /// Free-form templates accept any schedule.
+    ///
+    /// Each cliff's amount must equal its share of `total_amount` (rounded down), with the
+    /// last cliff receiving the remainder. Each cliff's release offset must equal the
+    /// template's week offset from launch. Since schedules are offsets from launch, this pins
+    /// the cliffs to their weeks after launch rather than only checking their spacing from the
+    /// first release: a correctly spaced schedule shifted in time is rejected.
+    pub fn validate_schedule(&self, total_amount: u64, schedule: &[VestingSchedule]) -> Result<()> {
+        let template = &self.tranches;
+        if template.is_empty() {
+            return Ok(());
+        }
+        require!(schedule.len() == template.len(), VestingError::InvalidSchedule);
+
+        let mut allocated = 0u64; // Sum of the expected amounts of the previous cliffs
+        for (i, (s, t)) in schedule.iter().zip(template).enumerate() {
+            // Expected amount: bps share of the total, or the remainder for the last cliff.
+            let expected_amount = if i == template.len() - 1 {
+                total_amount.checked_sub(allocated).ok_or(VestingError::InvalidTrancheAmount)?
+            } else {
+                t.share_of(total_amount)?
+            };
+            require!(s.amount == expected_amount, VestingError::InvalidTrancheAmount);
+            allocated = allocated.checked_add(expected_amount).ok_or(VestingError::InvalidTrancheAmount)?;
+
+            // Expected offset from launch, in seconds.
+            let expected_offset = t.week.checked_mul(SECONDS_PER_WEEK).ok_or(VestingError::InvalidTemplate)?;
+            require!(s.release_offset == expected_offset, VestingError::InvalidTrancheOffset);
+        }
+
+        Ok(())
+    }
+
+    /// Builds the cliff schedule for `total_amount` tokens following this template: each
+    /// cliff gets its share (rounded down) and the last cliff the remainder, so the result
+    /// passes `validate_schedule`. Free-form templates have no schedule to build.
+    pub fn build_schedule(&self, total_amount: u64) -> Result<Vec<VestingSchedule>> {
+        require!(!self.tranches.is_empty(), VestingError::InvalidTemplate);
+        let mut allocated = 0u64;
+        let mut schedule = Vec::with_capacity(self.tranches.len());
+        for (i, t) in self.tranches.iter().enumerate() {
+            let amount = if i == self.tranches.len() - 1 {
+                total_amount.checked_sub(allocated).ok_or(VestingError::InvalidTrancheAmount)?
+            } else {
+                t.share_of(total_amount)?
+            };
+            allocated = allocated.checked_add(amount).ok_or(VestingError::InvalidTrancheAmount)?;
+            let offset = t.week.checked_mul(SECONDS_PER_WEEK).ok_or(VestingError::InvalidTemplate)?;
+            schedule.push(VestingSchedule { release_offset: offset, amount, end_offset: offset, cliff_offset: offset });
+        }
+        Ok(schedule)
+    }
+}
+
+/// A schedule template compiled into the program, created on-chain by `create_builtin_template`.
+pub struct BuiltinTemplate {
+    pub name: &'static str,
+    pub kind: ScheduleKind,
+    pub tranches: &'static [TrancheTemplate],
+}
+
+/// The built-in schedule templates, indexed by template ID.
+pub const BUILTIN_TEMPLATES: [BuiltinTemplate; 5] = [
+    // 0: 5% at 2w, 15% at 24w, 30% at 30w, 50% at 36w
+    BuiltinTemplate {
+        name: "Dev",
+        kind: ScheduleKind::Cliff,
+        tranches: &[
+            TrancheTemplate { bps: 500, week: 2 },
+            TrancheTemplate { bps: 1_500, week: 24 },
+            TrancheTemplate { bps: 3_000, week: 30 },
+            TrancheTemplate { bps: 5_000, week: 36 },
+        ],
+    },
+    // 1: 10% at 2w, 15% at 6w, 25% at 10w, 50% at 14w
+    BuiltinTemplate {
+        name: "Marketing",
+        kind: ScheduleKind::Cliff,
+        tranches: &[
+            TrancheTemplate { bps: 1_000, week: 2 },
+            TrancheTemplate { bps: 1_500, week: 6 },
+            TrancheTemplate { bps: 2_500, week: 10 },
+            TrancheTemplate { bps: 5_000, week: 14 },
+        ],
+    },
+    // 2: 50% at 4w, 50% at 8w
+    BuiltinTemplate {
+        name: "Presale1",
+        kind: ScheduleKind::Cliff,
+        tranches: &[TrancheTemplate { bps: 5_000, week: 4 }, TrancheTemplate { bps: 5_000, week: 8 }],
+    },
+    // 3: 100% at 12w
+    BuiltinTemplate {
+        name: "Presale2",
+        kind: ScheduleKind::Cliff,
+        tranches: &[TrancheTemplate { bps: 10_000, week: 12 }],
+    },
+    // 4: Free-form linear grants
+    BuiltinTemplate {
+        name: "Contributor",
+        kind: ScheduleKind::Linear,
+        tranches: &[],
+    },
+];
+
+/// Number of seconds in one week, the unit of template offsets.
+pub const SECONDS_PER_WEEK: u64 = 7 * 24 * 60 * 60;
+
+/// Basis points denominator (10_000 bps = 100%).
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// A single cliff of a schedule template.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct TrancheTemplate {
+    /// Share of the total amount unlocked by this cliff, in basis points.
+    pub bps: u16,
+    /// Week (after launch) at which this cliff unlocks.
+    pub week: u64,
+}
+
+impl TrancheTemplate {
+    /// Latest week a tranche can unlock at: ten years after launch.
+    pub const MAX_WEEK: u64 = 520;
+
+    /// Serialized size of a tranche.
+    pub const SIZE: usize = 2 // bps: u16
+        + 8; // week: u64
+
+    /// Returns this cliff's share of `total_amount`, rounded down.
+    pub fn share_of(&self, total_amount: u64) -> Result<u64> {
+        let share = (total_amount as u128) * (self.bps as u128) / (BPS_DENOMINATOR as u128);
+        u64::try_from(share).map_err(|_| error!(VestingError::InvalidTrancheAmount))
+    }
+}
+
+/// Kind of entries a vesting schedule is made of.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ScheduleKind {
+    /// Each entry unlocks its whole amount at `release_offset`.
+    Cliff,
+    /// Each entry unlocks its amount linearly from `release_offset` to `end_offset`,
+    /// with nothing unlocked before `cliff_offset`.
+    Linear,
+}
 
-    /// The Solana System Program, required for creating accounts (`init`).
-    pub system_program: Program<'info, System>,
+/// Represents a single vesting cliff or linear segment in the schedule.
+/// All offsets are seconds after the launch time (`LaunchConfig::launch_time`).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct VestingSchedule {
+    /// Offset when the amount becomes unlocked (cliff) or starts unlocking (linear).
+    pub release_offset: u64,
+    /// The amount of tokens unlocked by this entry.
+    pub amount: u64,
+    /// Offset when the whole amount has unlocked. Equal to `release_offset` for cliffs.
+    pub end_offset: u64,
+    /// Offset before which nothing unlocks; the amount accrued so far unlocks at once when it passes.
+    /// Equal to `release_offset` for cliffs and for linear segments without a cliff.
+    pub cliff_offset: u64,
+}
 
-    /// The SPL Token Program, required for token operations (transfer, ATA init).
-    pub token_program: Program<'info, Token>,
+impl VestingSchedule {
+    /// Size of one VestingSchedule struct in bytes.
+    pub const SIZE: usize = 8 + 8 + 8 + 8; // release_offset, amount, end_offset, cliff_offset: u64
 
-    /// The SPL Associated Token Account Program, required for initializing the `vesting_token_account`.
-    pub associated_token_program: Program<'info, AssociatedToken>,
+    /// Verifies that the entry's offsets describe a valid entry of the given kind.
+    pub fn validate_shape(&self, kind: ScheduleKind) -> Result<()> {
+        match kind {
+            ScheduleKind::Cliff => require!(
+                self.end_offset == self.release_offset && self.cliff_offset == self.release_offset,
+                VestingError::InvalidScheduleKind
+            ),
+            ScheduleKind::Linear => require!(
+                self.release_offset < self.end_offset
+                    && self.release_offset <= self.cliff_offset
+                    && self.cliff_offset <= self.end_offset,
+                VestingError::InvalidScheduleKind
+            ),
+        }
+        Ok(())
+    }
+
+    /// Returns the amount unlocked by this entry at time `now`.
+    ///
+    /// Nothing unlocks before the cliff, the full amount unlocks at the end, and in between
+    /// the amount unlocks proportionally to the time elapsed since the start. Cliffs (where
+    /// start, cliff and end coincide) unlock fully at the release time.
+    pub fn unlocked_amount(&self, now: u64, launch_time: u64) -> u64 {
+        let start = launch_time.saturating_add(self.release_offset);
+        let cliff = launch_time.saturating_add(self.cliff_offset);
+        let end = launch_time.saturating_add(self.end_offset);
+        if now < cliff {
+            0
+        } else if now >= end {
+            self.amount
+        } else {
+            // Here start <= cliff <= now < end, so the duration is non-zero.
+            // Multiply in u128 so `amount * elapsed` cannot overflow; the result is < amount.
+            let elapsed = (now - start) as u128;
+            let duration = (end - start) as u128;
+            ((self.amount as u128) * elapsed / duration) as u64
+        }
+    }
 }
 
-/// Defines the accounts required for the `claim_unlocked` instruction.
-#[derive(Accounts)]
-pub struct ClaimUnlocked<'info> {
-    /// The vesting account PDA containing the schedule and state. Mutable because `claimed_amount` is updated.
-    /// `has_one = authority` constraint ensures the `authority` signer matches the one stored in the account.
-    #[account(mut, has_one = authority @ VestingError::InvalidAuthority)]
-    pub vesting_account: Account<'info, VestingAccount>,
-    /// The beneficiary of the vesting schedule, must be a signer to authorize the claim.
-    pub authority: Signer<'info>,
-    /// The PDA's SPL token account holding the vested tokens. Mutable because its balance decreases.
-    /// constraint = vesting_token_account.mint == vesting_account.mint,
-    /// constraint = vesting_token_account.owner == vesting_signer.key() // Checked manually in handler
-    #[account(mut)]
-    pub vesting_token_account: Account<'info, TokenAccount>,
-    /// The beneficiary's SPL token account where claimed tokens will be deposited. Mutable because its balance increases.
-    /// constraint = destination_token_account.mint == vesting_account.mint @ VestingError::InvalidMint
-    #[account(mut)]
-    pub destination_token_account: Account<'info, TokenAccount>,
-    /// The vesting account PDA, required as the authority for the token transfer CPI.
-    /// CHECK: This account is not deserialized. Its key is checked against the expected PDA
-    /// derived from seeds (`vesting_account.authority`, `vesting_account.bump`) within the handler logic.
-    #[account(
-        seeds = [b"vesting", vesting_account.authority.as_ref()], // Ensure seeds match for Anchor's internal checks if needed
-        bump = vesting_account.bump
-    )]
-    /// CHECK: PDA signer for vesting account
-    pub vesting_signer: UncheckedAccount<'info>,
-    /// The SPL Token Program, required for the token transfer CPI.
-    pub token_program: Program<'info, Token>,
+/// Presale configuration: the token sold and each phase's batch size, prices and template.
+/// Seeds: "presale". The PDA owns the token vault and the stablecoin vault.
+#[account]
+pub struct Presale {
+    /// The mint of the token being sold.
+    pub mint: Pubkey,
+    /// The stablecoin accepted as payment. `Pubkey::default()` when only SOL is accepted.
+    pub stable_mint: Pubkey,
+    /// The primary phase (1M tokens for $250, Presale1 schedule).
+    pub primary: PresalePhaseConfig,
+    /// The secondary phase (500k tokens for $150, Presale2 schedule).
+    pub secondary: PresalePhaseConfig,
+    /// The primary phase's sale window and tokens sold.
+    pub primary_state: PresalePhaseState,
+    /// The secondary phase's sale window and tokens sold. Opens only once the primary phase
+    /// has sold out or closed.
+    pub secondary_state: PresalePhaseState,
+    /// The bump seed used for the PDA derivation.
+    pub bump: u8,
+    /// The bump seed of the SOL vault PDA ("presale_vault").
+    pub sol_vault_bump: u8,
 }
 
-/// Defines the accounts required for the `admin_distribute_unlocked` instruction.
-/// Similar to ClaimUnlocked but doesn't require the authority (beneficiary) to sign.
-/// Instead, it requires the admin to sign.
-#[derive(Accounts)]
-pub struct AdminDistribute<'info> {
-    /// The admin account (must match ADMIN_PUBKEY)
-    #[account(address = Pubkey::from_str(ADMIN_PUBKEY).unwrap() @ VestingError::Unauthorized)]
-    pub admin: Signer<'info>,
-    
-    /// The vesting account PDA containing the schedule and state. Mutable because `claimed_amount` is updated.
-    #[account(mut)]
-    pub vesting_account: Account<'info, VestingAccount>,
-    
-    /// The beneficiary who will receive the tokens
-    /// CHECK: This account is only used for reference and verification
-    #[account(constraint = authority.key() == vesting_account.authority @ VestingError::InvalidAuthority)]
-    pub authority: UncheckedAccount<'info>,
-    
-    /// The PDA's SPL token account holding the vested tokens. Mutable because its balance decreases.
-    #[account(mut,
-        constraint = vesting_token_account.mint == vesting_account.mint @ VestingError::InvalidMint,
-        constraint = vesting_token_account.owner == vesting_signer.key() @ VestingError::InvalidOwner
-    )]
-    pub vesting_token_account: Account<'info, TokenAccount>,
-    
-    /// The beneficiary's SPL token account where claimed tokens will be deposited. Mutable because its balance increases.
-    #[account(mut,
-        constraint = destination_token_account.mint == vesting_account.mint @ VestingError::InvalidMint,
-        constraint = destination_token_account.owner == vesting_account.authority @ VestingError::InvalidAuthority
-    )]
-    pub destination_token_account: Account<'info, TokenAccount>,
-    
-    /// The vesting account PDA, required as the authority for the token transfer CPI.
-    #[account(
-        seeds = [b"vesting", vesting_account.authority.as_ref()],
-        bump = vesting_account.bump
-    )]
-    /// CHECK: PDA signer for vesting account
-    pub vesting_signer: UncheckedAccount<'info>,
-    
-    /// The SPL Token Program, required for the token transfer CPI.
-    pub token_program: Program<'info, Token>,
+impl Presale {
+    /// Size of the account data, excluding the 8 byte discriminator.
+    pub const SIZE: usize = 32 // mint: Pubkey
+        + 32 // stable_mint: Pubkey
+        + PresalePhaseConfig::SIZE // primary: PresalePhaseConfig
+        + PresalePhaseConfig::SIZE // secondary: PresalePhaseConfig
+        + PresalePhaseState::SIZE // primary_state: PresalePhaseState
+        + PresalePhaseState::SIZE // secondary_state: PresalePhaseState
+        + 1 // bump: u8
+        + 1; // sol_vault_bump: u8
+
+    /// Returns the configuration of `phase`.
+    pub fn phase(&self, phase: PresalePhase) -> &PresalePhaseConfig {
+        match phase {
+            PresalePhase::Primary => &self.primary,
+            PresalePhase::Secondary => &self.secondary,
+        }
+    }
+
+    /// Returns the sale window and tokens sold of `phase`.
+    pub fn state(&self, phase: PresalePhase) -> &PresalePhaseState {
+        match phase {
+            PresalePhase::Primary => &self.primary_state,
+            PresalePhase::Secondary => &self.secondary_state,
+        }
+    }
+
+    /// Returns the sale window and tokens sold of `phase`, mutably.
+    pub fn state_mut(&mut self, phase: PresalePhase) -> &mut PresalePhaseState {
+        match phase {
+            PresalePhase::Primary => &mut self.primary_state,
+            PresalePhase::Secondary => &mut self.secondary_state,
+        }
+    }
+
+    /// Returns whether `phase` has sold out, i.e. another batch would exceed its cap.
+    pub fn sold_out(&self, phase: PresalePhase) -> bool {
+        let config = self.phase(phase);
+        self.state(phase).sold.saturating_add(config.tokens_per_batch) > config.cap
+    }
+
+    /// Validates and stores the accepted stablecoin and the phase configurations.
+    /// Stablecoin prices are only required when a stablecoin is accepted. A phase's cap
+    /// cannot drop below what it has already sold.
+    pub fn configure(
+        &mut self,
+        stable_mint: Pubkey,
+        primary: PresalePhaseConfig,
+        secondary: PresalePhaseConfig,
+    ) -> Result<()> {
+        for (p, state) in [(&primary, &self.primary_state), (&secondary, &self.secondary_state)] {
+            require!(
+                p.tokens_per_batch > 0
+                    && p.price_lamports > 0
+                    && (p.price_stable > 0 || stable_mint == Pubkey::default())
+                    && p.cap >= p.tokens_per_batch
+                    && p.cap >= state.sold
+                    && p.duration > 0,
+                VestingError::InvalidPresaleConfig
+            );
+        }
+        self.stable_mint = stable_mint;
+        self.primary = primary;
+        self.secondary = secondary;
+        Ok(())
+    }
 }
 
-/// Represents the state of a vesting schedule stored in the PDA.
+/// Batch size, prices, cap, window length and vesting template of a presale phase.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct PresalePhaseConfig {
+    /// Tokens vested per batch, in base units.
+    pub tokens_per_batch: u64,
+    /// Hard cap on the tokens sold in the phase, in base units (100M primary, 50M secondary).
+    pub cap: u64,
+    /// Length of the sale window once the phase opens, in seconds (2 weeks for the secondary).
+    pub duration: i64,
+    /// Price of a batch in lamports.
+    pub price_lamports: u64,
+    /// Price of a batch in base units of the stablecoin.
+    pub price_stable: u64,
+    /// The cliff template the batch's vesting schedule is built from.
+    pub template_id: u32,
+}
+
+impl PresalePhaseConfig {
+    /// Serialized size of a phase configuration.
+    pub const SIZE: usize = 8 // tokens_per_batch: u64
+        + 8 // cap: u64
+        + 8 // duration: i64
+        + 8 // price_lamports: u64
+        + 8 // price_stable: u64
+        + 4; // template_id: u32
+}
+
+/// Sale window and progress of a presale phase.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default)]
+pub struct PresalePhaseState {
+    /// Unix timestamp the sale opens at. Zero until the phase is opened by the admin.
+    pub start_time: i64,
+    /// Unix timestamp the sale closes at (exclusive). Moved forward if the admin closes early.
+    pub end_time: i64,
+    /// Tokens sold so far, in base units.
+    pub sold: u64,
+}
+
+impl PresalePhaseState {
+    /// Serialized size of a phase state.
+    pub const SIZE: usize = 8 // start_time: i64
+        + 8 // end_time: i64
+        + 8; // sold: u64
+
+    /// Returns whether the phase has been opened and its window has ended.
+    pub fn closed(&self, now: i64) -> bool {
+        self.start_time > 0 && now >= self.end_time
+    }
+}
+
+/// Phase of the presale a batch is bought from.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PresalePhase {
+    /// 1M tokens for $250, vesting on the Presale1 schedule.
+    Primary,
+    /// 500k tokens for $150, vesting on the Presale2 schedule.
+    Secondary,
+}
+
+impl PresalePhase {
+    /// Returns the phase as a PDA seed.
+    pub fn seed(self) -> [u8; 1] {
+        [self as u8]
+    }
+}
+
+/// Record of a wallet's presale purchase in one phase. Its existence blocks a second purchase.
+/// Seeds: "receipt", phase (u8), buyer pubkey.
 #[account]
-pub struct VestingAccount {
-    /// The public key of the beneficiary who can claim the tokens. Used in `has_one` and PDA seeds.
-    pub authority: Pubkey,
-    /// The mint address of the SPL token being vested.
+pub struct PresaleReceipt {
+    /// The wallet that bought the batch.
+    pub buyer: Pubkey,
+    /// The phase the batch was bought from.
+    pub phase: PresalePhase,
+    /// The vesting account opened for the batch.
+    pub vesting_account: Pubkey,
+    /// The stablecoin paid with, or `Pubkey::default()` for SOL.
+    pub payment_mint: Pubkey,
+    /// The price paid, in lamports or stablecoin base units.
+    pub paid_amount: u64,
+    /// Tokens vested for the batch.
+    pub tokens: u64,
+    /// Unix timestamp of the purchase.
+    pub timestamp: i64,
+    /// The bump seed used for the PDA derivation.
+    pub bump: u8,
+}
+
+impl PresaleReceipt {
+    /// Size of the account data, excluding the 8 byte discriminator.
+    pub const SIZE: usize = 32 // buyer: Pubkey
+        + 1 // phase: PresalePhase
+        + 32 // vesting_account: Pubkey
+        + 32 // payment_mint: Pubkey
+        + 8 // paid_amount: u64
+        + 8 // tokens: u64
+        + 8 // timestamp: i64
+        + 1; // bump: u8
+}
+
+/// A Merkle distributor of allocations. Each leaf (see `merkle::leaf_hash`) lets a wallet
+/// claim an amount on a schedule template once, tracked by `claimed_bitmap`.
+/// Seeds: "distributor", distributor ID (u32, little endian). The PDA owns the vault.
+#[account]
+pub struct Distributor {
+    /// The distributor's ID, used in its PDA seeds.
+    pub distributor_id: u32,
+    /// The mint of the distributed token.
     pub mint: Pubkey,
-    /// The total amount of tokens initially locked in the schedule.
+    /// The Merkle root of the allocation leaves.
+    pub root: [u8; 32],
+    /// The number of leaves in the tree.
+    pub num_leaves: u32,
+    /// The sum of all leaves' amounts, funded into the vault at creation.
     pub total_amount: u64,
-    /// The amount of tokens already claimed by the beneficiary.
+    /// The sum of the claimed leaves' amounts.
     pub claimed_amount: u64,
-    /// The vector containing individual vesting cliffs (time and amount).
-    pub schedule: Vec<VestingSchedule>,
-    /// The type of wallet this schedule corresponds to (e.g., Dev, Marketing).
-    pub wallet_type: WalletType,
-    /// The bump seed used for the PDA derivation. Required for CPI signing.
+    /// One bit per leaf, set once the leaf is claimed.
+    pub claimed_bitmap: Vec<u8>,
+    /// The bump seed used for the PDA derivation.
     pub bump: u8,
 }
 
-impl VestingAccount {
-    /// Maximum number of schedule entries allowed (adjust based on longest schedule type).
-    pub const MAX_SCHEDULES: usize = 4; // e.g., Dev/Marketing schedule length
-    /// Calculate the maximum size required for the account, including discriminator (8 bytes).
-    pub const MAX_SIZE: usize = 8 // Discriminator
-        + 32 // authority: Pubkey
-        + 32 // mint: Pubkey
-        + 8  // total_amount: u64
-        + 8  // claimed_amount: u64
-        + (4 + Self::MAX_SCHEDULES * VestingSchedule::SIZE) // schedule: Vec<VestingSchedule> (4 bytes for len + size per item)
-        + 1 // wallet_type: WalletType (assuming enum size 1)
+impl Distributor {
+    /// Maximum number of leaves, keeping the account under the 10 KiB limit of accounts
+    /// created by a program (an 8 KiB bitmap).
+    pub const MAX_LEAVES: usize = 65_536;
+
+    /// Length of the claim bitmap for `num_leaves` leaves, in bytes.
+    pub fn bitmap_len(num_leaves: u32) -> usize {
+        (num_leaves as usize).div_ceil(8)
+    }
+
+    /// Size of the account data for `num_leaves` leaves (excluding the discriminator).
+    pub fn space(num_leaves: u32) -> usize {
+        4 // distributor_id: u32
+            + 32 // mint: Pubkey
+            + 32 // root: [u8; 32]
+            + 4 // num_leaves: u32
+            + 8 // total_amount: u64
+            + 8 // claimed_amount: u64
+            + (4 + Self::bitmap_len(num_leaves)) // claimed_bitmap: Vec<u8>
+            + 1 // bump: u8
+    }
+
+    /// Verifies `proof` for the leaf and marks it claimed.
+    ///
+    /// Fails if the index is out of range, the leaf was already claimed, the proof does not
+    /// lead to the root, or the claims would exceed the funded total.
+    pub fn claim_leaf(
+        &mut self,
+        index: u32,
+        wallet: &Pubkey,
+        amount: u64,
+        template_id: u32,
+        proof: &[[u8; 32]],
+    ) -> Result<()> {
+        require!(index < self.num_leaves, VestingError::InvalidLeafIndex);
+        let (byte, mask) = (index as usize / 8, 1u8 << (index % 8));
+        require!(self.claimed_bitmap[byte] & mask == 0, VestingError::AllocationAlreadyClaimed);
+
+        let leaf = merkle::leaf_hash(index, wallet, amount, template_id);
+        require!(merkle::verify(proof, &self.root, leaf), VestingError::InvalidMerkleProof);
+
+        let claimed_amount = self.claimed_amount.checked_add(amount).ok_or(VestingError::DistributorOverdrawn)?;
+        require!(claimed_amount <= self.total_amount, VestingError::DistributorOverdrawn);
+        self.claimed_amount = claimed_amount;
+        self.claimed_bitmap[byte] |= mask;
+        Ok(())
+    }
+}
+
+/// Tokens set aside for staking rewards, which revert to the community if staking is not
+/// live by `deadline`. Seeds: "staking_reserve". The PDA owns the vault.
+#[account]
+pub struct StakingReserve {
+    /// The mint of the reserved token.
+    pub mint: Pubkey,
+    /// The number of tokens funded into the vault at init.
+    pub amount: u64,
+    /// Unix timestamp until which the reserve can be moved to staking, and from which it can
+    /// be released to `release_account`.
+    pub deadline: i64,
+    /// The community/treasury token account receiving the reserve after the deadline.
+    pub release_account: Pubkey,
+    /// Whether the reserve is still held, or where it went.
+    pub status: StakingReserveStatus,
+    /// The bump seed used for the PDA derivation.
+    pub bump: u8,
+}
+
+impl StakingReserve {
+    /// Time from init to the deadline: nine months (273 days).
+    pub const DEADLINE_PERIOD: i64 = 273 * 24 * 60 * 60;
+
+    /// Size of the account data, excluding the 8 byte discriminator.
+    pub const SIZE: usize = 32 // mint: Pubkey
+        + 8 // amount: u64
+        + 8 // deadline: i64
+        + 32 // release_account: Pubkey
+        + 1 // status: StakingReserveStatus
         + 1; // bump: u8
 }
 
-/// Enum defining different types of vesting schedules (e.g., based on recipient role).
-/// Used to enforce specific schedule lengths during initialization.
+/// Status of the staking reserve. Both settled states are final.
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
-pub enum WalletType {
-    Dev,
-    Marketing,
-    Presale1,
-    Presale2,
+pub enum StakingReserveStatus {
+    /// The vault holds the reserve.
+    Funded,
+    /// The reserve was moved to the staking reward vault before the deadline.
+    MovedToStaking,
+    /// The reserve was released to the community account after the deadline.
+    Released,
 }
 
-/// Represents a single vesting cliff in the schedule.
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
-pub struct VestingSchedule {
-    /// The Unix timestamp (seconds since epoch) when the amount becomes unlocked.
-    pub release_time: u64,
-    /// The amount of tokens unlocked at the `release_time`.
+/// Configuration of merchant payments. Seeds: "payment_config".
+#[account]
+pub struct PaymentConfig {
+    /// The mint accepted by `pay_merchant`.
+    pub mint: Pubkey,
+    /// The protocol fee sent to the treasury, in basis points of each payment, per fee tier.
+    pub fee_tiers: [u16; PaymentConfig::NUM_FEE_TIERS],
+    /// The wallet allowed to set merchants' KYC status.
+    pub attestor: Pubkey,
+    /// The bump seed used for the PDA derivation.
+    pub bump: u8,
+}
+
+impl PaymentConfig {
+    /// Maximum protocol fee: 10%.
+    pub const MAX_FEE_BPS: u16 = 1_000;
+
+    /// Number of fee tiers merchants can be placed on.
+    pub const NUM_FEE_TIERS: usize = 4;
+
+    /// Maximum length of an order ID, in bytes.
+    pub const MAX_ORDER_ID_LEN: usize = 64;
+
+    /// Size of the account data, excluding the 8 byte discriminator.
+    pub const SIZE: usize = 32 // mint: Pubkey
+        + 2 * Self::NUM_FEE_TIERS // fee_tiers: [u16; NUM_FEE_TIERS]
+        + 32 // attestor: Pubkey
+        + 1; // bump: u8
+
+    /// Returns the protocol fee on a payment of `amount` to a merchant on `fee_tier`,
+    /// rounded down. Fails with `InvalidFeeTier` for an unknown tier rather than waiving the fee.
+    pub fn fee_for(&self, fee_tier: u8, amount: u64) -> Result<u64> {
+        let fee_bps = *self
+            .fee_tiers
+            .get(fee_tier as usize)
+            .ok_or_else(|| error!(VestingError::InvalidFeeTier))?;
+        Ok(((amount as u128) * (fee_bps as u128) / (BPS_DENOMINATOR as u128)) as u64)
+    }
+}
+
+/// A merchant accepting `pay_merchant` payments. Seeds: "merchant", owner pubkey.
+#[account]
+pub struct Merchant {
+    /// The wallet that registered the merchant.
+    pub owner: Pubkey,
+    /// The token account payments settle into.
+    pub settlement_account: Pubkey,
+    /// The SHA-256 hash of the merchant's display name.
+    pub name_hash: [u8; 32],
+    /// The merchant's index in `PaymentConfig::fee_tiers`. Set by the admin.
+    pub fee_tier: u8,
+    /// The merchant's KYC status. Set by the KYC attestor (`PaymentConfig::attestor`).
+    pub kyc_status: KycStatus,
+    /// Whether the admin has suspended the merchant.
+    pub suspended: bool,
+    /// The bump seed used for the PDA derivation.
+    pub bump: u8,
+}
+
+impl Merchant {
+    /// Size of the account data, excluding the 8 byte discriminator.
+    pub const SIZE: usize = 32 // owner: Pubkey
+        + 32 // settlement_account: Pubkey
+        + 32 // name_hash: [u8; 32]
+        + 1 // fee_tier: u8
+        + 1 // kyc_status: KycStatus
+        + 1 // suspended: bool
+        + 1; // bump: u8
+}
+
+/// KYC status of a merchant. Only `Verified` merchants can be paid.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum KycStatus {
+    /// Not yet checked by the attestor.
+    Unverified,
+    /// Passed the attestor's KYC check.
+    Verified,
+    /// Failed the attestor's KYC check.
+    Rejected,
+}
+
+// --- Events ---
+// Emitted alongside the `msg!` logs so off-chain consumers can decode vesting state
+// changes from the IDL. Timestamps are the cluster's Unix time when the instruction ran.
+
+/// Emitted by `initialize_vesting` when a new vesting account is created and funded.
+#[event]
+pub struct VestingInitialized {
+    pub vesting_account: Pubkey,
+    pub beneficiary: Pubkey,
+    pub mint: Pubkey,
+    pub template_id: u32,
+    pub grant_index: u32,
+    pub total_amount: u64,
+    pub revocable: bool,
+    pub timestamp: i64,
+}
+
+/// Emitted by `top_up_vesting`.
+#[event]
+pub struct VestingToppedUp {
+    pub vesting_account: Pubkey,
+    pub beneficiary: Pubkey,
+    pub mint: Pubkey,
+    pub template_id: u32,
+    /// Tokens added by this top-up.
     pub amount: u64,
+    /// Number of tranches appended to the schedule.
+    pub tranche_count: u32,
+    /// The new total of the schedule.
+    pub total_amount: u64,
+    pub timestamp: i64,
 }
 
-impl VestingSchedule {
-    /// Size of one VestingSchedule struct in bytes.
-    pub const SIZE: usize = 8 + 8; // release_time: u64 + amount: u64
+/// Emitted by `claim_unlocked` and `claim_amount`.
+#[event]
+pub struct TokensClaimed {
+    pub vesting_account: Pubkey,
+    pub beneficiary: Pubkey,
+    pub mint: Pubkey,
+    pub template_id: u32,
+    /// Tokens released from the schedule by this claim.
+    pub amount: u64,
+    /// Tokens credited to the destination; less than `amount` under a transfer-fee mint.
+    pub received_amount: u64,
+    /// Cumulative tokens claimed from the vesting account, including this claim.
+    pub claimed_amount: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted by `admin_distribute_unlocked`.
+#[event]
+pub struct AdminDistributed {
+    pub vesting_account: Pubkey,
+    pub admin: Pubkey,
+    pub beneficiary: Pubkey,
+    pub mint: Pubkey,
+    pub template_id: u32,
+    /// Tokens released from the schedule by this distribution.
+    pub amount: u64,
+    /// Tokens credited to the destination; less than `amount` under a transfer-fee mint.
+    pub received_amount: u64,
+    /// Cumulative tokens claimed from the vesting account, including this distribution.
+    pub claimed_amount: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted by `revoke_vesting`.
+#[event]
+pub struct VestingRevoked {
+    pub vesting_account: Pubkey,
+    pub beneficiary: Pubkey,
+    pub mint: Pubkey,
+    pub template_id: u32,
+    /// Unlocked tokens paid out to the beneficiary.
+    pub paid_amount: u64,
+    /// Tokens credited to the beneficiary; less than `paid_amount` under a transfer-fee mint.
+    pub received_amount: u64,
+    /// Unvested tokens returned to the treasury.
+    pub returned_amount: u64,
+    /// Tokens credited to the treasury; less than `returned_amount` under a transfer-fee mint.
+    pub returned_received_amount: u64,
+    /// Cumulative tokens claimed from the vesting account, including the payout.
+    pub claimed_amount: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted by `propose_authority_transfer`.
+#[event]
+pub struct AuthorityTransferProposed {
+    pub vesting_account: Pubkey,
+    pub authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub mint: Pubkey,
+    pub template_id: u32,
+    pub timestamp: i64,
+}
+
+/// Emitted by `accept_authority_transfer`.
+#[event]
+pub struct AuthorityTransferred {
+    pub vesting_account: Pubkey,
+    pub previous_authority: Pubkey,
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    pub template_id: u32,
+    pub timestamp: i64,
+}
+
+/// Emitted by `close_vesting`.
+#[event]
+pub struct VestingClosed {
+    pub vesting_account: Pubkey,
+    pub beneficiary: Pubkey,
+    pub mint: Pubkey,
+    pub template_id: u32,
+    /// Cumulative tokens claimed over the account's lifetime.
+    pub claimed_amount: u64,
+    /// Whether the account was revoked rather than fully claimed.
+    pub terminated: bool,
+    pub closed_by: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Emitted by `initialize_presale` and `update_presale`.
+#[event]
+pub struct PresaleConfigured {
+    pub mint: Pubkey,
+    pub stable_mint: Pubkey,
+    pub primary: PresalePhaseConfig,
+    pub secondary: PresalePhaseConfig,
+    pub admin: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Emitted by `buy_presale_batch`, after the `VestingInitialized` event of the new vesting account.
+#[event]
+pub struct PresaleBatchPurchased {
+    pub buyer: Pubkey,
+    pub phase: PresalePhase,
+    pub vesting_account: Pubkey,
+    pub mint: Pubkey,
+    pub template_id: u32,
+    /// Tokens vested for the batch.
+    pub tokens: u64,
+    /// The stablecoin paid with, or `Pubkey::default()` for SOL.
+    pub payment_mint: Pubkey,
+    /// The price paid, in lamports or stablecoin base units.
+    pub paid_amount: u64,
+    /// Tokens sold in the phase, including this batch.
+    pub phase_sold: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted by `open_presale_phase`.
+#[event]
+pub struct PresalePhaseOpened {
+    pub phase: PresalePhase,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub cap: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted by `close_presale_phase`.
+#[event]
+pub struct PresalePhaseClosed {
+    pub phase: PresalePhase,
+    /// Tokens sold in the phase.
+    pub sold: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted by `create_distributor`.
+#[event]
+pub struct DistributorCreated {
+    pub distributor: Pubkey,
+    pub distributor_id: u32,
+    pub mint: Pubkey,
+    pub root: [u8; 32],
+    pub num_leaves: u32,
+    pub total_amount: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted by `claim_distributor_vesting` (after `VestingInitialized`) and `claim_distributor_allocation`.
+#[event]
+pub struct DistributorClaimed {
+    pub distributor: Pubkey,
+    /// The claimed leaf's index.
+    pub index: u32,
+    pub claimant: Pubkey,
+    pub amount: u64,
+    pub template_id: u32,
+    /// The vesting account opened for the allocation, or `None` for a direct claim.
+    pub vesting_account: Option<Pubkey>,
+    /// Tokens credited to the vesting or destination token account.
+    pub received_amount: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted by `withdraw_presale_proceeds`.
+#[event]
+pub struct PresaleProceedsWithdrawn {
+    pub lamports: u64,
+    pub stable_mint: Pubkey,
+    pub stable_amount: u64,
+    pub admin: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Emitted by `stake_vesting`.
+#[event]
+pub struct VestingStaked {
+    pub vesting_account: Pubkey,
+    pub beneficiary: Pubkey,
+    /// The staking position opened for the vesting PDA.
+    pub position: Pubkey,
+    pub amount: u64,
+    /// Total tokens of the vesting account staked after this stake.
+    pub staked_amount: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted by `claim_vesting_stake_rewards`.
+#[event]
+pub struct VestingStakeRewardsClaimed {
+    pub vesting_account: Pubkey,
+    pub beneficiary: Pubkey,
+    pub position: Pubkey,
+    pub reward: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted by `unstake_vesting`.
+#[event]
+pub struct VestingUnstaked {
+    pub vesting_account: Pubkey,
+    pub beneficiary: Pubkey,
+    pub position: Pubkey,
+    /// Tokens returned to the vesting token account.
+    pub amount: u64,
+    /// Unclaimed reward paid to the beneficiary.
+    pub reward: u64,
+    /// Total tokens of the vesting account still staked.
+    pub staked_amount: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted by `initialize_staking_reserve`.
+#[event]
+pub struct StakingReserveInitialized {
+    pub staking_reserve: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub deadline: i64,
+    pub release_account: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Emitted by `fund_staking_from_reserve` and `release_staking_reserve`.
+#[event]
+pub struct StakingReserveSettled {
+    pub staking_reserve: Pubkey,
+    /// `MovedToStaking` or `Released`.
+    pub status: StakingReserveStatus,
+    pub amount: u64,
+    /// The staking reward vault or the community account.
+    pub destination: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Emitted by `initialize_payment_config` (once per tier) and `update_payment_fee`.
+#[event]
+pub struct PaymentFeeUpdated {
+    pub mint: Pubkey,
+    pub tier: u8,
+    pub fee_bps: u16,
+    pub timestamp: i64,
+}
+
+/// Emitted by `initialize_payment_config` and `set_kyc_attestor`.
+#[event]
+pub struct KycAttestorUpdated {
+    pub attestor: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Emitted by `register_merchant`.
+#[event]
+pub struct MerchantRegistered {
+    pub merchant: Pubkey,
+    pub owner: Pubkey,
+    pub settlement_account: Pubkey,
+    pub name_hash: [u8; 32],
+    pub timestamp: i64,
+}
+
+/// Emitted by `set_merchant_kyc_status`.
+#[event]
+pub struct MerchantKycUpdated {
+    pub merchant: Pubkey,
+    pub owner: Pubkey,
+    pub kyc_status: KycStatus,
+    pub attestor: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Emitted by `set_merchant_fee_tier`.
+#[event]
+pub struct MerchantFeeTierUpdated {
+    pub merchant: Pubkey,
+    pub owner: Pubkey,
+    pub fee_tier: u8,
+    pub timestamp: i64,
+}
+
+/// Emitted by `suspend_merchant` and `reinstate_merchant`.
+#[event]
+pub struct MerchantSuspensionUpdated {
+    pub merchant: Pubkey,
+    pub owner: Pubkey,
+    pub suspended: bool,
+    pub timestamp: i64,
+}
+
+/// Emitted by `pay_merchant`.
+#[event]
+pub struct MerchantPaid {
+    pub merchant: Pubkey,
+    pub buyer: Pubkey,
+    /// The Solana Pay reference of the payment request.
+    pub reference: Pubkey,
+    pub order_id: String,
+    /// Tokens paid by the buyer, including the fee.
+    pub amount: u64,
+    /// Protocol fee sent to the treasury.
+    pub fee: u64,
+    /// Tokens sent to the merchant's settlement account.
+    pub merchant_amount: u64,
+    pub timestamp: i64,
 }
 
 // --- Errors ---
@@ -410,4 +5178,134 @@ pub enum VestingError {
     InvalidPda,
     #[msg("Invalid owner: Vesting token account is not owned by the vesting PDA.")]
     InvalidOwner,
+    #[msg("Invalid tranche amount: A cliff's share of the total does not match the schedule template.")]
+    InvalidTrancheAmount,
+    #[msg("Invalid tranche offset: A cliff's release time does not match the schedule template.")]
+    InvalidTrancheOffset,
+    #[msg("Invalid schedule kind: A schedule entry's offsets do not describe a valid entry of the template's schedule kind.")]
+    InvalidScheduleKind,
+    #[msg("Tranche not in future: A topped-up tranche must start after the current time.")]
+    TrancheNotInFuture,
+    #[msg("Invalid template: Tranches must have positive shares summing to 100% at strictly increasing weeks of at most 520.")]
+    InvalidTemplate,
+    #[msg("Invalid template name: The name must be between 1 and 32 bytes.")]
+    InvalidTemplateName,
+    #[msg("Template retired: The schedule template can no longer be used for new grants.")]
+    TemplateRetired,
+    #[msg("Not revocable: This vesting account was not initialized as revocable.")]
+    NotRevocable,
+    #[msg("Vesting terminated: This vesting account has been revoked.")]
+    VestingTerminated,
+    #[msg("Invalid treasury: Token account is not owned by the treasury wallet.")]
+    InvalidTreasury,
+    #[msg("Invalid claim amount: The amount to claim must be greater than zero.")]
+    InvalidClaimAmount,
+    #[msg("Claim amount exceeds claimable: The amount to claim is more than what is currently claimable.")]
+    ClaimAmountExceedsClaimable,
+    #[msg("Invalid pending admin: Signer is not the proposed new admin.")]
+    InvalidPendingAdmin,
+    #[msg("Program paused: The program is paused by the guardian or admin.")]
+    ProgramPaused,
+    #[msg("Program not paused: The program is not paused.")]
+    ProgramNotPaused,
+    #[msg("Invalid multisig members: Members must be unique and between 1 and the maximum allowed.")]
+    InvalidMultisigMembers,
+    #[msg("Invalid multisig threshold: Threshold must be between 1 and the number of members.")]
+    InvalidMultisigThreshold,
+    #[msg("Not a multisig member: Signer is not a member of the multisig.")]
+    NotMultisigMember,
+    #[msg("Invalid proposal: Proposal does not belong to the multisig.")]
+    InvalidProposal,
+    #[msg("Proposal already executed: The proposal has already been executed.")]
+    ProposalAlreadyExecuted,
+    #[msg("Stale proposal: The multisig members changed after the proposal was created.")]
+    StaleProposal,
+    #[msg("Not enough approvals: The proposal has not reached the multisig threshold.")]
+    NotEnoughApprovals,
+    #[msg("Too many proposals: The multisig's proposal counter overflowed.")]
+    TooManyProposals,
+    #[msg("Invalid pending authority: Signer is not the proposed new authority.")]
+    InvalidPendingAuthority,
+    #[msg("Schedule not exhausted: The vesting account still has unclaimed tokens.")]
+    ScheduleNotExhausted,
+    #[msg("Vesting token account not empty: The vesting token account still holds tokens.")]
+    VestingTokenAccountNotEmpty,
+    #[msg("Invalid payer: Account does not match the payer recorded at init.")]
+    InvalidPayer,
+    #[msg("Too many grants: The beneficiary's grant counter overflowed.")]
+    TooManyGrants,
+    #[msg("Unexpected transfer fee: The vesting token account did not receive the exact vested amount.")]
+    UnexpectedTransferFee,
+    #[msg("Launch time not set: Vesting schedules cannot be resolved before the launch time is set.")]
+    LaunchTimeNotSet,
+    #[msg("Launch time already set: The launch time is frozen once set.")]
+    LaunchTimeAlreadySet,
+    #[msg("Invalid launch time: The launch time must be a positive Unix timestamp.")]
+    InvalidLaunchTime,
+    #[msg("Invalid presale config: Batch sizes, prices and durations must be positive, and caps must fit a batch and what was sold.")]
+    InvalidPresaleConfig,
+    #[msg("Stable payments disabled: The presale does not accept a stablecoin.")]
+    StablePaymentsDisabled,
+    #[msg("Invalid payment accounts: Pass either all or none of the stablecoin accounts.")]
+    InvalidPaymentAccounts,
+    #[msg("Presale inventory exhausted: The token vault cannot fund another batch.")]
+    PresaleInventoryExhausted,
+    #[msg("Presale phase not open: The admin has not opened this presale phase.")]
+    PresalePhaseNotOpen,
+    #[msg("Presale phase already open: A presale phase can only be opened once.")]
+    PresalePhaseAlreadyOpen,
+    #[msg("Presale not started: The presale phase's sale window has not started yet.")]
+    PresaleNotStarted,
+    #[msg("Presale ended: The presale phase's sale window has closed.")]
+    PresaleEnded,
+    #[msg("Presale cap reached: The batch would exceed the presale phase's token cap.")]
+    PresaleCapReached,
+    #[msg("Primary presale active: The secondary presale opens only once the primary sells out or closes.")]
+    PrimaryPresaleActive,
+    #[msg("Invalid presale window: The start time must be positive and the window must not overflow.")]
+    InvalidPresaleWindow,
+    #[msg("Invalid distributor: The distributor needs between 1 and 65536 leaves and a positive total.")]
+    InvalidDistributor,
+    #[msg("Transfer fee not supported: Distributors, vesting stakes, the staking reserve and merchant payments do not support Token-2022 transfer-fee mints.")]
+    TransferFeeNotSupported,
+    #[msg("Invalid leaf index: The leaf index is outside the distributor's tree.")]
+    InvalidLeafIndex,
+    #[msg("Allocation already claimed: This distributor leaf has already been claimed.")]
+    AllocationAlreadyClaimed,
+    #[msg("Invalid Merkle proof: The proof does not match the distributor's root.")]
+    InvalidMerkleProof,
+    #[msg("Distributor overdrawn: The claim would exceed the distributor's funded total.")]
+    DistributorOverdrawn,
+    #[msg("Allocation still vesting: Open a vesting account until the allocation's schedule has fully unlocked.")]
+    AllocationStillVesting,
+    #[msg("Revocable vesting not stakeable: Tokens of a revocable grant cannot be staked.")]
+    RevocableVestingNotStakeable,
+    #[msg("Invalid stake amount: The amount must be positive and not exceed the unclaimed, unstaked tokens.")]
+    InvalidStakeAmount,
+    #[msg("Invalid staking reserve: The reserve amount must be positive.")]
+    InvalidStakingReserve,
+    #[msg("Staking reserve settled: The reserve was already moved to staking or released.")]
+    StakingReserveSettled,
+    #[msg("Staking reserve deadline passed: The reserve can only be released to the community account.")]
+    StakingReserveDeadlinePassed,
+    #[msg("Staking reserve deadline not reached: The reserve cannot be released before its deadline.")]
+    StakingReserveDeadlineNotReached,
+    #[msg("Invalid release account: The account does not match the reserve's community account.")]
+    InvalidReleaseAccount,
+    #[msg("Invalid payment fee: The protocol fee cannot exceed 1000 bps.")]
+    InvalidPaymentFee,
+    #[msg("Invalid payment amount: The amount must be greater than zero.")]
+    InvalidPaymentAmount,
+    #[msg("Order ID too long: The order ID cannot exceed 64 bytes.")]
+    OrderIdTooLong,
+    #[msg("Invalid settlement account: The account does not match the merchant's settlement account.")]
+    InvalidSettlementAccount,
+    #[msg("Invalid fee tier: The fee tier does not exist.")]
+    InvalidFeeTier,
+    #[msg("Merchant not verified: The merchant has not passed KYC.")]
+    MerchantNotVerified,
+    #[msg("Merchant suspended: The merchant is suspended.")]
+    MerchantSuspended,
+    #[msg("Merchant not suspended: Only a suspended merchant can be reinstated.")]
+    MerchantNotSuspended,
 }
\ No newline at end of file
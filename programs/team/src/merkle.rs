@@ -0,0 +1,38 @@
+//! Merkle tree hashing for the allocation distributor, shared by the program and the
+//! off-chain tree builder (`tools/merkle`).
+//!
+//! Leaves and internal nodes are hashed with different prefixes, so an internal node can
+//! never be passed off as a leaf. The two children of a node are hashed in sorted order, so
+//! proofs are plain lists of sibling hashes without left/right flags.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::keccak::hashv;
+
+/// Prefix of leaf hashes.
+const LEAF_PREFIX: &[u8] = &[0];
+/// Prefix of internal node hashes.
+const NODE_PREFIX: &[u8] = &[1];
+
+/// Hashes an allocation leaf: `wallet` may claim `amount` tokens on schedule template
+/// `template_id`. `index` is the leaf's position in the tree and its bit in the claim bitmap.
+pub fn leaf_hash(index: u32, wallet: &Pubkey, amount: u64, template_id: u32) -> [u8; 32] {
+    hashv(&[
+        LEAF_PREFIX,
+        &index.to_le_bytes(),
+        wallet.as_ref(),
+        &amount.to_le_bytes(),
+        &template_id.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+/// Hashes two sibling nodes into their parent.
+pub fn node_hash(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[NODE_PREFIX, left, right]).to_bytes()
+}
+
+/// Returns whether `proof` leads from `leaf` to `root`.
+pub fn verify(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+    proof.iter().fold(leaf, |node, sibling| node_hash(&node, sibling)) == *root
+}
@@ -3,22 +3,24 @@ import { Program } from "@project-serum/anchor";
 import { PublicKey } from "@solana/web3.js";
 import {
   getAssociatedTokenAddressSync,
-  TOKEN_PROGRAM_ID,
 } from "@solana/spl-token";
 import {
   setupProvider,
   getProgram,
   loadKeypair,
   MINT_ADDRESS,
+  MINT_TOKEN_PROGRAM_ID,
   TOKEN_DECIMALS,
+  getLaunchConfigPda,
+  unlockedAmount,
+  getConfigPda,
 } from "./common";
 import fs from "fs";
 import path from "path";
 
 /**
  * Batch processor that automatically releases vested tokens to beneficiaries
- * NOTE: This requires the Anchor smart contract to be modified to include an 
- * admin_distribute_unlocked function that allows admin-initiated claims.
+ * using the admin-signed `admin_distribute_unlocked` instruction.
  */
 async function main() {
   console.log("=== Batch Token Release Processor ===");
@@ -31,6 +33,15 @@ async function main() {
   const adminKeypair = (provider.wallet as anchor.Wallet).payer;
   console.log(`Using admin: ${adminKeypair.publicKey.toString()}`);
   
+  // Schedules are offsets from the launch time, which must already be set
+  const launchConfigPda = getLaunchConfigPda(program.programId);
+  const launchConfig = await program.account.launchConfig.fetch(launchConfigPda);
+  const launchTime = launchConfig.launchTime.toNumber();
+  if (launchTime === 0) {
+    throw new Error("Launch time is not set yet; nothing can be released");
+  }
+  console.log(`Launch time: ${launchTime} (${new Date(launchTime * 1000).toISOString()})`);
+
   // Get all vesting accounts managed by the program
   console.log("Fetching all vesting accounts...");
   const vestingAccounts = await program.account.vestingAccount.all();
@@ -71,7 +82,7 @@ async function main() {
     
     log(`\n[${i + 1}/${vestingAccounts.length}] Processing ${vestingPDA.toString()}`);
     log(`  Beneficiary: ${beneficiary.toString()}`);
-    log(`  Wallet Type: ${Object.keys(vestingAccount.walletType)[0]}`);
+    log(`  Template ID: ${vestingAccount.templateId}`);
     
     // Calculate unlocked amount based on current time
     const now = Math.floor(Date.now() / 1000);
@@ -82,11 +93,12 @@ async function main() {
     // Log full schedule
     log(`  Vesting schedule:`);
     vestingAccount.schedule.forEach((item, idx) => {
-      log(`    [${idx}] ${item.releaseTime.toString()} (${new Date(item.releaseTime.toNumber() * 1000).toISOString()}) - ${item.amount.toString()} tokens (${item.amount.toNumber() / Math.pow(10, TOKEN_DECIMALS)} $TEAM)`);
+      const releaseTime = launchTime + item.releaseOffset.toNumber();
+      const endTime = launchTime + item.endOffset.toNumber();
+      const period = endTime === releaseTime ? `${releaseTime}` : `${releaseTime}..${endTime}`;
+      log(`    [${idx}] ${period} (${new Date(releaseTime * 1000).toISOString()}) - ${item.amount.toString()} tokens (${item.amount.toNumber() / Math.pow(10, TOKEN_DECIMALS)} $TEAM)`);
       
-      if (now >= item.releaseTime.toNumber()) {
-        totalUnlocked = totalUnlocked.add(item.amount);
-      }
+      totalUnlocked = totalUnlocked.add(unlockedAmount(item, now, launchTime));
     });
     
     // Calculate claimable amount
@@ -110,12 +122,15 @@ async function main() {
       const vestingTokenAccount = getAssociatedTokenAddressSync(
         MINT_ADDRESS,
         vestingPDA,
-        true // Allow owner off curve
+        true, // Allow owner off curve
+        MINT_TOKEN_PROGRAM_ID
       );
       
       const beneficiaryTokenAccount = getAssociatedTokenAddressSync(
         MINT_ADDRESS,
-        beneficiary
+        beneficiary,
+        false,
+        MINT_TOKEN_PROGRAM_ID
       );
       
       log(`  Vesting Token Account: ${vestingTokenAccount.toString()}`);
@@ -124,17 +139,19 @@ async function main() {
       // Send the transaction
       log(`  🔄 Sending transaction to distribute ${claimableAmount.toString()} tokens...`);
       
-      // NOTE: This instruction 'adminDistributeUnlocked' needs to be implemented in the contract
       const tx = await program.methods
         .adminDistributeUnlocked()
         .accounts({
           admin: adminKeypair.publicKey,
+          config: getConfigPda(program.programId),
           vestingAccount: vestingPDA,
           authority: beneficiary, // The beneficiary is still set as the authority
           vestingTokenAccount: vestingTokenAccount,
           destinationTokenAccount: beneficiaryTokenAccount,
           vestingSigner: vestingPDA,
-          tokenProgram: TOKEN_PROGRAM_ID,
+          launchConfig: launchConfigPda,
+          mint: MINT_ADDRESS,
+          tokenProgram: MINT_TOKEN_PROGRAM_ID,
         })
         .signers([adminKeypair])
         .rpc();
@@ -2,13 +2,15 @@ import * as anchor from "@project-serum/anchor";
 import { PublicKey } from "@solana/web3.js";
 import {
   getAssociatedTokenAddressSync,
-  TOKEN_PROGRAM_ID,
 } from "@solana/spl-token";
 import {
   setupProvider,
   getProgram,
   loadKeypair,
   MINT_ADDRESS,
+  MINT_TOKEN_PROGRAM_ID,
+  getLaunchConfigPda,
+  getConfigPda,
 } from "./common";
 import yargs from "yargs";
 import { hideBin } from "yargs/helpers";
@@ -27,6 +29,11 @@ async function main() {
       description: "Public key of the vesting account PDA",
       demandOption: true,
     })
+    .option("amount", {
+      alias: "a",
+      type: "string",
+      description: "Raw amount to claim (defaults to everything currently claimable)",
+    })
     .help()
     .alias("help", "h").argv;
 
@@ -44,35 +51,44 @@ async function main() {
   // Get the necessary associated token accounts
   const beneficiaryAta = getAssociatedTokenAddressSync(
     MINT_ADDRESS,
-    beneficiaryPublicKey
+    beneficiaryPublicKey,
+    false,
+    MINT_TOKEN_PROGRAM_ID
   );
 
-  const [vestingSigner] = PublicKey.findProgramAddressSync(
-    [Buffer.from("vesting"), beneficiaryPublicKey.toBuffer()],
-    program.programId
-  );
+  // The vesting account PDA signs for its own token account
+  const vestingSigner = vestingAccount;
 
   const vestingAta = getAssociatedTokenAddressSync(
     MINT_ADDRESS,
     vestingSigner, // Vesting PDA owns this account
-    true // Allow owner off curve
+    true, // Allow owner off curve
+    MINT_TOKEN_PROGRAM_ID
   );
 
   console.log(`Beneficiary ATA: ${beneficiaryAta.toString()}`);
   console.log(`Vesting ATA: ${vestingAta.toString()}`);
   console.log(`Vesting Signer PDA: ${vestingSigner.toString()}`);
 
+  // Claim everything claimable, or only the requested amount
+  const method = argv.amount
+    ? program.methods.claimAmount(new anchor.BN(argv.amount))
+    : program.methods.claimUnlocked();
+  console.log(`Claiming: ${argv.amount ?? "all claimable"}`);
+
   // Claim instruction
   try {
-    const tx = await program.methods
-      .claimUnlocked()
+    const tx = await method
       .accounts({
         vestingAccount: vestingAccount,
         authority: beneficiaryPublicKey,
         vestingTokenAccount: vestingAta, // Source PDA's ATA
         destinationTokenAccount: beneficiaryAta, // Destination Beneficiary's ATA
         vestingSigner: vestingSigner, // The PDA itself
-        tokenProgram: TOKEN_PROGRAM_ID,
+        launchConfig: getLaunchConfigPda(program.programId), // Resolves schedule offsets
+        config: getConfigPda(program.programId), // Fails while the program is paused
+        mint: MINT_ADDRESS,
+        tokenProgram: MINT_TOKEN_PROGRAM_ID,
       })
       .signers([beneficiaryKeypair]) // Beneficiary must sign
       .rpc();
@@ -0,0 +1,67 @@
+import { PublicKey } from "@solana/web3.js";
+import {
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+import { setupProvider, getProgram, loadKeypair, MINT_ADDRESS, MINT_TOKEN_PROGRAM_ID, getConfigPda } from "./common";
+import yargs from "yargs";
+import { hideBin } from "yargs/helpers";
+
+async function main() {
+  const argv = await yargs(hideBin(process.argv))
+    .option("vestingAccountPda", {
+      alias: "pda",
+      type: "string",
+      description: "Public key of the fully claimed (or revoked) vesting account PDA",
+      demandOption: true,
+    })
+    .option("closerKeypair", {
+      alias: "k",
+      type: "string",
+      description: "Path to the beneficiary's keypair (defaults to the admin wallet)",
+    })
+    .help()
+    .alias("help", "h").argv;
+
+  const provider = setupProvider();
+  const program = getProgram(provider);
+
+  const vestingAccount = new PublicKey(argv.vestingAccountPda);
+  const vestingData = await program.account.vestingAccount.fetch(vestingAccount);
+  const closer = argv.closerKeypair
+    ? loadKeypair(argv.closerKeypair)
+    : (provider.wallet as any).payer;
+
+  console.log("--- Closing Vesting Account ---");
+  console.log(`Vesting Account PDA: ${vestingAccount.toBase58()}`);
+  console.log(`Claimed: ${vestingData.claimedAmount.toString()} / ${vestingData.totalAmount.toString()}`);
+  console.log(`Rent refunded to: ${vestingData.payer.toBase58()}`);
+
+  try {
+    const tx = await program.methods
+      .closeVesting()
+      .accounts({
+        closer: closer.publicKey,
+        config: getConfigPda(program.programId),
+        vestingAccount,
+        vestingTokenAccount: getAssociatedTokenAddressSync(MINT_ADDRESS, vestingAccount, true, MINT_TOKEN_PROGRAM_ID),
+        payer: vestingData.payer,
+        mint: MINT_ADDRESS,
+        tokenProgram: MINT_TOKEN_PROGRAM_ID,
+      })
+      .signers([closer])
+      .rpc();
+
+    console.log("Close transaction signature", tx);
+  } catch (err) {
+    console.error("Failed to close vesting account:", err);
+    process.exit(1);
+  }
+}
+
+main().then(
+  () => process.exit(0),
+  (err) => {
+    console.error(err);
+    process.exit(1);
+  }
+);
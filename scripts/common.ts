@@ -4,20 +4,23 @@ import { PublicKey, Keypair, SystemProgram } from "@solana/web3.js";
 import {
   getAssociatedTokenAddressSync,
   TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
   ASSOCIATED_TOKEN_PROGRAM_ID,
 } from "@solana/spl-token";
 import { Team } from "../target/types/team";
+import { Staking } from "../target/types/staking";
 import dotenv from "dotenv";
 import fs from "fs";
 
 dotenv.config();
 
-// WalletType Enum mirroring the Rust definition
-export enum WalletType {
-  Dev,
-  Marketing,
-  Presale1,
-  Presale2,
+// IDs of the built-in schedule templates, mirroring `BUILTIN_TEMPLATES` in the program
+export enum BuiltinTemplate {
+  Dev = 0,
+  Marketing = 1,
+  Presale1 = 2,
+  Presale2 = 3,
+  Contributor = 4,
 }
 
 // Function to load a keypair from a file path
@@ -35,12 +38,165 @@ export function loadKeypair(keypairPath: string): Keypair {
   return loaded;
 }
 
-// Function to calculate future timestamp in seconds
-export function weeksFromNow(weeks: number): number {
-  const now = Math.floor(Date.now() / 1000);
-  const futureDate = new Date();
-  futureDate.setDate(futureDate.getDate() + weeks * 7);
-  return Math.floor(futureDate.getTime() / 1000);
+// Number of seconds in one week, the unit of on-chain schedule offsets
+export const SECONDS_PER_WEEK = 7 * 24 * 60 * 60;
+
+// A cliff of an on-chain schedule template
+export type TrancheTemplate = { bps: number; week: anchor.BN };
+
+// A schedule entry as expected by the program (offsets in seconds after launch)
+// NOTE: Use camelCase field names to match Anchor's generated types
+export type ScheduleEntry = {
+  releaseOffset: anchor.BN;
+  amount: anchor.BN;
+  endOffset: anchor.BN;
+  cliffOffset: anchor.BN;
+};
+
+// Build a schedule of cliffs that passes on-chain template validation:
+// each cliff gets its share rounded down, the last cliff gets the remainder.
+export function buildSchedule(
+  template: TrancheTemplate[],
+  totalAmount: anchor.BN
+): ScheduleEntry[] {
+  let allocated = new anchor.BN(0);
+  return template.map(({ bps, week }, i) => {
+    const amount =
+      i === template.length - 1
+        ? totalAmount.sub(allocated)
+        : totalAmount.mul(new anchor.BN(bps)).div(new anchor.BN(10000));
+    allocated = allocated.add(amount);
+    // Cliffs unlock at once: start, cliff and end coincide
+    const offset = week.mul(new anchor.BN(SECONDS_PER_WEEK));
+    return { releaseOffset: offset, amount, endOffset: offset, cliffOffset: offset };
+  });
+}
+
+// Amount unlocked by a schedule entry at `now`, mirroring `VestingSchedule::unlocked_amount`
+export function unlockedAmount(entry: ScheduleEntry, now: number, launchTime: number): anchor.BN {
+  const start = launchTime + entry.releaseOffset.toNumber();
+  const cliff = launchTime + entry.cliffOffset.toNumber();
+  const end = launchTime + entry.endOffset.toNumber();
+  if (now < cliff) return new anchor.BN(0);
+  if (now >= end) return entry.amount;
+  return entry.amount.mul(new anchor.BN(now - start)).div(new anchor.BN(end - start));
+}
+
+// Derive a schedule template PDA from its ID
+export function getTemplatePda(programId: PublicKey, templateId: number): PublicKey {
+  const id = Buffer.alloc(4);
+  id.writeUInt32LE(templateId);
+  const [template] = PublicKey.findProgramAddressSync([Buffer.from("template"), id], programId);
+  return template;
+}
+
+// Fetch the cliffs of an on-chain schedule template
+export async function fetchTemplateTranches(program: Program<Team>, templateId: number): Promise<TrancheTemplate[]> {
+  const template = await program.account.scheduleTemplate.fetch(getTemplatePda(program.programId, templateId));
+  return template.tranches as TrancheTemplate[];
+}
+
+// Derive a beneficiary's grant counter PDA
+export function getBeneficiaryCounterPda(programId: PublicKey, beneficiary: PublicKey): PublicKey {
+  const [counter] = PublicKey.findProgramAddressSync(
+    [Buffer.from("counter"), beneficiary.toBuffer()],
+    programId
+  );
+  return counter;
+}
+
+// Derive the vesting account PDA for a beneficiary's grant index
+export function getVestingPda(programId: PublicKey, beneficiary: PublicKey, grantIndex: number): PublicKey {
+  const index = Buffer.alloc(4);
+  index.writeUInt32LE(grantIndex);
+  const [vesting] = PublicKey.findProgramAddressSync(
+    [Buffer.from("vesting"), beneficiary.toBuffer(), index],
+    programId
+  );
+  return vesting;
+}
+
+// Fetch the index the next vesting account for a beneficiary will use (0 if they have none yet)
+export async function fetchNextGrantIndex(program: Program<Team>, beneficiary: PublicKey): Promise<number> {
+  const counterPda = getBeneficiaryCounterPda(program.programId, beneficiary);
+  const counter = await program.account.beneficiaryCounter.fetchNullable(counterPda);
+  return counter ? counter.grantCount : 0;
+}
+
+// Derive the program config PDA holding the admin key
+export function getConfigPda(programId: PublicKey): PublicKey {
+  const [config] = PublicKey.findProgramAddressSync([Buffer.from("config")], programId);
+  return config;
+}
+
+// Derive the global launch config PDA
+export function getLaunchConfigPda(programId: PublicKey): PublicKey {
+  const [launchConfig] = PublicKey.findProgramAddressSync(
+    [Buffer.from("launch_config")],
+    programId
+  );
+  return launchConfig;
+}
+
+// Derive the presale configuration PDA (owner of the presale token and stablecoin vaults)
+export function getPresalePda(programId: PublicKey): PublicKey {
+  const [presale] = PublicKey.findProgramAddressSync([Buffer.from("presale")], programId);
+  return presale;
+}
+
+// Derive the PDA collecting presale SOL payments
+export function getPresaleSolVaultPda(programId: PublicKey): PublicKey {
+  const [vault] = PublicKey.findProgramAddressSync([Buffer.from("presale_vault")], programId);
+  return vault;
+}
+
+// Presale phases, mirroring `PresalePhase` in the program (also the receipt seed byte)
+export enum PresalePhase {
+  Primary = 0,
+  Secondary = 1,
+}
+
+// Derive a buyer's purchase receipt PDA for a presale phase
+export function getPresaleReceiptPda(programId: PublicKey, phase: PresalePhase, buyer: PublicKey): PublicKey {
+  const [receipt] = PublicKey.findProgramAddressSync(
+    [Buffer.from("receipt"), Buffer.from([phase]), buyer.toBuffer()],
+    programId
+  );
+  return receipt;
+}
+
+// Derive the staking pool PDA for a mint (owner of the stake and reward vaults)
+export function getStakingPoolPda(programId: PublicKey, mint: PublicKey): PublicKey {
+  const [pool] = PublicKey.findProgramAddressSync([Buffer.from("pool"), mint.toBuffer()], programId);
+  return pool;
+}
+
+// Derive a staking pool's stake vault PDA
+export function getStakeVaultPda(programId: PublicKey, pool: PublicKey): PublicKey {
+  const [vault] = PublicKey.findProgramAddressSync([Buffer.from("stake_vault"), pool.toBuffer()], programId);
+  return vault;
+}
+
+// Derive a staking pool's reward vault PDA
+export function getRewardVaultPda(programId: PublicKey, pool: PublicKey): PublicKey {
+  const [vault] = PublicKey.findProgramAddressSync([Buffer.from("reward_vault"), pool.toBuffer()], programId);
+  return vault;
+}
+
+// Derive a staking position PDA from its owner and position ID
+export function getStakePositionPda(
+  programId: PublicKey,
+  pool: PublicKey,
+  owner: PublicKey,
+  positionId: number
+): PublicKey {
+  const id = Buffer.alloc(4);
+  id.writeUInt32LE(positionId);
+  const [position] = PublicKey.findProgramAddressSync(
+    [Buffer.from("position"), pool.toBuffer(), owner.toBuffer(), id],
+    programId
+  );
+  return position;
 }
 
 // Configure the client to use the local cluster or specified RPC URL
@@ -65,9 +221,17 @@ export function getProgram(provider: anchor.AnchorProvider): Program<Team> {
   return anchor.workspace.Team as Program<Team>; // Assumes program ID is in Anchor.toml
 }
 
+// Get the staking program instance
+export function getStakingProgram(provider: anchor.AnchorProvider): Program<Staking> {
+  return anchor.workspace.Staking as Program<Staking>; // Assumes program ID is in Anchor.toml
+}
+
 // Common constants
 export const MINT_ADDRESS = new PublicKey(process.env.MINT_ADDRESS!);
 export const TOKEN_DECIMALS = parseInt(process.env.TOKEN_DECIMALS || "9");
+// Token program owning the mint: set TOKEN_PROGRAM=token-2022 for a Token-2022 mint
+export const MINT_TOKEN_PROGRAM_ID =
+  process.env.TOKEN_PROGRAM === "token-2022" ? TOKEN_2022_PROGRAM_ID : TOKEN_PROGRAM_ID;
 
 // Basic validation
 if (!process.env.ADMIN_KEYPAIR_PATH) {
@@ -85,4 +249,5 @@ if (!process.env.PROGRAM_ID) {
 console.log(`Using RPC: ${process.env.RPC_URL || "http://127.0.0.1:8899"}`);
 console.log(`Using Mint: ${MINT_ADDRESS.toBase58()}`);
 console.log(`Token Decimals: ${TOKEN_DECIMALS}`);
+console.log(`Token Program: ${MINT_TOKEN_PROGRAM_ID.toBase58()}`);
 console.log(`Program ID: ${process.env.PROGRAM_ID || "From Anchor.toml"}`);
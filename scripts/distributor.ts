@@ -0,0 +1,178 @@
+import * as anchor from "@project-serum/anchor";
+import { PublicKey, SystemProgram } from "@solana/web3.js";
+import { ASSOCIATED_TOKEN_PROGRAM_ID, getAssociatedTokenAddressSync } from "@solana/spl-token";
+import {
+  setupProvider,
+  getProgram,
+  loadKeypair,
+  MINT_ADDRESS,
+  MINT_TOKEN_PROGRAM_ID,
+  getConfigPda,
+  getLaunchConfigPda,
+  getTemplatePda,
+  getBeneficiaryCounterPda,
+  getVestingPda,
+  fetchNextGrantIndex,
+} from "./common";
+import yargs from "yargs";
+import { hideBin } from "yargs/helpers";
+import fs from "fs";
+
+// Output of `cargo run -p team-merkle -- allocations.csv tree.json`
+type Tree = {
+  root: string;
+  num_leaves: number;
+  total_amount: number;
+  leaves: { index: number; wallet: string; amount: number; template_id: number; proof: string[] }[];
+};
+
+// Derive a distributor PDA from its ID
+function getDistributorPda(programId: PublicKey, distributorId: number): PublicKey {
+  const id = Buffer.alloc(4);
+  id.writeUInt32LE(distributorId);
+  const [distributor] = PublicKey.findProgramAddressSync([Buffer.from("distributor"), id], programId);
+  return distributor;
+}
+
+/**
+ * Manage Merkle distributors of presale allocations.
+ *
+ *   create: publish a tree built by tools/merkle and fund its vault from the admin wallet
+ *   claim:  prove a wallet's leaf and open its vesting account (or --direct once fully unlocked)
+ *   status: print a distributor's funding and claims
+ */
+async function main() {
+  const argv = await yargs(hideBin(process.argv))
+    .command("create", "Create and fund a distributor")
+    .command("claim", "Claim an allocation")
+    .command("status", "Print a distributor's state")
+    .demandCommand(1)
+    .option("id", { type: "number", demandOption: true, description: "Distributor ID" })
+    .option("tree", { type: "string", description: "Tree JSON written by tools/merkle (create, claim)" })
+    .option("claimantKeypair", { type: "string", description: "Claimant keypair path, defaults to the admin (claim)" })
+    .option("direct", { type: "boolean", default: false, description: "Claim tokens directly instead of vesting (claim)" })
+    .help()
+    .alias("help", "h").argv;
+
+  const provider = setupProvider();
+  const program = getProgram(provider);
+  const config = getConfigPda(program.programId);
+  const distributor = getDistributorPda(program.programId, argv.id);
+  const vault = getAssociatedTokenAddressSync(MINT_ADDRESS, distributor, true, MINT_TOKEN_PROGRAM_ID);
+  const action = String(argv._[0]);
+  const readTree = (): Tree => {
+    if (!argv.tree) {
+      throw new Error("--tree is required");
+    }
+    return JSON.parse(fs.readFileSync(argv.tree, "utf8"));
+  };
+
+  console.log(`--- Distributor #${argv.id} (${action}) ---`);
+
+  try {
+    if (action === "create") {
+      const tree = readTree();
+      const tx = await program.methods
+        .createDistributor(
+          argv.id,
+          Array.from(Buffer.from(tree.root, "hex")),
+          tree.num_leaves,
+          new anchor.BN(tree.total_amount)
+        )
+        .accounts({
+          admin: provider.wallet.publicKey,
+          config,
+          distributor,
+          adminTokenAccount: getAssociatedTokenAddressSync(
+            MINT_ADDRESS, provider.wallet.publicKey, false, MINT_TOKEN_PROGRAM_ID
+          ),
+          mint: MINT_ADDRESS,
+          vault,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: MINT_TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      console.log(`Root: ${tree.root}, ${tree.num_leaves} leaves, ${tree.total_amount} tokens`);
+      console.log("Transaction signature", tx);
+    } else if (action === "claim") {
+      const claimant = argv.claimantKeypair
+        ? loadKeypair(argv.claimantKeypair)
+        : (provider.wallet as anchor.Wallet).payer;
+      const leaf = readTree().leaves.find((l) => l.wallet === claimant.publicKey.toBase58());
+      if (!leaf) {
+        throw new Error(`No allocation for ${claimant.publicKey.toBase58()}`);
+      }
+      const proof = leaf.proof.map((p) => Array.from(Buffer.from(p, "hex")));
+      const args = [leaf.index, new anchor.BN(leaf.amount), leaf.template_id, proof] as const;
+      const template = getTemplatePda(program.programId, leaf.template_id);
+
+      let tx: string;
+      if (argv.direct) {
+        tx = await program.methods
+          .claimDistributorAllocation(...args)
+          .accounts({
+            claimant: claimant.publicKey,
+            config,
+            distributor,
+            template,
+            launchConfig: getLaunchConfigPda(program.programId),
+            mint: MINT_ADDRESS,
+            vault,
+            destinationTokenAccount: getAssociatedTokenAddressSync(
+              MINT_ADDRESS, claimant.publicKey, false, MINT_TOKEN_PROGRAM_ID
+            ),
+            tokenProgram: MINT_TOKEN_PROGRAM_ID,
+          })
+          .signers([claimant])
+          .rpc();
+      } else {
+        const grantIndex = await fetchNextGrantIndex(program, claimant.publicKey);
+        const vestingAccount = getVestingPda(program.programId, claimant.publicKey, grantIndex);
+        tx = await program.methods
+          .claimDistributorVesting(...args)
+          .accounts({
+            claimant: claimant.publicKey,
+            config,
+            distributor,
+            template,
+            beneficiaryCounter: getBeneficiaryCounterPda(program.programId, claimant.publicKey),
+            vestingAccount,
+            mint: MINT_ADDRESS,
+            vault,
+            vestingTokenAccount: getAssociatedTokenAddressSync(MINT_ADDRESS, vestingAccount, true, MINT_TOKEN_PROGRAM_ID),
+            systemProgram: SystemProgram.programId,
+            tokenProgram: MINT_TOKEN_PROGRAM_ID,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          })
+          .signers([claimant])
+          .rpc();
+        console.log(`Vesting account (grant #${grantIndex}): ${vestingAccount.toBase58()}`);
+      }
+      console.log(`Claimed leaf #${leaf.index}: ${leaf.amount} tokens on template #${leaf.template_id}`);
+      console.log("Transaction signature", tx);
+    } else if (action === "status") {
+      const state = await program.account.distributor.fetch(distributor);
+      const claimedLeaves = (state.claimedBitmap as number[]).reduce(
+        (count, byte) => count + byte.toString(2).split("1").length - 1,
+        0
+      );
+      console.log(`Root: ${Buffer.from(state.root as number[]).toString("hex")}`);
+      console.log(`Claimed: ${claimedLeaves} / ${state.numLeaves} leaves`);
+      console.log(`Claimed amount: ${state.claimedAmount.toString()} / ${state.totalAmount.toString()}`);
+    } else {
+      throw new Error(`Unknown action: ${action}`);
+    }
+  } catch (err) {
+    console.error(`Failed to ${action}:`, err);
+    process.exit(1);
+  }
+}
+
+main().then(
+  () => process.exit(0),
+  (err) => {
+    console.error(err);
+    process.exit(1);
+  }
+);
@@ -0,0 +1,73 @@
+import { PublicKey, SystemProgram } from "@solana/web3.js";
+import { setupProvider, getProgram, getConfigPda } from "./common";
+import yargs from "yargs";
+import { hideBin } from "yargs/helpers";
+
+// The BPF upgradeable loader, owner of the program data account
+const BPF_LOADER_UPGRADEABLE_ID = new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111");
+
+/**
+ * Creates the program config holding the admin key and the treasury wallet.
+ * Must be signed by the program's upgrade authority (the provider wallet).
+ */
+async function main() {
+  const argv = await yargs(hideBin(process.argv))
+    .option("admin", {
+      alias: "a",
+      type: "string",
+      description: "Admin public key (defaults to the provider wallet)",
+    })
+    .option("treasury", {
+      alias: "t",
+      type: "string",
+      description: "Treasury public key (defaults to TREASURY_WALLET)",
+    })
+    .help()
+    .alias("help", "h").argv;
+
+  const provider = setupProvider();
+  const program = getProgram(provider);
+
+  const admin = argv.admin ? new PublicKey(argv.admin) : provider.wallet.publicKey;
+  const treasuryArg = argv.treasury ?? process.env.TREASURY_WALLET;
+  if (!treasuryArg) {
+    throw new Error("--treasury or TREASURY_WALLET is required");
+  }
+  const treasury = new PublicKey(treasuryArg);
+  const config = getConfigPda(program.programId);
+  const [programData] = PublicKey.findProgramAddressSync(
+    [program.programId.toBuffer()],
+    BPF_LOADER_UPGRADEABLE_ID
+  );
+
+  console.log("--- Initializing Program Config ---");
+  console.log(`Config PDA: ${config.toBase58()}`);
+  console.log(`Admin: ${admin.toBase58()}`);
+  console.log(`Treasury: ${treasury.toBase58()}`);
+
+  try {
+    const tx = await program.methods
+      .initializeConfig(admin, treasury)
+      .accounts({
+        upgradeAuthority: provider.wallet.publicKey,
+        config,
+        program: program.programId,
+        programData,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    console.log("Initialization transaction signature", tx);
+  } catch (err) {
+    console.error("Failed to initialize program config:", err);
+    process.exit(1);
+  }
+}
+
+main().then(
+  () => process.exit(0),
+  (err) => {
+    console.error(err);
+    process.exit(1);
+  }
+);
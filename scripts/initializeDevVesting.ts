@@ -2,16 +2,22 @@ import * as anchor from "@project-serum/anchor";
 import { PublicKey, SystemProgram } from "@solana/web3.js";
 import {
   getAssociatedTokenAddressSync,
-  TOKEN_PROGRAM_ID,
   ASSOCIATED_TOKEN_PROGRAM_ID,
 } from "@solana/spl-token";
 import {
   setupProvider,
   getProgram,
-  WalletType,
+  BuiltinTemplate,
   MINT_ADDRESS,
+  MINT_TOKEN_PROGRAM_ID,
   TOKEN_DECIMALS,
-  weeksFromNow,
+  buildSchedule,
+  fetchTemplateTranches,
+  getTemplatePda,
+  fetchNextGrantIndex,
+  getBeneficiaryCounterPda,
+  getVestingPda,
+  getConfigPda,
 } from "./common";
 
 async function main() {
@@ -29,11 +35,11 @@ async function main() {
     );
   }
 
-  // Dev vesting schedule: 100% at 12m (52 weeks)
-  // NOTE: Use camelCase 'releaseTime' to match Anchor's generated types
-  const schedule = [
-    { releaseTime: new anchor.BN(weeksFromNow(52)), amount: totalAmount },
-  ];
+  // Dev vesting schedule: 5%@2w, 15%@24w, 30%@30w, 50%@36w after launch
+  const schedule = buildSchedule(
+    await fetchTemplateTranches(program, BuiltinTemplate.Dev),
+    totalAmount
+  );
 
   console.log("--- Initializing Dev Vesting ---");
   console.log(`Beneficiary: ${beneficiaryWallet.toBase58()}`);
@@ -43,22 +49,25 @@ async function main() {
   // Get the Associated Token Account addresses
   const adminTokenAccount = getAssociatedTokenAddressSync(
     MINT_ADDRESS,
-    provider.wallet.publicKey
+    provider.wallet.publicKey,
+    false,
+    MINT_TOKEN_PROGRAM_ID
   );
 
-  // Find PDA for vesting account
-  const [vestingAccountPDA] = PublicKey.findProgramAddressSync(
-    [Buffer.from("vesting"), beneficiaryWallet.toBuffer()],
-    program.programId
-  );
+  // Find PDA for the beneficiary's next vesting account (one per grant)
+  const grantIndex = await fetchNextGrantIndex(program, beneficiaryWallet);
+  const beneficiaryCounter = getBeneficiaryCounterPda(program.programId, beneficiaryWallet);
+  const vestingAccountPDA = getVestingPda(program.programId, beneficiaryWallet, grantIndex);
 
   // Get the vesting account's Associated Token Account address
   const vestingTokenAccount = getAssociatedTokenAddressSync(
     MINT_ADDRESS,
     vestingAccountPDA, // The PDA is the owner of this ATA
-    true // Allow owner off curve (required for PDAs)
+    true, // Allow owner off curve (required for PDAs)
+    MINT_TOKEN_PROGRAM_ID
   );
 
+  console.log(`Grant Index: ${grantIndex}`);
   console.log(`Vesting Account PDA: ${vestingAccountPDA.toString()}`);
   console.log(`Admin ATA: ${adminTokenAccount.toString()}`);
   console.log(`Vesting ATA: ${vestingTokenAccount.toString()}`);
@@ -66,16 +75,19 @@ async function main() {
   // Initialize vesting account instruction
   try {
     const tx = await program.methods
-      .initializeVesting({ dev: {} }, totalAmount, schedule)
+      .initializeVesting(BuiltinTemplate.Dev, totalAmount, schedule, true) // Revocable if a team member leaves
       .accounts({
         admin: provider.wallet.publicKey,
+        config: getConfigPda(program.programId),
+        template: getTemplatePda(program.programId, BuiltinTemplate.Dev),
         adminTokenAccount: adminTokenAccount,
         beneficiary: beneficiaryWallet,
+        beneficiaryCounter: beneficiaryCounter,
         vestingAccount: vestingAccountPDA,
         mint: MINT_ADDRESS,
         vestingTokenAccount: vestingTokenAccount,
         systemProgram: SystemProgram.programId,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        tokenProgram: MINT_TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       })
       .rpc();
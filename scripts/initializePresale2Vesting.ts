@@ -2,16 +2,22 @@ import * as anchor from "@project-serum/anchor";
 import { PublicKey, SystemProgram } from "@solana/web3.js";
 import {
   getAssociatedTokenAddressSync,
-  TOKEN_PROGRAM_ID,
   ASSOCIATED_TOKEN_PROGRAM_ID,
 } from "@solana/spl-token";
 import {
   setupProvider,
   getProgram,
-  WalletType,
+  BuiltinTemplate,
   MINT_ADDRESS,
+  MINT_TOKEN_PROGRAM_ID,
   TOKEN_DECIMALS,
-  weeksFromNow,
+  buildSchedule,
+  fetchTemplateTranches,
+  getTemplatePda,
+  fetchNextGrantIndex,
+  getBeneficiaryCounterPda,
+  getVestingPda,
+  getConfigPda,
 } from "./common";
 
 async function main() {
@@ -29,15 +35,11 @@ async function main() {
     );
   }
 
-  // Presale2 vesting schedule: 25% TGE, 25%@4w, 25%@8w, 25%@12w
-  // NOTE: Use camelCase 'releaseTime' to match Anchor's generated types
-  const tgeAmount = new anchor.BN(Math.floor(totalAmount.toNumber() * 0.25));
-  const schedule = [
-    { releaseTime: new anchor.BN(weeksFromNow(0)), amount: tgeAmount }, // TGE
-    { releaseTime: new anchor.BN(weeksFromNow(4)), amount: tgeAmount },
-    { releaseTime: new anchor.BN(weeksFromNow(8)), amount: tgeAmount },
-    { releaseTime: new anchor.BN(weeksFromNow(12)), amount: totalAmount.sub(tgeAmount.mul(new anchor.BN(3))) }, // Remainder
-  ];
+  // Presale2 vesting schedule: 100%@12w after launch
+  const schedule = buildSchedule(
+    await fetchTemplateTranches(program, BuiltinTemplate.Presale2),
+    totalAmount
+  );
 
   console.log("--- Initializing Presale2 Vesting ---");
   console.log(`Beneficiary: ${beneficiaryWallet.toBase58()}`);
@@ -47,22 +49,25 @@ async function main() {
   // Get the Associated Token Account addresses
   const adminTokenAccount = getAssociatedTokenAddressSync(
     MINT_ADDRESS,
-    provider.wallet.publicKey
+    provider.wallet.publicKey,
+    false,
+    MINT_TOKEN_PROGRAM_ID
   );
 
-  // Find PDA for vesting account
-  const [vestingAccountPDA] = PublicKey.findProgramAddressSync(
-    [Buffer.from("vesting"), beneficiaryWallet.toBuffer()],
-    program.programId
-  );
+  // Find PDA for the beneficiary's next vesting account (one per grant)
+  const grantIndex = await fetchNextGrantIndex(program, beneficiaryWallet);
+  const beneficiaryCounter = getBeneficiaryCounterPda(program.programId, beneficiaryWallet);
+  const vestingAccountPDA = getVestingPda(program.programId, beneficiaryWallet, grantIndex);
 
   // Get the vesting account's Associated Token Account address
   const vestingTokenAccount = getAssociatedTokenAddressSync(
     MINT_ADDRESS,
     vestingAccountPDA,
-    true // Allow owner off curve
+    true, // Allow owner off curve
+    MINT_TOKEN_PROGRAM_ID
   );
 
+  console.log(`Grant Index: ${grantIndex}`);
   console.log(`Vesting Account PDA: ${vestingAccountPDA.toString()}`);
   console.log(`Admin ATA: ${adminTokenAccount.toString()}`);
   console.log(`Vesting ATA: ${vestingTokenAccount.toString()}`);
@@ -70,16 +75,19 @@ async function main() {
   // Initialize vesting account instruction
   try {
     const tx = await program.methods
-      .initializeVesting({ presale2: {} }, totalAmount, schedule) // Pass enum variant as object
+      .initializeVesting(BuiltinTemplate.Presale2, totalAmount, schedule, false) // Purchased tokens are not revocable
       .accounts({
         admin: provider.wallet.publicKey,
+        config: getConfigPda(program.programId),
+        template: getTemplatePda(program.programId, BuiltinTemplate.Presale2),
         adminTokenAccount: adminTokenAccount,
         beneficiary: beneficiaryWallet,
+        beneficiaryCounter: beneficiaryCounter,
         vestingAccount: vestingAccountPDA,
         mint: MINT_ADDRESS,
         vestingTokenAccount: vestingTokenAccount,
         systemProgram: SystemProgram.programId,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        tokenProgram: MINT_TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       })
       .rpc();
@@ -0,0 +1,179 @@
+import * as anchor from "@project-serum/anchor";
+import { PublicKey, SystemProgram, TransactionInstruction } from "@solana/web3.js";
+import {
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+import {
+  setupProvider,
+  getProgram,
+  loadKeypair,
+  MINT_ADDRESS,
+  MINT_TOKEN_PROGRAM_ID,
+  getConfigPda,
+  getLaunchConfigPda,
+} from "./common";
+import yargs from "yargs";
+import { hideBin } from "yargs/helpers";
+
+/**
+ * Native M-of-N multisig for vesting administration.
+ *
+ *   create:                 create a multisig (signed by the creator, the provider wallet)
+ *   propose-accept-admin:   propose that the multisig accepts a pending admin rotation
+ *   propose-distribute:     propose an admin distribution for a vesting account
+ *   approve:                approve a proposal (signed by a member)
+ *   execute:                execute a proposal that reached the threshold
+ *
+ * To hand admin to the multisig: run `rotateAdmin.ts propose --newAdmin <MULTISIG_SIGNER>`,
+ * then propose, approve and execute `propose-accept-admin`.
+ */
+
+function getMultisigPdas(programId: PublicKey, creator: PublicKey) {
+  const [multisig] = PublicKey.findProgramAddressSync(
+    [Buffer.from("multisig"), creator.toBuffer()],
+    programId
+  );
+  const [multisigSigner] = PublicKey.findProgramAddressSync(
+    [Buffer.from("multisig_signer"), multisig.toBuffer()],
+    programId
+  );
+  return { multisig, multisigSigner };
+}
+
+function getProposalPda(programId: PublicKey, multisig: PublicKey, index: anchor.BN): PublicKey {
+  const [proposal] = PublicKey.findProgramAddressSync(
+    [Buffer.from("proposal"), multisig.toBuffer(), index.toArrayLike(Buffer, "le", 8)],
+    programId
+  );
+  return proposal;
+}
+
+async function main() {
+  const argv = await yargs(hideBin(process.argv))
+    .command("create", "Create a multisig")
+    .command("propose-accept-admin", "Propose that the multisig accepts the pending admin rotation")
+    .command("propose-distribute", "Propose an admin distribution for a vesting account")
+    .command("approve", "Approve a proposal")
+    .command("execute", "Execute an approved proposal")
+    .demandCommand(1)
+    .option("creator", { type: "string", description: "Multisig creator public key (defaults to the provider wallet)" })
+    .option("members", { type: "string", description: "Comma-separated member public keys (create only)" })
+    .option("threshold", { type: "number", description: "Approvals required (create only)" })
+    .option("memberKeypair", { alias: "k", type: "string", description: "Path to the signing member's keypair" })
+    .option("proposalIndex", { alias: "i", type: "number", description: "Proposal index (approve/execute)" })
+    .option("vestingAccountPda", { alias: "pda", type: "string", description: "Vesting account PDA (propose-distribute)" })
+    .help()
+    .alias("help", "h").argv;
+
+  const provider = setupProvider();
+  const program = getProgram(provider);
+  const action = String(argv._[0]);
+
+  const creator = argv.creator ? new PublicKey(argv.creator) : provider.wallet.publicKey;
+  const { multisig, multisigSigner } = getMultisigPdas(program.programId, creator);
+  const member = argv.memberKeypair ? loadKeypair(argv.memberKeypair) : (provider.wallet as anchor.Wallet).payer;
+
+  console.log(`--- Multisig (${action}) ---`);
+  console.log(`Multisig: ${multisig.toBase58()}`);
+  console.log(`Multisig Signer (admin): ${multisigSigner.toBase58()}`);
+
+  // Create a proposal for an instruction signed by the multisig signer
+  const propose = async (ix: TransactionInstruction) => {
+    const multisigData = await program.account.multisig.fetch(multisig);
+    const proposal = getProposalPda(program.programId, multisig, multisigData.proposalCount);
+    const tx = await program.methods
+      .createProposal(
+        ix.programId,
+        ix.keys.map((k) => ({ pubkey: k.pubkey, isSigner: k.isSigner, isWritable: k.isWritable })),
+        ix.data
+      )
+      .accounts({ multisig, proposal, proposer: member.publicKey, systemProgram: SystemProgram.programId })
+      .signers([member])
+      .rpc();
+    console.log(`Proposal #${multisigData.proposalCount.toString()}: ${proposal.toBase58()}`);
+    return tx;
+  };
+
+  try {
+    let tx: string;
+    if (action === "create") {
+      if (!argv.members || !argv.threshold) {
+        throw new Error("--members and --threshold are required to create a multisig");
+      }
+      const members = argv.members.split(",").map((m) => new PublicKey(m.trim()));
+      tx = await program.methods
+        .createMultisig(members, argv.threshold)
+        .accounts({ creator: provider.wallet.publicKey, multisig, multisigSigner, systemProgram: SystemProgram.programId })
+        .rpc();
+    } else if (action === "propose-accept-admin") {
+      const ix = await program.methods
+        .acceptAdmin()
+        .accounts({ newAdmin: multisigSigner, config: getConfigPda(program.programId) })
+        .instruction();
+      tx = await propose(ix);
+    } else if (action === "propose-distribute") {
+      if (!argv.vestingAccountPda) {
+        throw new Error("--vestingAccountPda is required");
+      }
+      const vestingAccount = new PublicKey(argv.vestingAccountPda);
+      const vestingData = await program.account.vestingAccount.fetch(vestingAccount);
+      const ix = await program.methods
+        .adminDistributeUnlocked()
+        .accounts({
+          admin: multisigSigner,
+          config: getConfigPda(program.programId),
+          vestingAccount,
+          authority: vestingData.authority,
+          vestingTokenAccount: getAssociatedTokenAddressSync(MINT_ADDRESS, vestingAccount, true, MINT_TOKEN_PROGRAM_ID),
+          destinationTokenAccount: getAssociatedTokenAddressSync(MINT_ADDRESS, vestingData.authority, false, MINT_TOKEN_PROGRAM_ID),
+          vestingSigner: vestingAccount,
+          launchConfig: getLaunchConfigPda(program.programId),
+          mint: MINT_ADDRESS,
+          tokenProgram: MINT_TOKEN_PROGRAM_ID,
+        })
+        .instruction();
+      tx = await propose(ix);
+    } else if (action === "approve" || action === "execute") {
+      if (argv.proposalIndex === undefined) {
+        throw new Error("--proposalIndex is required");
+      }
+      const proposal = getProposalPda(program.programId, multisig, new anchor.BN(argv.proposalIndex));
+      if (action === "approve") {
+        tx = await program.methods
+          .approveProposal()
+          .accounts({ multisig, proposal, member: member.publicKey })
+          .signers([member])
+          .rpc();
+      } else {
+        const proposalData = await program.account.proposal.fetch(proposal);
+        // The multisig signer signs via the program, not the transaction
+        const remainingAccounts = proposalData.accounts.map((a) => ({
+          pubkey: a.pubkey,
+          isSigner: false,
+          isWritable: a.isWritable,
+        }));
+        remainingAccounts.push({ pubkey: proposalData.programId, isSigner: false, isWritable: false });
+        tx = await program.methods
+          .executeProposal()
+          .accounts({ multisig, proposal, multisigSigner })
+          .remainingAccounts(remainingAccounts)
+          .rpc();
+      }
+    } else {
+      throw new Error(`Unknown action: ${action}`);
+    }
+
+    console.log("Transaction signature", tx);
+  } catch (err) {
+    console.error(`Failed to ${action}:`, err);
+    process.exit(1);
+  }
+}
+
+main().then(
+  () => process.exit(0),
+  (err) => {
+    console.error(err);
+    process.exit(1);
+  }
+);
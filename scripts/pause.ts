@@ -0,0 +1,74 @@
+import { PublicKey } from "@solana/web3.js";
+import { setupProvider, getProgram, loadKeypair, getConfigPda } from "./common";
+import yargs from "yargs";
+import { hideBin } from "yargs/helpers";
+
+/**
+ * Emergency pause switch.
+ *
+ *   set-guardian: admin sets the wallet allowed to pause
+ *   pause:        guardian (or admin) pauses all vesting instructions
+ *   unpause:      admin resumes the program
+ */
+async function main() {
+  const argv = await yargs(hideBin(process.argv))
+    .command("set-guardian", "Set the guardian (signed by the admin)")
+    .command("pause", "Pause the program (signed by the guardian or admin)")
+    .command("unpause", "Unpause the program (signed by the admin)")
+    .demandCommand(1)
+    .option("guardian", { alias: "g", type: "string", description: "Guardian public key (set-guardian only)" })
+    .option("signerKeypair", {
+      alias: "k",
+      type: "string",
+      description: "Path to the guardian's keypair (pause only; defaults to the admin wallet)",
+    })
+    .help()
+    .alias("help", "h").argv;
+
+  const provider = setupProvider();
+  const program = getProgram(provider);
+  const config = getConfigPda(program.programId);
+  const action = String(argv._[0]);
+
+  console.log(`--- Pause Switch (${action}) ---`);
+
+  try {
+    let tx: string;
+    if (action === "set-guardian") {
+      if (!argv.guardian) {
+        throw new Error("--guardian is required");
+      }
+      tx = await program.methods
+        .setGuardian(new PublicKey(argv.guardian))
+        .accounts({ admin: provider.wallet.publicKey, config })
+        .rpc();
+    } else if (action === "pause") {
+      const signer = argv.signerKeypair ? loadKeypair(argv.signerKeypair) : (provider.wallet as any).payer;
+      tx = await program.methods
+        .pause()
+        .accounts({ signer: signer.publicKey, config })
+        .signers([signer])
+        .rpc();
+    } else if (action === "unpause") {
+      tx = await program.methods
+        .unpause()
+        .accounts({ admin: provider.wallet.publicKey, config })
+        .rpc();
+    } else {
+      throw new Error(`Unknown action: ${action}`);
+    }
+
+    console.log("Transaction signature", tx);
+  } catch (err) {
+    console.error(`Failed to ${action}:`, err);
+    process.exit(1);
+  }
+}
+
+main().then(
+  () => process.exit(0),
+  (err) => {
+    console.error(err);
+    process.exit(1);
+  }
+);
@@ -0,0 +1,206 @@
+import * as anchor from "@project-serum/anchor";
+import { createHash } from "crypto";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { getAssociatedTokenAddressSync } from "@solana/spl-token";
+import {
+  setupProvider,
+  getProgram,
+  loadKeypair,
+  MINT_ADDRESS,
+  MINT_TOKEN_PROGRAM_ID,
+  TOKEN_DECIMALS,
+  getConfigPda,
+} from "./common";
+import yargs from "yargs";
+import { hideBin } from "yargs/helpers";
+
+// Derive the merchant payment config PDA
+function getPaymentConfigPda(programId: PublicKey): PublicKey {
+  const [paymentConfig] = PublicKey.findProgramAddressSync([Buffer.from("payment_config")], programId);
+  return paymentConfig;
+}
+
+// Derive a merchant PDA from its owner
+function getMerchantPda(programId: PublicKey, owner: PublicKey): PublicKey {
+  const [merchant] = PublicKey.findProgramAddressSync([Buffer.from("merchant"), owner.toBuffer()], programId);
+  return merchant;
+}
+
+const KYC_STATUSES = ["unverified", "verified", "rejected"];
+
+/**
+ * Manage merchant payments in $TEAM.
+ *
+ *   init:      create the payment config with a protocol fee and KYC attestor (admin only)
+ *   fee:       update the protocol fee of a fee tier (admin only)
+ *   attestor:  designate the KYC attestor (admin only)
+ *   register:  register a merchant settling into its owner's ATA
+ *   kyc:       set a merchant's KYC status (attestor only)
+ *   tier:      move a merchant to a fee tier (admin only)
+ *   suspend:   suspend a merchant (admin only)
+ *   reinstate: reinstate a suspended merchant (admin only)
+ *   pay:       pay a merchant, tagged with a Solana Pay reference and an order ID
+ *   status:    print the payment config, or a merchant with --merchant
+ */
+async function main() {
+  const argv = await yargs(hideBin(process.argv))
+    .command("init", "Create the payment config")
+    .command("fee", "Update the protocol fee of a fee tier")
+    .command("attestor", "Designate the KYC attestor")
+    .command("register", "Register a merchant")
+    .command("kyc", "Set a merchant's KYC status")
+    .command("tier", "Move a merchant to a fee tier")
+    .command("suspend", "Suspend a merchant")
+    .command("reinstate", "Reinstate a merchant")
+    .command("pay", "Pay a merchant")
+    .command("status", "Print the payment config or a merchant")
+    .demandCommand(1)
+    .option("feeBps", { type: "number", default: 100, description: "Protocol fee in bps (init, fee)" })
+    .option("tier", { type: "number", default: 0, description: "Fee tier (fee, tier)" })
+    .option("attestor", { type: "string", description: "KYC attestor pubkey, defaults to the admin (init, attestor)" })
+    .option("attestorKeypair", { type: "string", description: "KYC attestor keypair path, defaults to the admin (kyc)" })
+    .option("kycStatus", { type: "string", choices: KYC_STATUSES, default: "verified", description: "KYC status (kyc)" })
+    .option("ownerKeypair", { type: "string", description: "Merchant owner keypair path (register)" })
+    .option("name", { type: "string", description: "Merchant display name, stored as a SHA-256 hash (register)" })
+    .option("merchant", { type: "string", description: "Merchant owner pubkey (kyc, tier, suspend, reinstate, pay, status)" })
+    .option("buyerKeypair", { type: "string", description: "Buyer keypair path, defaults to the admin (pay)" })
+    .option("amount", { type: "number", description: "Whole tokens to pay (pay)" })
+    .option("orderId", { type: "string", default: "", description: "Order ID (pay)" })
+    .option("reference", { type: "string", description: "Solana Pay reference, generated if omitted (pay)" })
+    .help()
+    .alias("help", "h").argv;
+
+  const provider = setupProvider();
+  const program = getProgram(provider);
+  const config = getConfigPda(program.programId);
+  const paymentConfig = getPaymentConfigPda(program.programId);
+  const ata = (owner: PublicKey) => getAssociatedTokenAddressSync(MINT_ADDRESS, owner, false, MINT_TOKEN_PROGRAM_ID);
+  const action = String(argv._[0]);
+  const attestor = argv.attestor ? new PublicKey(argv.attestor) : provider.wallet.publicKey;
+  const merchantPda = (): PublicKey => {
+    if (!argv.merchant) {
+      throw new Error("--merchant is required");
+    }
+    return getMerchantPda(program.programId, new PublicKey(argv.merchant));
+  };
+
+  console.log(`--- Merchant payments (${action}) ---`);
+
+  try {
+    let tx: string | undefined;
+    if (action === "init") {
+      tx = await program.methods
+        .initializePaymentConfig(argv.feeBps, attestor)
+        .accounts({
+          admin: provider.wallet.publicKey,
+          config,
+          paymentConfig,
+          mint: MINT_ADDRESS,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    } else if (action === "fee") {
+      tx = await program.methods
+        .updatePaymentFee(argv.tier, argv.feeBps)
+        .accounts({ admin: provider.wallet.publicKey, config, paymentConfig })
+        .rpc();
+    } else if (action === "attestor") {
+      tx = await program.methods
+        .setKycAttestor(attestor)
+        .accounts({ admin: provider.wallet.publicKey, config, paymentConfig })
+        .rpc();
+    } else if (action === "register") {
+      if (!argv.ownerKeypair || !argv.name) {
+        throw new Error("--ownerKeypair and --name are required");
+      }
+      const nameHash = [...createHash("sha256").update(argv.name).digest()];
+      const owner = loadKeypair(argv.ownerKeypair);
+      const merchant = getMerchantPda(program.programId, owner.publicKey);
+      tx = await program.methods
+        .registerMerchant(nameHash)
+        .accounts({
+          owner: owner.publicKey,
+          config,
+          merchant,
+          paymentConfig,
+          settlementAccount: ata(owner.publicKey),
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([owner])
+        .rpc();
+      console.log(`Merchant: ${merchant.toBase58()} (pending KYC)`);
+    } else if (action === "kyc") {
+      const signer = argv.attestorKeypair ? loadKeypair(argv.attestorKeypair) : (provider.wallet as anchor.Wallet).payer;
+      tx = await program.methods
+        .setMerchantKycStatus({ [argv.kycStatus]: {} })
+        .accounts({ attestor: signer.publicKey, config, paymentConfig, merchant: merchantPda() })
+        .signers([signer])
+        .rpc();
+    } else if (action === "tier") {
+      tx = await program.methods
+        .setMerchantFeeTier(argv.tier)
+        .accounts({ admin: provider.wallet.publicKey, config, merchant: merchantPda() })
+        .rpc();
+    } else if (action === "suspend" || action === "reinstate") {
+      const method = action === "suspend" ? program.methods.suspendMerchant() : program.methods.reinstateMerchant();
+      tx = await method.accounts({ admin: provider.wallet.publicKey, config, merchant: merchantPda() }).rpc();
+    } else if (action === "pay") {
+      if (!argv.merchant || argv.amount === undefined) {
+        throw new Error("--merchant and --amount are required");
+      }
+      const treasury = (await program.account.config.fetch(config)).treasury;
+      const buyer = argv.buyerKeypair ? loadKeypair(argv.buyerKeypair) : (provider.wallet as anchor.Wallet).payer;
+      const merchant = getMerchantPda(program.programId, new PublicKey(argv.merchant));
+      const state = await program.account.merchant.fetch(merchant);
+      const reference = argv.reference ? new PublicKey(argv.reference) : Keypair.generate().publicKey;
+      tx = await program.methods
+        .payMerchant(
+          new anchor.BN(argv.amount).mul(new anchor.BN(10).pow(new anchor.BN(TOKEN_DECIMALS))),
+          argv.orderId
+        )
+        .accounts({
+          buyer: buyer.publicKey,
+          config,
+          paymentConfig,
+          merchant,
+          buyerTokenAccount: ata(buyer.publicKey),
+          settlementAccount: state.settlementAccount,
+          treasuryTokenAccount: ata(treasury),
+          reference,
+          mint: MINT_ADDRESS,
+          tokenProgram: MINT_TOKEN_PROGRAM_ID,
+        })
+        .signers([buyer])
+        .rpc();
+      console.log(`Reference: ${reference.toBase58()}`);
+    } else if (action === "status") {
+      const state = await program.account.paymentConfig.fetch(paymentConfig);
+      console.log(`Mint: ${state.mint.toBase58()}`);
+      (state.feeTiers as number[]).forEach((bps, i) => console.log(`Fee tier ${i}: ${bps} bps`));
+      console.log(`KYC attestor: ${state.attestor.toBase58()}`);
+      if (argv.merchant) {
+        const merchant = await program.account.merchant.fetch(merchantPda());
+        console.log(`Merchant ${merchant.owner.toBase58()} settles to ${merchant.settlementAccount.toBase58()}`);
+        console.log(`  Name hash: ${Buffer.from(merchant.nameHash as number[]).toString("hex")}`);
+        console.log(`  Fee tier: ${merchant.feeTier}`);
+        console.log(`  KYC: ${Object.keys(merchant.kycStatus as object)[0]}${merchant.suspended ? " (suspended)" : ""}`);
+      }
+    } else {
+      throw new Error(`Unknown action: ${action}`);
+    }
+    if (tx) {
+      console.log("Transaction signature", tx);
+    }
+  } catch (err) {
+    console.error(`Failed to ${action}:`, err);
+    process.exit(1);
+  }
+}
+
+main().then(
+  () => process.exit(0),
+  (err) => {
+    console.error(err);
+    process.exit(1);
+  }
+);
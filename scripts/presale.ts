@@ -0,0 +1,252 @@
+import * as anchor from "@project-serum/anchor";
+import { PublicKey, SystemProgram, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  createAssociatedTokenAccountIdempotentInstruction,
+  createTransferCheckedInstruction,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+import {
+  setupProvider,
+  getProgram,
+  loadKeypair,
+  MINT_ADDRESS,
+  MINT_TOKEN_PROGRAM_ID,
+  TOKEN_DECIMALS,
+  SECONDS_PER_WEEK,
+  BuiltinTemplate,
+  PresalePhase,
+  getConfigPda,
+  getTemplatePda,
+  getBeneficiaryCounterPda,
+  getVestingPda,
+  fetchNextGrantIndex,
+  getPresalePda,
+  getPresaleSolVaultPda,
+  getPresaleReceiptPda,
+} from "./common";
+import yargs from "yargs";
+import { hideBin } from "yargs/helpers";
+
+// Stablecoin accepted by the presale (unset = SOL only); set STABLE_TOKEN_PROGRAM=token-2022 if needed
+const STABLE_MINT = process.env.STABLE_MINT ? new PublicKey(process.env.STABLE_MINT) : PublicKey.default;
+const STABLE_DECIMALS = parseInt(process.env.STABLE_DECIMALS || "6");
+const STABLE_TOKEN_PROGRAM_ID =
+  process.env.STABLE_TOKEN_PROGRAM === "token-2022" ? TOKEN_2022_PROGRAM_ID : TOKEN_PROGRAM_ID;
+
+/**
+ * Manage the on-chain presale.
+ *
+ *   init:     create the presale config (1M tokens for $250 up to 100M, 500k tokens for $150 up to 50M)
+ *   update:   update prices, e.g. after a SOL price move
+ *   open:     open a phase's sale window (the secondary only after the primary sells out or closes)
+ *   close:    close a phase's sale window early
+ *   fund:     move tokens for sale from the admin into the presale token vault
+ *   buy:      buy one batch of a phase and open its vesting account
+ *   withdraw: send the SOL and stablecoin proceeds to the treasury
+ *   status:   print the presale config and vault balances
+ */
+async function main() {
+  const argv = await yargs(hideBin(process.argv))
+    .command("init", "Create the presale config")
+    .command("update", "Update the presale config")
+    .command("open", "Open a phase's sale window")
+    .command("close", "Close a phase's sale window early")
+    .command("fund", "Fund the presale token vault")
+    .command("buy", "Buy one batch")
+    .command("withdraw", "Withdraw proceeds to the treasury")
+    .command("status", "Print the presale state")
+    .demandCommand(1)
+    .option("solPrice", { type: "number", description: "USD price of 1 SOL, used to price batches in SOL (init, update)" })
+    .option("amount", { type: "number", description: "Tokens to move into the vault, UI amount (fund)" })
+    .option("phase", { choices: ["primary", "secondary"], default: "primary", description: "Phase (open, close, buy)" })
+    .option("startTime", { type: "number", description: "Unix timestamp the sale opens at, defaults to now (open)" })
+    .option("primaryWeeks", { type: "number", default: 4, description: "Length of the primary sale window (init, update)" })
+    .option("stable", { type: "boolean", default: false, description: "Pay in the stablecoin instead of SOL (buy)" })
+    .option("buyerKeypair", { type: "string", description: "Buyer keypair path, defaults to the admin (buy)" })
+    .help()
+    .alias("help", "h").argv;
+
+  const provider = setupProvider();
+  const program = getProgram(provider);
+  const admin = provider.wallet.publicKey;
+  const config = getConfigPda(program.programId);
+  const presale = getPresalePda(program.programId);
+  const solVault = getPresaleSolVaultPda(program.programId);
+  const tokenVault = getAssociatedTokenAddressSync(MINT_ADDRESS, presale, true, MINT_TOKEN_PROGRAM_ID);
+  const stableVault = getAssociatedTokenAddressSync(STABLE_MINT, presale, true, STABLE_TOKEN_PROGRAM_ID);
+  const hasStable = !STABLE_MINT.equals(PublicKey.default);
+  const action = String(argv._[0]);
+
+  console.log(`--- Presale (${action}) ---`);
+
+  const phase = argv.phase === "secondary" ? PresalePhase.Secondary : PresalePhase.Primary;
+  const phaseArg = phase === PresalePhase.Primary ? { primary: {} } : { secondary: {} };
+
+  // Batch size, cap, window and $ price of each phase; SOL prices are derived from --solPrice
+  const phaseConfig = (tokens: number, cap: number, weeks: number, usd: number, templateId: number) => {
+    if (!argv.solPrice) {
+      throw new Error("--solPrice is required");
+    }
+    const unit = new anchor.BN(10).pow(new anchor.BN(TOKEN_DECIMALS));
+    return {
+      tokensPerBatch: new anchor.BN(tokens).mul(unit),
+      cap: new anchor.BN(cap).mul(unit),
+      duration: new anchor.BN(weeks * SECONDS_PER_WEEK),
+      priceLamports: new anchor.BN(Math.ceil((usd / argv.solPrice) * LAMPORTS_PER_SOL)),
+      priceStable: new anchor.BN(usd).mul(new anchor.BN(10).pow(new anchor.BN(STABLE_DECIMALS))),
+      templateId,
+    };
+  };
+
+  try {
+    if (action === "init" || action === "update") {
+      const primary = phaseConfig(1_000_000, 100_000_000, argv.primaryWeeks, 250, BuiltinTemplate.Presale1);
+      const secondary = phaseConfig(500_000, 50_000_000, 2, 150, BuiltinTemplate.Presale2);
+      const tx =
+        action === "init"
+          ? await program.methods
+              .initializePresale(STABLE_MINT, primary, secondary)
+              .accounts({ admin, config, presale, mint: MINT_ADDRESS, solVault, systemProgram: SystemProgram.programId })
+              .rpc()
+          : await program.methods
+              .updatePresale(STABLE_MINT, primary, secondary)
+              .accounts({ admin, config, presale })
+              .rpc();
+      console.log("Transaction signature", tx);
+    } else if (action === "open") {
+      const startTime = argv.startTime ?? Math.floor(Date.now() / 1000);
+      const tx = await program.methods
+        .openPresalePhase(phaseArg, new anchor.BN(startTime))
+        .accounts({ admin, config, presale })
+        .rpc();
+      console.log("Transaction signature", tx);
+    } else if (action === "close") {
+      const tx = await program.methods.closePresalePhase(phaseArg).accounts({ admin, config, presale }).rpc();
+      console.log("Transaction signature", tx);
+    } else if (action === "fund") {
+      if (!argv.amount) {
+        throw new Error("--amount is required");
+      }
+      // The vaults are the presale PDA's associated token accounts
+      const tx = new anchor.web3.Transaction().add(
+        createAssociatedTokenAccountIdempotentInstruction(
+          admin, tokenVault, presale, MINT_ADDRESS, MINT_TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID
+        ),
+        createTransferCheckedInstruction(
+          getAssociatedTokenAddressSync(MINT_ADDRESS, admin, false, MINT_TOKEN_PROGRAM_ID),
+          MINT_ADDRESS,
+          tokenVault,
+          admin,
+          BigInt(argv.amount) * BigInt(10) ** BigInt(TOKEN_DECIMALS),
+          TOKEN_DECIMALS,
+          [],
+          MINT_TOKEN_PROGRAM_ID
+        )
+      );
+      if (hasStable) {
+        tx.add(
+          createAssociatedTokenAccountIdempotentInstruction(
+            admin, stableVault, presale, STABLE_MINT, STABLE_TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID
+          )
+        );
+      }
+      console.log("Transaction signature", await provider.sendAndConfirm(tx));
+    } else if (action === "buy") {
+      const buyer = argv.buyerKeypair ? loadKeypair(argv.buyerKeypair) : (provider.wallet as anchor.Wallet).payer;
+      const presaleState = await program.account.presale.fetch(presale);
+      const { templateId } = phase === PresalePhase.Primary ? presaleState.primary : presaleState.secondary;
+      const grantIndex = await fetchNextGrantIndex(program, buyer.publicKey);
+      const vestingAccount = getVestingPda(program.programId, buyer.publicKey, grantIndex);
+
+      const tx = await program.methods
+        .buyPresaleBatch(phaseArg)
+        .accounts({
+          buyer: buyer.publicKey,
+          config,
+          presale,
+          template: getTemplatePda(program.programId, templateId),
+          receipt: getPresaleReceiptPda(program.programId, phase, buyer.publicKey),
+          beneficiaryCounter: getBeneficiaryCounterPda(program.programId, buyer.publicKey),
+          vestingAccount,
+          mint: MINT_ADDRESS,
+          tokenVault,
+          vestingTokenAccount: getAssociatedTokenAddressSync(MINT_ADDRESS, vestingAccount, true, MINT_TOKEN_PROGRAM_ID),
+          solVault,
+          // Stablecoin accounts are passed only to pay in stablecoin
+          stableMint: argv.stable ? STABLE_MINT : null,
+          buyerStableAccount: argv.stable
+            ? getAssociatedTokenAddressSync(STABLE_MINT, buyer.publicKey, false, STABLE_TOKEN_PROGRAM_ID)
+            : null,
+          stableVault: argv.stable ? stableVault : null,
+          stableTokenProgram: argv.stable ? STABLE_TOKEN_PROGRAM_ID : null,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: MINT_TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        })
+        .signers([buyer])
+        .rpc();
+      console.log(`Vesting account (grant #${grantIndex}): ${vestingAccount.toBase58()}`);
+      console.log("Transaction signature", tx);
+    } else if (action === "withdraw") {
+      const treasury = (await program.account.config.fetch(config)).treasury;
+      const tx = await program.methods
+        .withdrawPresaleProceeds()
+        .accounts({
+          admin,
+          config,
+          presale,
+          solVault,
+          treasury,
+          stableMint: hasStable ? STABLE_MINT : null,
+          stableVault: hasStable ? stableVault : null,
+          treasuryStableAccount: hasStable
+            ? getAssociatedTokenAddressSync(STABLE_MINT, treasury, false, STABLE_TOKEN_PROGRAM_ID)
+            : null,
+          stableTokenProgram: hasStable ? STABLE_TOKEN_PROGRAM_ID : null,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      console.log("Transaction signature", tx);
+    } else if (action === "status") {
+      const state = await program.account.presale.fetch(presale);
+      const phases = {
+        primary: [state.primary, state.primaryState],
+        secondary: [state.secondary, state.secondaryState],
+      };
+      for (const [name, [p, sale]] of Object.entries(phases)) {
+        console.log(
+          `${name}: ${p.tokensPerBatch.toString()} tokens per batch for ${p.priceLamports.toString()} lamports` +
+            ` or ${p.priceStable.toString()} stablecoin units (template #${p.templateId})`
+        );
+        const window = sale.startTime.isZero()
+          ? "not opened"
+          : `${new Date(sale.startTime.toNumber() * 1000).toISOString()} - ${new Date(sale.endTime.toNumber() * 1000).toISOString()}`;
+        console.log(`  sold ${sale.sold.toString()} / ${p.cap.toString()}, window: ${window}`);
+      }
+      console.log(`Stablecoin: ${state.stableMint.equals(PublicKey.default) ? "none" : state.stableMint.toBase58()}`);
+      const vault = await provider.connection.getTokenAccountBalance(tokenVault).catch(() => null);
+      console.log(`Tokens for sale: ${vault ? vault.value.uiAmountString : "vault not created"}`);
+      console.log(`SOL proceeds: ${(await provider.connection.getBalance(solVault)) / LAMPORTS_PER_SOL} SOL`);
+      if (hasStable) {
+        const stable = await provider.connection.getTokenAccountBalance(stableVault).catch(() => null);
+        console.log(`Stablecoin proceeds: ${stable ? stable.value.uiAmountString : "vault not created"}`);
+      }
+    } else {
+      throw new Error(`Unknown action: ${action}`);
+    }
+  } catch (err) {
+    console.error(`Failed to ${action}:`, err);
+    process.exit(1);
+  }
+}
+
+main().then(
+  () => process.exit(0),
+  (err) => {
+    console.error(err);
+    process.exit(1);
+  }
+);
@@ -3,8 +3,9 @@ import { PublicKey } from "@solana/web3.js";
 import {
   setupProvider,
   getProgram,
-  WalletType,
-  TOKEN_DECIMALS
+  BuiltinTemplate,
+  TOKEN_DECIMALS,
+  SECONDS_PER_WEEK
 } from "./common";
 import yargs from "yargs";
 import { hideBin } from "yargs/helpers";
@@ -32,15 +33,20 @@ async function main() {
 
     console.log(`Beneficiary (Authority): ${vestingAccountData.authority.toBase58()}`);
     console.log(`Mint: ${vestingAccountData.mint.toBase58()}`);
-    const walletTypeName = Object.keys(vestingAccountData.walletType)[0];
-    console.log(`Wallet Type: ${walletTypeName.charAt(0).toUpperCase() + walletTypeName.slice(1)}`); // Capitalize for display
+    const templateName = BuiltinTemplate[vestingAccountData.templateId] ?? "Custom";
+    console.log(`Template: #${vestingAccountData.templateId} (${templateName})`);
+    console.log(`Schedule Kind: ${Object.keys(vestingAccountData.scheduleKind)[0]}`);
     console.log(`Total Amount: ${vestingAccountData.totalAmount.toString()} (raw) / ${vestingAccountData.totalAmount.toNumber() / (10 ** TOKEN_DECIMALS)} (ui)`);
     console.log(`Claimed Amount: ${vestingAccountData.claimedAmount.toString()} (raw) / ${vestingAccountData.claimedAmount.toNumber() / (10 ** TOKEN_DECIMALS)} (ui)`);
+    console.log(`Staked Amount: ${vestingAccountData.stakedAmount.toString()} (raw) / ${vestingAccountData.stakedAmount.toNumber() / (10 ** TOKEN_DECIMALS)} (ui)`);
     console.log(`Bump: ${vestingAccountData.bump}`);
     console.log(`Schedule:`);
     vestingAccountData.schedule.forEach((s, index) => {
-      const releaseDate = new Date(s.releaseTime.toNumber() * 1000);
-      console.log(`  [${index}] Release Time: ${s.releaseTime.toString()} (${releaseDate.toISOString()})`);
+      const weeks = s.releaseOffset.toNumber() / SECONDS_PER_WEEK;
+      console.log(`  [${index}] Release Offset: ${s.releaseOffset.toString()}s after launch (${weeks} weeks)`);
+      if (!s.endOffset.eq(s.releaseOffset)) {
+        console.log(`      Linear until: ${s.endOffset.toString()}s, cliff at: ${s.cliffOffset.toString()}s`);
+      }
       console.log(`      Amount: ${s.amount.toString()} (raw) / ${s.amount.toNumber() / (10 ** TOKEN_DECIMALS)} (ui)`);
     });
 
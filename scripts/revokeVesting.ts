@@ -0,0 +1,78 @@
+import * as anchor from "@project-serum/anchor";
+import { PublicKey } from "@solana/web3.js";
+import {
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+import {
+  setupProvider,
+  getProgram,
+  MINT_ADDRESS,
+  MINT_TOKEN_PROGRAM_ID,
+  getLaunchConfigPda,
+  getConfigPda,
+} from "./common";
+import yargs from "yargs";
+import { hideBin } from "yargs/helpers";
+
+async function main() {
+  const argv = await yargs(hideBin(process.argv))
+    .option("vestingAccountPda", {
+      alias: "pda",
+      type: "string",
+      description: "Public key of the vesting account PDA to revoke",
+      demandOption: true,
+    })
+    .help()
+    .alias("help", "h").argv;
+
+  const provider = setupProvider();
+  const program = getProgram(provider);
+
+  // The unvested remainder must go to the treasury recorded in the program config
+  const treasuryWallet = (await program.account.config.fetch(getConfigPda(program.programId))).treasury;
+
+  const vestingAccount = new PublicKey(argv.vestingAccountPda);
+  const vestingData = await program.account.vestingAccount.fetch(vestingAccount);
+  const beneficiary = vestingData.authority;
+
+  console.log("--- Revoking Vesting ---");
+  console.log(`Vesting Account PDA: ${vestingAccount.toBase58()}`);
+  console.log(`Beneficiary: ${beneficiary.toBase58()}`);
+  console.log(`Treasury: ${treasuryWallet.toBase58()}`);
+
+  const vestingAta = getAssociatedTokenAddressSync(MINT_ADDRESS, vestingAccount, true, MINT_TOKEN_PROGRAM_ID);
+  const beneficiaryAta = getAssociatedTokenAddressSync(MINT_ADDRESS, beneficiary, false, MINT_TOKEN_PROGRAM_ID);
+  const treasuryAta = getAssociatedTokenAddressSync(MINT_ADDRESS, treasuryWallet, false, MINT_TOKEN_PROGRAM_ID);
+
+  try {
+    const tx = await program.methods
+      .revokeVesting()
+      .accounts({
+        admin: provider.wallet.publicKey,
+        config: getConfigPda(program.programId),
+        vestingAccount,
+        vestingTokenAccount: vestingAta,
+        destinationTokenAccount: beneficiaryAta, // Receives anything already unlocked
+        treasuryTokenAccount: treasuryAta, // Receives the unvested remainder
+        vestingSigner: vestingAccount,
+        launchConfig: getLaunchConfigPda(program.programId),
+        mint: MINT_ADDRESS,
+        tokenProgram: MINT_TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    console.log("Revoke transaction signature", tx);
+    console.log("Vesting revoked successfully!");
+  } catch (err) {
+    console.error("Failed to revoke vesting:", err);
+    process.exit(1);
+  }
+}
+
+main().then(
+  () => process.exit(0),
+  (err) => {
+    console.error(err);
+    process.exit(1);
+  }
+);
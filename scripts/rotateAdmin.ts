@@ -0,0 +1,76 @@
+import { PublicKey } from "@solana/web3.js";
+import { setupProvider, getProgram, loadKeypair, getConfigPda } from "./common";
+import yargs from "yargs";
+import { hideBin } from "yargs/helpers";
+
+/**
+ * Two-step admin rotation (e.g. moving admin to a multisig after launch).
+ *
+ *   propose: signed by the current admin (the provider wallet), names the new admin
+ *   accept:  signed by the new admin, completes the rotation
+ */
+async function main() {
+  const argv = await yargs(hideBin(process.argv))
+    .command("propose", "Propose a new admin (signed by the current admin)")
+    .command("accept", "Accept a pending rotation (signed by the new admin)")
+    .demandCommand(1)
+    .option("newAdmin", {
+      alias: "n",
+      type: "string",
+      description: "Public key of the new admin (propose only)",
+    })
+    .option("newAdminKeypair", {
+      alias: "k",
+      type: "string",
+      description: "Path to the new admin's keypair (accept only)",
+    })
+    .help()
+    .alias("help", "h").argv;
+
+  const provider = setupProvider();
+  const program = getProgram(provider);
+  const config = getConfigPda(program.programId);
+  const action = String(argv._[0]);
+
+  console.log(`--- Admin Rotation (${action}) ---`);
+
+  try {
+    let tx: string;
+    if (action === "propose") {
+      if (!argv.newAdmin) {
+        throw new Error("--newAdmin is required to propose a rotation");
+      }
+      const newAdmin = new PublicKey(argv.newAdmin);
+      console.log(`New Admin: ${newAdmin.toBase58()}`);
+      tx = await program.methods
+        .proposeAdmin(newAdmin)
+        .accounts({ admin: provider.wallet.publicKey, config })
+        .rpc();
+    } else if (action === "accept") {
+      if (!argv.newAdminKeypair) {
+        throw new Error("--newAdminKeypair is required to accept a rotation");
+      }
+      const newAdmin = loadKeypair(argv.newAdminKeypair);
+      tx = await program.methods
+        .acceptAdmin()
+        .accounts({ newAdmin: newAdmin.publicKey, config })
+        .signers([newAdmin])
+        .rpc();
+    } else {
+      throw new Error(`Unknown action: ${action}`);
+    }
+
+    console.log("Transaction signature", tx);
+  } catch (err) {
+    console.error(`Failed to ${action} admin rotation:`, err);
+    process.exit(1);
+  }
+}
+
+main().then(
+  () => process.exit(0),
+  (err) => {
+    console.error(err);
+    process.exit(1);
+  }
+);
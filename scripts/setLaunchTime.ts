@@ -0,0 +1,67 @@
+import * as anchor from "@project-serum/anchor";
+import { SystemProgram } from "@solana/web3.js";
+import { setupProvider, getProgram, getLaunchConfigPda, getConfigPda } from "./common";
+import yargs from "yargs";
+import { hideBin } from "yargs/helpers";
+
+async function main() {
+  const argv = await yargs(hideBin(process.argv))
+    .option("launchTime", {
+      alias: "t",
+      type: "number",
+      description: "Launch Unix timestamp (seconds). Omit to only create the launch config.",
+    })
+    .help()
+    .alias("help", "h").argv;
+
+  const provider = setupProvider();
+  const program = getProgram(provider);
+  const launchConfig = getLaunchConfigPda(program.programId);
+
+  console.log("--- Launch Config ---");
+  console.log(`Launch Config PDA: ${launchConfig.toBase58()}`);
+
+  // Create the launch config if it does not exist yet
+  const existing = await provider.connection.getAccountInfo(launchConfig);
+  if (!existing) {
+    const tx = await program.methods
+      .initializeLaunchConfig()
+      .accounts({
+        admin: provider.wallet.publicKey,
+        config: getConfigPda(program.programId),
+        launchConfig,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    console.log("Launch config initialized, signature", tx);
+  }
+
+  if (argv.launchTime === undefined) {
+    return;
+  }
+
+  // NOTE: The launch time can only be set once; it is frozen afterwards
+  try {
+    const tx = await program.methods
+      .setLaunchTime(new anchor.BN(argv.launchTime))
+      .accounts({
+        admin: provider.wallet.publicKey,
+        config: getConfigPda(program.programId),
+        launchConfig,
+      })
+      .rpc();
+    console.log(`Launch time set to ${argv.launchTime} (${new Date(argv.launchTime * 1000).toISOString()})`);
+    console.log("Transaction signature", tx);
+  } catch (err) {
+    console.error("Failed to set launch time:", err);
+    process.exit(1);
+  }
+}
+
+main().then(
+  () => process.exit(0),
+  (err) => {
+    console.error(err);
+    process.exit(1);
+  }
+);
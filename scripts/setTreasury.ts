@@ -0,0 +1,49 @@
+import { PublicKey } from "@solana/web3.js";
+import { setupProvider, getProgram, getConfigPda } from "./common";
+import yargs from "yargs";
+import { hideBin } from "yargs/helpers";
+
+/**
+ * Sets the treasury wallet receiving revoked tokens, presale proceeds and merchant payment fees.
+ * Must be signed by the config admin (the provider wallet).
+ */
+async function main() {
+  const argv = await yargs(hideBin(process.argv))
+    .option("treasury", {
+      alias: "t",
+      type: "string",
+      description: "Treasury public key",
+      demandOption: true,
+    })
+    .help()
+    .alias("help", "h").argv;
+
+  const provider = setupProvider();
+  const program = getProgram(provider);
+  const config = getConfigPda(program.programId);
+  const treasury = new PublicKey(argv.treasury);
+
+  console.log("--- Setting Treasury ---");
+  console.log(`Previous treasury: ${(await program.account.config.fetch(config)).treasury.toBase58()}`);
+  console.log(`New treasury: ${treasury.toBase58()}`);
+
+  try {
+    const tx = await program.methods
+      .setTreasury(treasury)
+      .accounts({ admin: provider.wallet.publicKey, config })
+      .rpc();
+
+    console.log("Transaction signature", tx);
+  } catch (err) {
+    console.error("Failed to set the treasury:", err);
+    process.exit(1);
+  }
+}
+
+main().then(
+  () => process.exit(0),
+  (err) => {
+    console.error(err);
+    process.exit(1);
+  }
+);
@@ -0,0 +1,138 @@
+import * as anchor from "@project-serum/anchor";
+import { PublicKey, SystemProgram } from "@solana/web3.js";
+import { getAssociatedTokenAddressSync } from "@solana/spl-token";
+import {
+  setupProvider,
+  getProgram,
+  getStakingProgram,
+  loadKeypair,
+  MINT_ADDRESS,
+  MINT_TOKEN_PROGRAM_ID,
+  getConfigPda,
+  getStakingPoolPda,
+  getStakeVaultPda,
+  getRewardVaultPda,
+  getStakePositionPda,
+} from "./common";
+import yargs from "yargs";
+import { hideBin } from "yargs/helpers";
+
+/**
+ * Stake the locked tokens of a vesting account. The vesting PDA owns the staking position.
+ *
+ *   stake:   commit unclaimed tokens to a staking tier
+ *   claim:   pay the position's accrued rewards to the beneficiary
+ *   unstake: return the stake to the vesting account after the lock ends
+ */
+async function main() {
+  const argv = await yargs(hideBin(process.argv))
+    .command("stake", "Stake vesting tokens")
+    .command("claim", "Claim staking rewards")
+    .command("unstake", "Unstake vesting tokens")
+    .demandCommand(1)
+    .option("beneficiaryKeypair", { alias: "k", type: "string", demandOption: true, description: "Path to the beneficiary's keypair file" })
+    .option("vestingAccountPda", { alias: "pda", type: "string", demandOption: true, description: "Public key of the vesting account PDA" })
+    .option("positionId", { type: "number", default: 0, description: "Position ID of the vesting account" })
+    .option("tier", { type: "number", description: "Staking tier index (stake)" })
+    .option("amount", { alias: "a", type: "string", description: "Raw amount to stake (stake)" })
+    .help()
+    .alias("help", "h").argv;
+
+  const provider = setupProvider();
+  const program = getProgram(provider);
+  const stakingProgram = getStakingProgram(provider);
+
+  const beneficiary = loadKeypair(argv.beneficiaryKeypair);
+  const vestingAccount = new PublicKey(argv.vestingAccountPda);
+  const pool = getStakingPoolPda(stakingProgram.programId, MINT_ADDRESS);
+  const position = getStakePositionPda(stakingProgram.programId, pool, vestingAccount, argv.positionId);
+  const vestingTokenAccount = getAssociatedTokenAddressSync(MINT_ADDRESS, vestingAccount, true, MINT_TOKEN_PROGRAM_ID);
+  const beneficiaryAta = getAssociatedTokenAddressSync(MINT_ADDRESS, beneficiary.publicKey, false, MINT_TOKEN_PROGRAM_ID);
+  const action = String(argv._[0]);
+
+  console.log(`--- Vesting stake (${action}) ---`);
+  console.log(`Vesting Account PDA: ${vestingAccount.toBase58()}`);
+  console.log(`Position #${argv.positionId}: ${position.toBase58()}`);
+
+  try {
+    let tx: string;
+    if (action === "stake") {
+      if (argv.tier === undefined || !argv.amount) {
+        throw new Error("--tier and --amount are required");
+      }
+      tx = await program.methods
+        .stakeVesting(argv.positionId, argv.tier, new anchor.BN(argv.amount))
+        .accounts({
+          authority: beneficiary.publicKey,
+          vestingAccount,
+          vestingTokenAccount,
+          config: getConfigPda(program.programId),
+          pool,
+          position,
+          stakeVault: getStakeVaultPda(stakingProgram.programId, pool),
+          rewardVault: getRewardVaultPda(stakingProgram.programId, pool),
+          mint: MINT_ADDRESS,
+          stakingProgram: stakingProgram.programId,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: MINT_TOKEN_PROGRAM_ID,
+        })
+        .signers([beneficiary])
+        .rpc();
+    } else if (action === "claim") {
+      tx = await program.methods
+        .claimVestingStakeRewards()
+        .accounts({
+          authority: beneficiary.publicKey,
+          vestingAccount,
+          config: getConfigPda(program.programId),
+          position,
+          pool,
+          destinationTokenAccount: beneficiaryAta,
+          rewardVault: getRewardVaultPda(stakingProgram.programId, pool),
+          mint: MINT_ADDRESS,
+          stakingProgram: stakingProgram.programId,
+          tokenProgram: MINT_TOKEN_PROGRAM_ID,
+        })
+        .signers([beneficiary])
+        .rpc();
+    } else if (action === "unstake") {
+      const state = await stakingProgram.account.stakePosition.fetch(position);
+      tx = await program.methods
+        .unstakeVesting()
+        .accounts({
+          authority: beneficiary.publicKey,
+          vestingAccount,
+          vestingTokenAccount,
+          config: getConfigPda(program.programId),
+          position,
+          positionPayer: state.payer,
+          pool,
+          destinationTokenAccount: beneficiaryAta,
+          stakeVault: getStakeVaultPda(stakingProgram.programId, pool),
+          rewardVault: getRewardVaultPda(stakingProgram.programId, pool),
+          mint: MINT_ADDRESS,
+          stakingProgram: stakingProgram.programId,
+          tokenProgram: MINT_TOKEN_PROGRAM_ID,
+        })
+        .signers([beneficiary])
+        .rpc();
+    } else {
+      throw new Error(`Unknown action: ${action}`);
+    }
+    console.log("Transaction signature", tx);
+  } catch (err) {
+    console.error(`Failed to ${action}:`, err);
+    if (err instanceof anchor.AnchorError) {
+      console.error(`AnchorError: ${err.error.errorMessage} (Code: ${err.error.errorCode.code})`);
+    }
+    process.exit(1);
+  }
+}
+
+main().then(
+  () => process.exit(0),
+  (err) => {
+    console.error(err);
+    process.exit(1);
+  }
+);
@@ -0,0 +1,211 @@
+import * as anchor from "@project-serum/anchor";
+import { PublicKey, SystemProgram } from "@solana/web3.js";
+import { getAssociatedTokenAddressSync } from "@solana/spl-token";
+import {
+  setupProvider,
+  getStakingProgram,
+  loadKeypair,
+  MINT_ADDRESS,
+  MINT_TOKEN_PROGRAM_ID,
+  TOKEN_DECIMALS,
+  getStakingPoolPda,
+  getStakeVaultPda,
+  getRewardVaultPda,
+  getStakePositionPda,
+} from "./common";
+import yargs from "yargs";
+import { hideBin } from "yargs/helpers";
+
+// Roadmap tiers: 2.5% for a 30 day lock up to 15% for 240 days
+const DEFAULT_TIERS = "30:250,60:500,90:750,180:1000,240:1500";
+
+// Parse "days:bps,days:bps" into tiers as expected by the program
+function parseTiers(tiers: string): { lockDays: number; aprBps: number }[] {
+  return tiers.split(",").map((tier) => {
+    const [lockDays, aprBps] = tier.split(":").map(Number);
+    return { lockDays, aprBps };
+  });
+}
+
+/**
+ * Manage the $TEAM staking pool.
+ *
+ *   init:    create the pool and its vaults (upgrade authority only)
+ *   tiers:   set the lock duration to APR tiers (pool admin only)
+ *   fund:    add tokens from the admin wallet to the reward vault
+ *   stake:   open a position in a tier
+ *   claim:   claim a position's accrued rewards
+ *   unstake: close a position after its lock ends
+ *   status:  print the pool, or a position with --positionId
+ */
+async function main() {
+  const argv = await yargs(hideBin(process.argv))
+    .command("init", "Create the staking pool")
+    .command("tiers", "Set the staking tiers")
+    .command("fund", "Fund the reward vault")
+    .command("stake", "Open a staking position")
+    .command("claim", "Claim a position's rewards")
+    .command("unstake", "Close a position after its lock")
+    .command("status", "Print the pool or a position")
+    .demandCommand(1)
+    .option("admin", { type: "string", description: "Pool admin, defaults to the upgrade authority (init)" })
+    .option("tiers", { type: "string", default: DEFAULT_TIERS, description: "days:bps pairs (tiers)" })
+    .option("amount", { type: "number", description: "Whole tokens (fund, stake)" })
+    .option("tier", { type: "number", description: "Tier index (stake)" })
+    .option("positionId", { type: "number", default: 0, description: "Position ID (stake, claim, unstake, status)" })
+    .option("stakerKeypair", { type: "string", description: "Staker keypair path, defaults to the admin" })
+    .help()
+    .alias("help", "h").argv;
+
+  const provider = setupProvider();
+  const program = getStakingProgram(provider);
+  const pool = getStakingPoolPda(program.programId, MINT_ADDRESS);
+  const stakeVault = getStakeVaultPda(program.programId, pool);
+  const rewardVault = getRewardVaultPda(program.programId, pool);
+  const staker = argv.stakerKeypair
+    ? loadKeypair(argv.stakerKeypair)
+    : (provider.wallet as anchor.Wallet).payer;
+  const position = getStakePositionPda(program.programId, pool, staker.publicKey, argv.positionId);
+  const stakerTokenAccount = getAssociatedTokenAddressSync(
+    MINT_ADDRESS, staker.publicKey, false, MINT_TOKEN_PROGRAM_ID
+  );
+  const action = String(argv._[0]);
+  const tokens = (): anchor.BN => {
+    if (argv.amount === undefined) {
+      throw new Error("--amount is required");
+    }
+    return new anchor.BN(argv.amount).mul(new anchor.BN(10).pow(new anchor.BN(TOKEN_DECIMALS)));
+  };
+
+  console.log(`--- Staking pool ${pool.toBase58()} (${action}) ---`);
+
+  try {
+    let tx: string | undefined;
+    if (action === "init") {
+      const [programData] = PublicKey.findProgramAddressSync(
+        [program.programId.toBuffer()],
+        new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+      );
+      const admin = argv.admin ? new PublicKey(argv.admin) : provider.wallet.publicKey;
+      tx = await program.methods
+        .initializePool(admin)
+        .accounts({
+          upgradeAuthority: provider.wallet.publicKey,
+          pool,
+          mint: MINT_ADDRESS,
+          stakeVault,
+          rewardVault,
+          program: program.programId,
+          programData,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: MINT_TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+    } else if (action === "tiers") {
+      const tiers = parseTiers(argv.tiers);
+      tx = await program.methods
+        .setTiers(tiers)
+        .accounts({ admin: provider.wallet.publicKey, pool, systemProgram: SystemProgram.programId })
+        .rpc();
+      tiers.forEach((t, i) => console.log(`Tier ${i}: ${t.lockDays} days at ${t.aprBps / 100}% APR`));
+    } else if (action === "fund") {
+      tx = await program.methods
+        .fundRewards(tokens())
+        .accounts({
+          funder: provider.wallet.publicKey,
+          pool,
+          funderTokenAccount: getAssociatedTokenAddressSync(
+            MINT_ADDRESS, provider.wallet.publicKey, false, MINT_TOKEN_PROGRAM_ID
+          ),
+          rewardVault,
+          mint: MINT_ADDRESS,
+          tokenProgram: MINT_TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+    } else if (action === "stake") {
+      if (argv.tier === undefined) {
+        throw new Error("--tier is required");
+      }
+      tx = await program.methods
+        .stake(argv.positionId, argv.tier, tokens())
+        .accounts({
+          payer: staker.publicKey,
+          owner: staker.publicKey,
+          pool,
+          position,
+          ownerTokenAccount: stakerTokenAccount,
+          stakeVault,
+          rewardVault,
+          mint: MINT_ADDRESS,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: MINT_TOKEN_PROGRAM_ID,
+        })
+        .signers([staker])
+        .rpc();
+      console.log(`Position #${argv.positionId}: ${position.toBase58()}`);
+    } else if (action === "claim") {
+      tx = await program.methods
+        .claimRewards()
+        .accounts({
+          owner: staker.publicKey,
+          pool,
+          position,
+          ownerTokenAccount: stakerTokenAccount,
+          rewardVault,
+          mint: MINT_ADDRESS,
+          tokenProgram: MINT_TOKEN_PROGRAM_ID,
+        })
+        .signers([staker])
+        .rpc();
+    } else if (action === "unstake") {
+      tx = await program.methods
+        .unstake()
+        .accounts({
+          owner: staker.publicKey,
+          payer: staker.publicKey,
+          pool,
+          position,
+          ownerTokenAccount: stakerTokenAccount,
+          rewardTokenAccount: stakerTokenAccount,
+          stakeVault,
+          rewardVault,
+          mint: MINT_ADDRESS,
+          tokenProgram: MINT_TOKEN_PROGRAM_ID,
+        })
+        .signers([staker])
+        .rpc();
+    } else if (action === "status") {
+      const state = await program.account.stakingPool.fetch(pool);
+      const rewards = await provider.connection.getTokenAccountBalance(rewardVault);
+      console.log(`Admin: ${state.admin.toBase58()}`);
+      (state.tiers as { lockDays: number; aprBps: number }[]).forEach((t, i) =>
+        console.log(`Tier ${i}: ${t.lockDays} days at ${t.aprBps / 100}% APR`)
+      );
+      console.log(`Total staked: ${state.totalStaked.toString()}`);
+      console.log(`Reward vault: ${rewards.value.amount} (${state.reservedRewards.toString()} reserved)`);
+      const p = await program.account.stakePosition.fetchNullable(position);
+      if (p) {
+        console.log(`Position #${argv.positionId} of ${staker.publicKey.toBase58()}:`);
+        console.log(`  Amount: ${p.amount.toString()} at ${p.aprBps / 100}% APR`);
+        console.log(`  Locked until: ${new Date(p.endTime.toNumber() * 1000).toISOString()}`);
+        console.log(`  Rewards claimed: ${p.claimedReward.toString()} / ${p.totalReward.toString()}`);
+      }
+    } else {
+      throw new Error(`Unknown action: ${action}`);
+    }
+    if (tx) {
+      console.log("Transaction signature", tx);
+    }
+  } catch (err) {
+    console.error(`Failed to ${action}:`, err);
+    process.exit(1);
+  }
+}
+
+main().then(
+  () => process.exit(0),
+  (err) => {
+    console.error(err);
+    process.exit(1);
+  }
+);
@@ -0,0 +1,144 @@
+import * as anchor from "@project-serum/anchor";
+import { PublicKey, SystemProgram } from "@solana/web3.js";
+import { ASSOCIATED_TOKEN_PROGRAM_ID, getAssociatedTokenAddressSync } from "@solana/spl-token";
+import {
+  setupProvider,
+  getProgram,
+  getStakingProgram,
+  MINT_ADDRESS,
+  MINT_TOKEN_PROGRAM_ID,
+  TOKEN_DECIMALS,
+  getConfigPda,
+  getStakingPoolPda,
+  getRewardVaultPda,
+} from "./common";
+import yargs from "yargs";
+import { hideBin } from "yargs/helpers";
+
+// Derive the staking reserve PDA (owner of the reserve vault)
+function getStakingReservePda(programId: PublicKey): PublicKey {
+  const [reserve] = PublicKey.findProgramAddressSync([Buffer.from("staking_reserve")], programId);
+  return reserve;
+}
+
+/**
+ * Manage the staking reserve set aside from the Marketing allocation.
+ *
+ *   init:    fund the reserve from the admin wallet; the deadline is nine months out
+ *   stake:   move the reserve into the staking reward vault (admin, before the deadline)
+ *   release: release the reserve to the community account (anyone, after the deadline)
+ *   status:  print the reserve
+ */
+async function main() {
+  const argv = await yargs(hideBin(process.argv))
+    .command("init", "Create and fund the staking reserve")
+    .command("stake", "Move the reserve to the staking reward vault")
+    .command("release", "Release the reserve to the community account")
+    .command("status", "Print the reserve")
+    .demandCommand(1)
+    .option("amount", { type: "number", description: "Whole tokens to reserve (init)" })
+    .option("releaseAccount", {
+      type: "string",
+      description: "Community token account, defaults to the TREASURY_WALLET's ATA (init)",
+    })
+    .help()
+    .alias("help", "h").argv;
+
+  const provider = setupProvider();
+  const program = getProgram(provider);
+  const stakingReserve = getStakingReservePda(program.programId);
+  const vault = getAssociatedTokenAddressSync(MINT_ADDRESS, stakingReserve, true, MINT_TOKEN_PROGRAM_ID);
+  const action = String(argv._[0]);
+
+  console.log(`--- Staking reserve ${stakingReserve.toBase58()} (${action}) ---`);
+
+  try {
+    let tx: string | undefined;
+    if (action === "init") {
+      if (argv.amount === undefined) {
+        throw new Error("--amount is required");
+      }
+      let releaseAccount: PublicKey;
+      if (argv.releaseAccount) {
+        releaseAccount = new PublicKey(argv.releaseAccount);
+      } else if (process.env.TREASURY_WALLET) {
+        releaseAccount = getAssociatedTokenAddressSync(
+          MINT_ADDRESS, new PublicKey(process.env.TREASURY_WALLET), false, MINT_TOKEN_PROGRAM_ID
+        );
+      } else {
+        throw new Error("--releaseAccount or TREASURY_WALLET is required");
+      }
+      tx = await program.methods
+        .initializeStakingReserve(
+          new anchor.BN(argv.amount).mul(new anchor.BN(10).pow(new anchor.BN(TOKEN_DECIMALS)))
+        )
+        .accounts({
+          admin: provider.wallet.publicKey,
+          config: getConfigPda(program.programId),
+          stakingReserve,
+          adminTokenAccount: getAssociatedTokenAddressSync(
+            MINT_ADDRESS, provider.wallet.publicKey, false, MINT_TOKEN_PROGRAM_ID
+          ),
+          releaseAccount,
+          mint: MINT_ADDRESS,
+          vault,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: MINT_TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+    } else if (action === "stake") {
+      const stakingProgram = getStakingProgram(provider);
+      const pool = getStakingPoolPda(stakingProgram.programId, MINT_ADDRESS);
+      tx = await program.methods
+        .fundStakingFromReserve()
+        .accounts({
+          admin: provider.wallet.publicKey,
+          config: getConfigPda(program.programId),
+          stakingReserve,
+          vault,
+          pool,
+          rewardVault: getRewardVaultPda(stakingProgram.programId, pool),
+          mint: MINT_ADDRESS,
+          stakingProgram: stakingProgram.programId,
+          tokenProgram: MINT_TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+    } else if (action === "release") {
+      const state = await program.account.stakingReserve.fetch(stakingReserve);
+      tx = await program.methods
+        .releaseStakingReserve()
+        .accounts({
+          config: getConfigPda(program.programId),
+          stakingReserve,
+          vault,
+          releaseAccount: state.releaseAccount,
+          mint: MINT_ADDRESS,
+          tokenProgram: MINT_TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+    } else if (action === "status") {
+      const state = await program.account.stakingReserve.fetch(stakingReserve);
+      console.log(`Amount: ${state.amount.toString()}`);
+      console.log(`Deadline: ${new Date(state.deadline.toNumber() * 1000).toISOString()}`);
+      console.log(`Release account: ${state.releaseAccount.toBase58()}`);
+      console.log(`Status: ${Object.keys(state.status)[0]}`);
+    } else {
+      throw new Error(`Unknown action: ${action}`);
+    }
+    if (tx) {
+      console.log("Transaction signature", tx);
+    }
+  } catch (err) {
+    console.error(`Failed to ${action}:`, err);
+    process.exit(1);
+  }
+}
+
+main().then(
+  () => process.exit(0),
+  (err) => {
+    console.error(err);
+    process.exit(1);
+  }
+);
@@ -0,0 +1,108 @@
+import * as anchor from "@project-serum/anchor";
+import { SystemProgram } from "@solana/web3.js";
+import { setupProvider, getProgram, getConfigPda, getTemplatePda, BuiltinTemplate } from "./common";
+import yargs from "yargs";
+import { hideBin } from "yargs/helpers";
+
+/**
+ * Manage on-chain schedule templates.
+ *
+ *   create-builtins: create the built-in templates (Dev, Marketing, Presale1, Presale2, Contributor)
+ *   create:          create a custom template, e.g. --id 10 --name Advisor --tranches 2500@26,2500@52,5000@104
+ *   retire:          retire a template so it can no longer be used for new grants
+ *   list:            print all templates
+ */
+async function main() {
+  const argv = await yargs(hideBin(process.argv))
+    .command("create-builtins", "Create the built-in templates")
+    .command("create", "Create a custom template")
+    .command("retire", "Retire a template")
+    .command("list", "List all templates")
+    .demandCommand(1)
+    .option("id", { type: "number", description: "Template ID (create, retire)" })
+    .option("name", { type: "string", description: "Template name (create)" })
+    .option("kind", { choices: ["cliff", "linear"], default: "cliff", description: "Schedule kind (create)" })
+    .option("tranches", {
+      type: "string",
+      description: "Comma-separated bps@week cliffs (create); omit for a free-form template",
+    })
+    .help()
+    .alias("help", "h").argv;
+
+  const provider = setupProvider();
+  const program = getProgram(provider);
+  const config = getConfigPda(program.programId);
+  const action = String(argv._[0]);
+
+  console.log(`--- Schedule Templates (${action}) ---`);
+
+  try {
+    if (action === "create-builtins") {
+      for (const id of Object.values(BuiltinTemplate).filter((v) => typeof v === "number") as number[]) {
+        const template = getTemplatePda(program.programId, id);
+        if (await provider.connection.getAccountInfo(template)) {
+          console.log(`Template #${id} (${BuiltinTemplate[id]}) already exists`);
+          continue;
+        }
+        const tx = await program.methods
+          .createBuiltinTemplate(id)
+          .accounts({ admin: provider.wallet.publicKey, config, template, systemProgram: SystemProgram.programId })
+          .rpc();
+        console.log(`Template #${id} (${BuiltinTemplate[id]}) created: ${tx}`);
+      }
+    } else if (action === "create") {
+      if (argv.id === undefined || !argv.name) {
+        throw new Error("--id and --name are required");
+      }
+      const tranches = (argv.tranches ? argv.tranches.split(",") : []).map((t) => {
+        const [bps, week] = t.split("@").map((v) => parseInt(v.trim()));
+        return { bps, week: new anchor.BN(week) };
+      });
+      const tx = await program.methods
+        .createTemplate(argv.id, argv.name, argv.kind === "linear" ? { linear: {} } : { cliff: {} }, tranches)
+        .accounts({
+          admin: provider.wallet.publicKey,
+          config,
+          template: getTemplatePda(program.programId, argv.id),
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      console.log("Transaction signature", tx);
+    } else if (action === "retire") {
+      if (argv.id === undefined) {
+        throw new Error("--id is required");
+      }
+      const tx = await program.methods
+        .retireTemplate()
+        .accounts({ admin: provider.wallet.publicKey, config, template: getTemplatePda(program.programId, argv.id) })
+        .rpc();
+      console.log("Transaction signature", tx);
+    } else if (action === "list") {
+      const templates = await program.account.scheduleTemplate.all();
+      templates
+        .sort((a, b) => a.account.templateId - b.account.templateId)
+        .forEach(({ account }) => {
+          const tranches = account.tranches.length
+            ? account.tranches.map((t: any) => `${t.bps}@${t.week.toString()}w`).join(", ")
+            : "free-form";
+          const kind = Object.keys(account.kind)[0];
+          console.log(
+            `#${account.templateId} ${account.name} (${kind}${account.retired ? ", retired" : ""}): ${tranches}`
+          );
+        });
+    } else {
+      throw new Error(`Unknown action: ${action}`);
+    }
+  } catch (err) {
+    console.error(`Failed to ${action}:`, err);
+    process.exit(1);
+  }
+}
+
+main().then(
+  () => process.exit(0),
+  (err) => {
+    console.error(err);
+    process.exit(1);
+  }
+);
@@ -0,0 +1,106 @@
+import * as anchor from "@project-serum/anchor";
+import { PublicKey, SystemProgram } from "@solana/web3.js";
+import {
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+import {
+  setupProvider,
+  getProgram,
+  MINT_ADDRESS,
+  MINT_TOKEN_PROGRAM_ID,
+  TOKEN_DECIMALS,
+  SECONDS_PER_WEEK,
+  ScheduleEntry,
+  getLaunchConfigPda,
+  getConfigPda,
+} from "./common";
+import yargs from "yargs";
+import { hideBin } from "yargs/helpers";
+
+async function main() {
+  const argv = await yargs(hideBin(process.argv))
+    .option("vestingAccountPda", {
+      alias: "pda",
+      type: "string",
+      description: "Public key of the vesting account PDA to top up",
+      demandOption: true,
+    })
+    .option("amount", {
+      alias: "a",
+      type: "number",
+      description: "Tokens to add (UI amount), split evenly across the new tranches",
+      demandOption: true,
+    })
+    .option("weeks", {
+      alias: "w",
+      type: "string",
+      description: "Comma-separated release weeks after launch of the new tranches, e.g. 40,44",
+      demandOption: true,
+    })
+    .option("durationWeeks", {
+      type: "number",
+      description: "Length of each tranche in weeks, for linear (Contributor) accounts",
+    })
+    .help()
+    .alias("help", "h").argv;
+
+  const provider = setupProvider();
+  const program = getProgram(provider);
+
+  const vestingAccount = new PublicKey(argv.vestingAccountPda);
+  const weeks = argv.weeks.split(",").map((w) => parseInt(w.trim()));
+  const totalAmount = new anchor.BN(argv.amount).mul(new anchor.BN(10).pow(new anchor.BN(TOKEN_DECIMALS)));
+
+  // Split the amount evenly; the last tranche gets the remainder
+  const perTranche = totalAmount.div(new anchor.BN(weeks.length));
+  const tranches: ScheduleEntry[] = weeks.map((week, i) => {
+    const amount =
+      i === weeks.length - 1 ? totalAmount.sub(perTranche.mul(new anchor.BN(weeks.length - 1))) : perTranche;
+    const start = new anchor.BN(week * SECONDS_PER_WEEK);
+    // Cliffs unlock at once; linear tranches stream over `durationWeeks`
+    const end = argv.durationWeeks ? start.add(new anchor.BN(argv.durationWeeks * SECONDS_PER_WEEK)) : start;
+    return { releaseOffset: start, amount, endOffset: end, cliffOffset: start };
+  });
+
+  console.log("--- Topping Up Vesting ---");
+  console.log(`Vesting Account PDA: ${vestingAccount.toBase58()}`);
+  tranches.forEach((t) =>
+    console.log(`  + ${t.amount.toString()} at week ${t.releaseOffset.toNumber() / SECONDS_PER_WEEK}`)
+  );
+
+  try {
+    const tx = await program.methods
+      .topUpVesting(tranches)
+      .accounts({
+        admin: provider.wallet.publicKey,
+        config: getConfigPda(program.programId),
+        vestingAccount,
+        adminTokenAccount: getAssociatedTokenAddressSync(
+          MINT_ADDRESS,
+          provider.wallet.publicKey,
+          false,
+          MINT_TOKEN_PROGRAM_ID
+        ),
+        vestingTokenAccount: getAssociatedTokenAddressSync(MINT_ADDRESS, vestingAccount, true, MINT_TOKEN_PROGRAM_ID),
+        mint: MINT_ADDRESS,
+        launchConfig: getLaunchConfigPda(program.programId),
+        tokenProgram: MINT_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    console.log("Top-up transaction signature", tx);
+    console.log("Vesting topped up successfully!");
+  } catch (err) {
+    console.error("Failed to top up vesting:", err);
+    process.exit(1);
+  }
+}
+
+main().then(
+  () => process.exit(0),
+  (err) => {
+    console.error(err);
+    process.exit(1);
+  }
+);
@@ -0,0 +1,83 @@
+import { PublicKey } from "@solana/web3.js";
+import { setupProvider, getProgram, loadKeypair, getConfigPda } from "./common";
+import yargs from "yargs";
+import { hideBin } from "yargs/helpers";
+
+/**
+ * Two-step transfer of a vesting position to a new wallet (e.g. the Team556 app wallet).
+ *
+ *   propose: signed by the current beneficiary, names the new wallet
+ *   accept:  signed by the new wallet, completes the transfer
+ */
+async function main() {
+  const argv = await yargs(hideBin(process.argv))
+    .command("propose", "Propose a new authority (signed by the current beneficiary)")
+    .command("accept", "Accept a pending transfer (signed by the new authority)")
+    .demandCommand(1)
+    .option("vestingAccountPda", {
+      alias: "pda",
+      type: "string",
+      description: "Public key of the vesting account PDA",
+      demandOption: true,
+    })
+    .option("signerKeypair", {
+      alias: "k",
+      type: "string",
+      description: "Path to the keypair of the current beneficiary (propose) or new authority (accept)",
+      demandOption: true,
+    })
+    .option("newAuthority", {
+      alias: "n",
+      type: "string",
+      description: "Public key of the new authority (propose only)",
+    })
+    .help()
+    .alias("help", "h").argv;
+
+  const provider = setupProvider();
+  const program = getProgram(provider);
+
+  const action = String(argv._[0]);
+  const signer = loadKeypair(argv.signerKeypair);
+  const vestingAccount = new PublicKey(argv.vestingAccountPda);
+
+  console.log(`--- Vesting Authority Transfer (${action}) ---`);
+  console.log(`Vesting Account PDA: ${vestingAccount.toBase58()}`);
+
+  try {
+    let tx: string;
+    if (action === "propose") {
+      if (!argv.newAuthority) {
+        throw new Error("--newAuthority is required to propose a transfer");
+      }
+      const newAuthority = new PublicKey(argv.newAuthority);
+      console.log(`New Authority: ${newAuthority.toBase58()}`);
+      tx = await program.methods
+        .proposeAuthorityTransfer(newAuthority)
+        .accounts({ vestingAccount, authority: signer.publicKey, config: getConfigPda(program.programId) })
+        .signers([signer])
+        .rpc();
+    } else if (action === "accept") {
+      tx = await program.methods
+        .acceptAuthorityTransfer()
+        .accounts({ vestingAccount, newAuthority: signer.publicKey, config: getConfigPda(program.programId) })
+        .signers([signer])
+        .rpc();
+    } else {
+      throw new Error(`Unknown action: ${action}`);
+    }
+
+    console.log("Transaction signature", tx);
+  } catch (err) {
+    console.error(`Failed to ${action} authority transfer:`, err);
+    process.exit(1);
+  }
+}
+
+main().then(
+  () => process.exit(0),
+  (err) => {
+    console.error(err);
+    process.exit(1);
+  }
+);
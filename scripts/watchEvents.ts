@@ -0,0 +1,65 @@
+import { BN } from "@project-serum/anchor";
+import { PublicKey } from "@solana/web3.js";
+import { setupProvider, getProgram } from "./common";
+
+// Events emitted by the vesting program, as named in the IDL.
+const EVENTS = [
+  "VestingInitialized",
+  "VestingToppedUp",
+  "TokensClaimed",
+  "AdminDistributed",
+  "VestingRevoked",
+  "AuthorityTransferProposed",
+  "AuthorityTransferred",
+  "VestingClosed",
+  "PresaleConfigured",
+  "PresalePhaseOpened",
+  "PresalePhaseClosed",
+  "PresaleBatchPurchased",
+  "PresaleProceedsWithdrawn",
+  "DistributorCreated",
+  "DistributorClaimed",
+  "VestingStaked",
+  "VestingStakeRewardsClaimed",
+  "VestingUnstaked",
+  "StakingReserveInitialized",
+  "StakingReserveSettled",
+  "PaymentFeeUpdated",
+  "KycAttestorUpdated",
+  "MerchantRegistered",
+  "MerchantKycUpdated",
+  "MerchantFeeTierUpdated",
+  "MerchantSuspensionUpdated",
+  "MerchantPaid",
+];
+
+async function main() {
+  const provider = setupProvider();
+  const program = getProgram(provider);
+
+  console.log(`--- Watching vesting events for program ${program.programId.toBase58()} ---`);
+
+  for (const name of EVENTS) {
+    program.addEventListener(name, (event: any, slot: number, signature: string) => {
+      const fields: Record<string, string> = {};
+      for (const [key, value] of Object.entries(event)) {
+        // Pubkeys and u64s are printed as strings; enums as their variant name.
+        fields[key] =
+          value instanceof PublicKey || BN.isBN(value)
+            ? value.toString()
+            : typeof value === "object" && value !== null
+            ? Object.keys(value)[0]
+            : String(value);
+      }
+      console.log(JSON.stringify({ event: name, slot, signature, ...fields }));
+    });
+  }
+
+  // Keep the process alive until interrupted.
+  await new Promise(() => {});
+}
+
+main().catch((err) => {
+  console.error(err);
+  process.exit(1);
+});
@@ -0,0 +1,84 @@
+import * as anchor from '@coral-xyz/anchor'
+import BN from 'bn.js'
+import assert from 'assert'
+import * as web3 from '@solana/web3.js'
+import {
+  TOKEN_PROGRAM_ID,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount
+} from '@solana/spl-token'
+import type { Team } from '../target/types/team'
+import {
+  MARKETING_CLIFFS,
+  MARKETING_TEMPLATE,
+  configPda,
+  createFundedMint,
+  ensureConfigAndLaunch,
+  initializeGrant,
+  launchConfigPda,
+  templateSchedule
+} from './setup'
+
+const TOTAL_AMOUNT = new BN(1_000_000_000_000)
+
+describe('admin distribution', () => {
+  const provider = anchor.AnchorProvider.env()
+  anchor.setProvider(provider)
+
+  const program = anchor.workspace.Team as anchor.Program<Team>
+  const admin = (provider.wallet as anchor.Wallet).payer
+  const beneficiary = web3.Keypair.generate()
+  let mint: web3.PublicKey
+  let vestingAccount: web3.PublicKey
+
+  const ata = (owner: web3.PublicKey, offCurve = false) => getAssociatedTokenAddressSync(mint, owner, offCurve)
+
+  const distribute = (signer: web3.Keypair, destinationTokenAccount = ata(beneficiary.publicKey)) =>
+    program.methods
+      .adminDistributeUnlocked()
+      .accounts({
+        admin: signer.publicKey,
+        config: configPda(program),
+        vestingAccount,
+        authority: beneficiary.publicKey,
+        vestingTokenAccount: ata(vestingAccount, true),
+        destinationTokenAccount,
+        vestingSigner: vestingAccount,
+        launchConfig: launchConfigPda(program),
+        mint,
+        tokenProgram: TOKEN_PROGRAM_ID
+      })
+      .signers([signer])
+      .rpc()
+
+  before(async () => {
+    await ensureConfigAndLaunch(program, admin)
+    mint = await createFundedMint(program, admin)
+    await getOrCreateAssociatedTokenAccount(provider.connection, admin, mint, beneficiary.publicKey)
+    vestingAccount = await initializeGrant(
+      program, admin, mint, beneficiary.publicKey, MARKETING_TEMPLATE, templateSchedule(MARKETING_CLIFFS, TOTAL_AMOUNT)
+    )
+  })
+
+  it('rejects a signer other than the config admin', async () => {
+    const outsider = web3.Keypair.generate()
+    await assert.rejects(distribute(outsider), /Unauthorized/)
+  })
+
+  it('rejects a destination not owned by the beneficiary', async () => {
+    await assert.rejects(distribute(admin, ata(admin.publicKey)), /InvalidAuthority/)
+  })
+
+  it('pushes the unlocked half to the beneficiary without their signature', async () => {
+    await distribute(admin)
+
+    const half = TOTAL_AMOUNT.divn(2).toString()
+    assert.strictEqual((await getAccount(provider.connection, ata(beneficiary.publicKey))).amount.toString(), half)
+    assert.strictEqual((await program.account.vestingAccount.fetch(vestingAccount)).claimedAmount.toString(), half)
+  })
+
+  it('fails once everything unlocked has been distributed', async () => {
+    await assert.rejects(distribute(admin), /NothingToClaim/)
+  })
+})
@@ -0,0 +1,58 @@
+import * as anchor from '@coral-xyz/anchor'
+import assert from 'assert'
+import * as web3 from '@solana/web3.js'
+import type { Team } from '../target/types/team'
+import { configPda, ensureConfigAndLaunch } from './setup'
+
+describe('admin rotation', () => {
+  const provider = anchor.AnchorProvider.env()
+  anchor.setProvider(provider)
+
+  const program = anchor.workspace.Team as anchor.Program<Team>
+  const admin = (provider.wallet as anchor.Wallet).payer
+  const config = configPda(program)
+  const [newAdmin, outsider] = [web3.Keypair.generate(), web3.Keypair.generate()]
+
+  const propose = (signer: web3.Keypair, proposed: web3.PublicKey) =>
+    program.methods.proposeAdmin(proposed).accounts({ admin: signer.publicKey, config }).signers([signer]).rpc()
+  const accept = (signer: web3.Keypair) =>
+    program.methods.acceptAdmin().accounts({ newAdmin: signer.publicKey, config }).signers([signer]).rpc()
+  const configAdmin = async () => (await program.account.config.fetch(config)).admin
+
+  before(async () => {
+    await ensureConfigAndLaunch(program, admin)
+  })
+
+  // The config is shared by every test file: hand the admin key back whatever happened.
+  after(async () => {
+    if ((await configAdmin()).equals(newAdmin.publicKey)) {
+      await propose(newAdmin, admin.publicKey)
+      await accept(admin)
+    }
+  })
+
+  it('rejects a proposal from someone other than the admin', async () => {
+    await assert.rejects(propose(outsider, outsider.publicKey), /Unauthorized/)
+  })
+
+  it('keeps the admin until the proposed wallet accepts', async () => {
+    await propose(admin, newAdmin.publicKey)
+    assert.ok((await program.account.config.fetch(config)).pendingAdmin!.equals(newAdmin.publicKey))
+    assert.ok((await configAdmin()).equals(admin.publicKey))
+
+    await assert.rejects(accept(outsider), /InvalidPendingAdmin/)
+  })
+
+  it('hands the admin key to the proposed wallet once it accepts', async () => {
+    await accept(newAdmin)
+    const state = await program.account.config.fetch(config)
+    assert.ok(state.admin.equals(newAdmin.publicKey))
+    assert.strictEqual(state.pendingAdmin, null)
+
+    // The previous admin has lost its rights; the new admin rotates the key back.
+    await assert.rejects(propose(admin, admin.publicKey), /Unauthorized/)
+    await propose(newAdmin, admin.publicKey)
+    await accept(admin)
+    assert.ok((await configAdmin()).equals(admin.publicKey))
+  })
+})
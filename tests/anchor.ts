@@ -1,41 +0,0 @@
-import * as anchor from '@coral-xyz/anchor'
-import BN from 'bn.js'
-import assert from 'assert'
-import * as web3 from '@solana/web3.js'
-import type { HelloAnchor } from '../target/types/hello_anchor'
-
-describe('Test', () => {
-  // Configure the client to use the local cluster
-  anchor.setProvider(anchor.AnchorProvider.env())
-
-  const program = anchor.workspace.HelloAnchor as anchor.Program<HelloAnchor>
-
-  it('initialize', async () => {
-    // Generate keypair for the new account
-    const newAccountKp = new web3.Keypair()
-
-    // Send transaction
-    const data = new BN(42)
-    const txHash = await program.methods
-      .initialize(data)
-      .accounts({
-        newAccount: newAccountKp.publicKey,
-        signer: program.provider.publicKey,
-        systemProgram: web3.SystemProgram.programId
-      })
-      .signers([newAccountKp])
-      .rpc()
-    console.log(`Use 'solana confirm -v ${txHash}' to see the logs`)
-
-    // Confirm transaction
-    await program.provider.connection.confirmTransaction(txHash)
-
-    // Fetch the created account
-    const newAccount = await program.account.newAccount.fetch(newAccountKp.publicKey)
-
-    console.log('On-chain data is:', newAccount.data.toString())
-
-    // Check whether the data on-chain is equal to local 'data'
-    assert(data.eq(newAccount.data))
-  })
-})
@@ -0,0 +1,88 @@
+import * as anchor from '@coral-xyz/anchor'
+import BN from 'bn.js'
+import assert from 'assert'
+import * as web3 from '@solana/web3.js'
+import { getAccount, getAssociatedTokenAddressSync, getOrCreateAssociatedTokenAccount } from '@solana/spl-token'
+import type { Team } from '../target/types/team'
+import {
+  PRESALE2_TEMPLATE,
+  claimAccounts,
+  cliffEntry,
+  configPda,
+  createFundedMint,
+  ensureConfigAndLaunch,
+  initializeGrant
+} from './setup'
+
+const TOTAL_AMOUNT = new BN(1_000_000_000_000)
+
+describe('vesting authority transfer', () => {
+  const provider = anchor.AnchorProvider.env()
+  anchor.setProvider(provider)
+
+  const program = anchor.workspace.Team as anchor.Program<Team>
+  const admin = (provider.wallet as anchor.Wallet).payer
+  const config = configPda(program)
+  const [beneficiary, newWallet, outsider] = [web3.Keypair.generate(), web3.Keypair.generate(), web3.Keypair.generate()]
+  let mint: web3.PublicKey
+  let vestingAccount: web3.PublicKey
+
+  const propose = (signer: web3.Keypair, newAuthority: web3.PublicKey) =>
+    program.methods
+      .proposeAuthorityTransfer(newAuthority)
+      .accounts({ vestingAccount, authority: signer.publicKey, config })
+      .signers([signer])
+      .rpc()
+  const accept = (signer: web3.Keypair) =>
+    program.methods
+      .acceptAuthorityTransfer()
+      .accounts({ vestingAccount, newAuthority: signer.publicKey, config })
+      .signers([signer])
+      .rpc()
+  const claim = (signer: web3.Keypair) =>
+    program.methods
+      .claimUnlocked()
+      .accounts(claimAccounts(program, mint, vestingAccount, signer.publicKey))
+      .signers([signer])
+      .rpc()
+
+  before(async () => {
+    await ensureConfigAndLaunch(program, admin)
+    mint = await createFundedMint(program, admin)
+    for (const wallet of [beneficiary, newWallet]) {
+      await getOrCreateAssociatedTokenAccount(provider.connection, admin, mint, wallet.publicKey)
+    }
+    vestingAccount = await initializeGrant(
+      program, admin, mint, beneficiary.publicKey, PRESALE2_TEMPLATE, [cliffEntry(12, TOTAL_AMOUNT)]
+    )
+  })
+
+  it('rejects a proposal from someone other than the authority', async () => {
+    await assert.rejects(propose(outsider, outsider.publicKey), /InvalidAuthority/)
+  })
+
+  it('rejects a transfer to the current authority', async () => {
+    await assert.rejects(propose(beneficiary, beneficiary.publicKey), /InvalidAuthority/)
+  })
+
+  it('lets only the proposed wallet accept', async () => {
+    await propose(beneficiary, newWallet.publicKey)
+    assert.ok((await program.account.vestingAccount.fetch(vestingAccount)).pendingAuthority!.equals(newWallet.publicKey))
+
+    await assert.rejects(accept(outsider), /InvalidPendingAuthority/)
+  })
+
+  it('moves the position to the new wallet at the same address', async () => {
+    await accept(newWallet)
+
+    const vesting = await program.account.vestingAccount.fetch(vestingAccount)
+    assert.ok(vesting.authority.equals(newWallet.publicKey))
+    assert.ok(vesting.originalBeneficiary.equals(beneficiary.publicKey))
+    assert.strictEqual(vesting.pendingAuthority, null)
+
+    await assert.rejects(claim(beneficiary), /InvalidAuthority/)
+    await claim(newWallet)
+    const received = await getAccount(provider.connection, getAssociatedTokenAddressSync(mint, newWallet.publicKey))
+    assert.strictEqual(received.amount.toString(), TOTAL_AMOUNT.toString())
+  })
+})
@@ -0,0 +1,68 @@
+import * as anchor from '@coral-xyz/anchor'
+import BN from 'bn.js'
+import assert from 'assert'
+import * as web3 from '@solana/web3.js'
+import { getAccount, getAssociatedTokenAddressSync, getOrCreateAssociatedTokenAccount } from '@solana/spl-token'
+import type { Team } from '../target/types/team'
+import {
+  MARKETING_CLIFFS,
+  MARKETING_TEMPLATE,
+  claimAccounts,
+  createFundedMint,
+  ensureConfigAndLaunch,
+  initializeGrant,
+  templateSchedule
+} from './setup'
+
+const TOTAL_AMOUNT = new BN(1_000_000_000_000)
+// Half of the Marketing cliffs have unlocked (launch was 13 weeks ago).
+const UNLOCKED = TOTAL_AMOUNT.divn(2)
+
+describe('partial claims', () => {
+  const provider = anchor.AnchorProvider.env()
+  anchor.setProvider(provider)
+
+  const program = anchor.workspace.Team as anchor.Program<Team>
+  const admin = (provider.wallet as anchor.Wallet).payer
+  const beneficiary = web3.Keypair.generate()
+  let mint: web3.PublicKey
+  let vestingAccount: web3.PublicKey
+
+  const claimAmount = (amount: BN) =>
+    program.methods
+      .claimAmount(amount)
+      .accounts(claimAccounts(program, mint, vestingAccount, beneficiary.publicKey))
+      .signers([beneficiary])
+      .rpc()
+  const claimed = async () => (await program.account.vestingAccount.fetch(vestingAccount)).claimedAmount.toString()
+
+  before(async () => {
+    await ensureConfigAndLaunch(program, admin)
+    mint = await createFundedMint(program, admin)
+    await getOrCreateAssociatedTokenAccount(provider.connection, admin, mint, beneficiary.publicKey)
+    vestingAccount = await initializeGrant(
+      program, admin, mint, beneficiary.publicKey, MARKETING_TEMPLATE, templateSchedule(MARKETING_CLIFFS, TOTAL_AMOUNT)
+    )
+  })
+
+  it('rejects a zero amount', async () => {
+    await assert.rejects(claimAmount(new BN(0)), /InvalidClaimAmount/)
+  })
+
+  it('rejects more than the claimable amount', async () => {
+    await assert.rejects(claimAmount(UNLOCKED.addn(1)), /ClaimAmountExceedsClaimable/)
+  })
+
+  it('claims the unlocked tokens in parts', async () => {
+    const part = UNLOCKED.divn(4)
+    await claimAmount(part)
+    assert.strictEqual(await claimed(), part.toString())
+
+    await claimAmount(UNLOCKED.sub(part))
+    assert.strictEqual(await claimed(), UNLOCKED.toString())
+    const received = await getAccount(provider.connection, getAssociatedTokenAddressSync(mint, beneficiary.publicKey))
+    assert.strictEqual(received.amount.toString(), UNLOCKED.toString())
+
+    await assert.rejects(claimAmount(new BN(1)), /NothingToClaim/)
+  })
+})
@@ -0,0 +1,118 @@
+import * as anchor from '@coral-xyz/anchor'
+import BN from 'bn.js'
+import assert from 'assert'
+import * as web3 from '@solana/web3.js'
+import { TOKEN_PROGRAM_ID, getAssociatedTokenAddressSync, getOrCreateAssociatedTokenAccount } from '@solana/spl-token'
+import type { Team } from '../target/types/team'
+import {
+  MARKETING_CLIFFS,
+  MARKETING_TEMPLATE,
+  PRESALE2_TEMPLATE,
+  claimAccounts,
+  cliffEntry,
+  configPda,
+  configTreasury,
+  createFundedMint,
+  ensureConfigAndLaunch,
+  initializeGrant,
+  launchConfigPda,
+  templateSchedule
+} from './setup'
+
+const TOTAL_AMOUNT = new BN(1_000_000_000_000)
+
+describe('close vesting', () => {
+  const provider = anchor.AnchorProvider.env()
+  anchor.setProvider(provider)
+
+  const program = anchor.workspace.Team as anchor.Program<Team>
+  const admin = (provider.wallet as anchor.Wallet).payer
+  const config = configPda(program)
+  let mint: web3.PublicKey
+
+  const ata = (owner: web3.PublicKey, offCurve = false) => getAssociatedTokenAddressSync(mint, owner, offCurve)
+
+  // Initializes a Marketing grant (half unlocked) and claims what has unlocked.
+  const initializeClaimedGrant = async (beneficiary: web3.Keypair, templateId = MARKETING_TEMPLATE, revocable = false) => {
+    await getOrCreateAssociatedTokenAccount(provider.connection, admin, mint, beneficiary.publicKey)
+    const schedule =
+      templateId === MARKETING_TEMPLATE ? templateSchedule(MARKETING_CLIFFS, TOTAL_AMOUNT) : [cliffEntry(12, TOTAL_AMOUNT)]
+    const vestingAccount = await initializeGrant(program, admin, mint, beneficiary.publicKey, templateId, schedule, revocable)
+    await program.methods
+      .claimUnlocked()
+      .accounts(claimAccounts(program, mint, vestingAccount, beneficiary.publicKey))
+      .signers([beneficiary])
+      .rpc()
+    return vestingAccount
+  }
+
+  const close = (vestingAccount: web3.PublicKey, closer: web3.Keypair, payer = admin.publicKey) =>
+    program.methods
+      .closeVesting()
+      .accounts({
+        closer: closer.publicKey,
+        config,
+        vestingAccount,
+        vestingTokenAccount: ata(vestingAccount, true),
+        payer,
+        mint,
+        tokenProgram: TOKEN_PROGRAM_ID
+      })
+      .signers([closer])
+      .rpc()
+
+  before(async () => {
+    await ensureConfigAndLaunch(program, admin)
+    mint = await createFundedMint(program, admin)
+  })
+
+  it('rejects closing a grant that still has locked tokens', async () => {
+    const beneficiary = web3.Keypair.generate()
+    const vestingAccount = await initializeClaimedGrant(beneficiary)
+    await assert.rejects(close(vestingAccount, beneficiary), /ScheduleNotExhausted/)
+  })
+
+  it('lets the beneficiary close a fully claimed grant and refunds the rent payer', async () => {
+    const beneficiary = web3.Keypair.generate()
+    const vestingAccount = await initializeClaimedGrant(beneficiary, PRESALE2_TEMPLATE)
+
+    const outsider = web3.Keypair.generate()
+    await assert.rejects(close(vestingAccount, outsider), /Unauthorized/)
+    await assert.rejects(close(vestingAccount, beneficiary, beneficiary.publicKey), /InvalidPayer/)
+
+    const rent =
+      (await provider.connection.getBalance(vestingAccount)) + (await provider.connection.getBalance(ata(vestingAccount, true)))
+    const before = await provider.connection.getBalance(admin.publicKey)
+    await close(vestingAccount, beneficiary)
+
+    assert.strictEqual(await provider.connection.getAccountInfo(vestingAccount), null)
+    assert.strictEqual(await provider.connection.getAccountInfo(ata(vestingAccount, true)), null)
+    // The admin paid the transaction fee.
+    assert.ok((await provider.connection.getBalance(admin.publicKey)) > before + rent - 10_000)
+  })
+
+  it('lets the admin close a revoked grant', async () => {
+    const beneficiary = web3.Keypair.generate()
+    const vestingAccount = await initializeClaimedGrant(beneficiary, MARKETING_TEMPLATE, true)
+    const treasury = await configTreasury(program)
+    await getOrCreateAssociatedTokenAccount(provider.connection, admin, mint, treasury)
+    await program.methods
+      .revokeVesting()
+      .accounts({
+        admin: admin.publicKey,
+        config,
+        vestingAccount,
+        vestingTokenAccount: ata(vestingAccount, true),
+        destinationTokenAccount: ata(beneficiary.publicKey),
+        treasuryTokenAccount: ata(treasury),
+        vestingSigner: vestingAccount,
+        launchConfig: launchConfigPda(program),
+        mint,
+        tokenProgram: TOKEN_PROGRAM_ID
+      })
+      .rpc()
+
+    await close(vestingAccount, admin)
+    assert.strictEqual(await provider.connection.getAccountInfo(vestingAccount), null)
+  })
+})
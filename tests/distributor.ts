@@ -0,0 +1,175 @@
+import * as anchor from '@coral-xyz/anchor'
+import BN from 'bn.js'
+import assert from 'assert'
+import * as web3 from '@solana/web3.js'
+import { execFileSync } from 'child_process'
+import fs from 'fs'
+import os from 'os'
+import path from 'path'
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createMint,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  mintTo
+} from '@solana/spl-token'
+import type { Team } from '../target/types/team'
+import {
+  PRESALE1_TEMPLATE,
+  PRESALE2_TEMPLATE,
+  configPda,
+  ensureConfigAndLaunch,
+  launchConfigPda,
+  templatePda
+} from './setup'
+
+const DEV_TEMPLATE = 0
+const DISTRIBUTOR_ID = 1
+
+type LeafProof = { index: number; wallet: string; amount: number; template_id: number; proof: string[] }
+
+describe('merkle distributor', () => {
+  const provider = anchor.AnchorProvider.env()
+  anchor.setProvider(provider)
+
+  const program = anchor.workspace.Team as anchor.Program<Team>
+  const admin = (provider.wallet as anchor.Wallet).payer
+  const config = configPda(program)
+  const id = Buffer.alloc(4)
+  id.writeUInt32LE(DISTRIBUTOR_ID)
+  const [distributor] = web3.PublicKey.findProgramAddressSync([Buffer.from('distributor'), id], program.programId)
+
+  // Presale1 is opened as vesting, Presale2 has fully unlocked and is claimed directly,
+  // and Dev is still vesting, so it cannot be claimed directly.
+  const vestingClaimant = web3.Keypair.generate()
+  const directClaimant = web3.Keypair.generate()
+  const lockedClaimant = web3.Keypair.generate()
+  const allocations = [
+    { wallet: vestingClaimant, amount: 1_000_000, templateId: PRESALE1_TEMPLATE },
+    { wallet: directClaimant, amount: 2_000_000, templateId: PRESALE2_TEMPLATE },
+    { wallet: lockedClaimant, amount: 3_000_000, templateId: DEV_TEMPLATE }
+  ]
+  let mint: web3.PublicKey
+  let tree: { root: string; num_leaves: number; total_amount: number; leaves: LeafProof[] }
+
+  before(async () => {
+    await ensureConfigAndLaunch(program, admin)
+    mint = await createMint(provider.connection, admin, admin.publicKey, null, 9)
+    const adminAta = await getOrCreateAssociatedTokenAccount(provider.connection, admin, mint, admin.publicKey)
+    await mintTo(provider.connection, admin, mint, adminAta.address, admin, 6_000_000)
+    for (const { wallet } of allocations) {
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(wallet.publicKey, web3.LAMPORTS_PER_SOL)
+      )
+    }
+
+    // Build the tree with the off-chain tool, as the admin would.
+    const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'distributor-'))
+    const csv = path.join(dir, 'allocations.csv')
+    const out = path.join(dir, 'tree.json')
+    fs.writeFileSync(
+      csv,
+      'wallet,amount,template_id\n' +
+        allocations.map((a) => `${a.wallet.publicKey.toBase58()},${a.amount},${a.templateId}`).join('\n')
+    )
+    execFileSync('cargo', ['run', '-q', '-p', 'team-merkle', '--', csv, out])
+    tree = JSON.parse(fs.readFileSync(out, 'utf8'))
+  })
+
+  const vault = () => getAssociatedTokenAddressSync(mint, distributor, true)
+  const proofOf = (leaf: LeafProof) => leaf.proof.map((p) => Array.from(Buffer.from(p, 'hex')))
+
+  const claimDirect = (leaf: LeafProof, wallet: web3.Keypair, amount = leaf.amount) =>
+    program.methods
+      .claimDistributorAllocation(leaf.index, new BN(amount), leaf.template_id, proofOf(leaf))
+      .accounts({
+        claimant: wallet.publicKey,
+        config,
+        distributor,
+        template: templatePda(program, leaf.template_id),
+        launchConfig: launchConfigPda(program),
+        mint,
+        vault: vault(),
+        destinationTokenAccount: getAssociatedTokenAddressSync(mint, wallet.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID
+      })
+      .signers([wallet])
+      .rpc()
+
+  it('creates and funds the distributor from the tool output', async () => {
+    await program.methods
+      .createDistributor(DISTRIBUTOR_ID, Array.from(Buffer.from(tree.root, 'hex')), tree.num_leaves, new BN(tree.total_amount))
+      .accounts({
+        admin: admin.publicKey,
+        config,
+        distributor,
+        adminTokenAccount: getAssociatedTokenAddressSync(mint, admin.publicKey),
+        mint,
+        vault: vault(),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID
+      })
+      .rpc()
+
+    const vaultAccount = await getAccount(provider.connection, vault())
+    assert.strictEqual(vaultAccount.amount.toString(), '6000000')
+  })
+
+  it('opens a vesting account for a proven allocation, once', async () => {
+    const leaf = tree.leaves[0]
+    const [beneficiaryCounter] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from('counter'), vestingClaimant.publicKey.toBuffer()],
+      program.programId
+    )
+    const vestingAccount = (grantIndex: number) =>
+      web3.PublicKey.findProgramAddressSync(
+        [Buffer.from('vesting'), vestingClaimant.publicKey.toBuffer(), Buffer.from([grantIndex, 0, 0, 0])],
+        program.programId
+      )[0]
+    const claim = (grantIndex: number) =>
+      program.methods
+        .claimDistributorVesting(leaf.index, new BN(leaf.amount), leaf.template_id, proofOf(leaf))
+        .accounts({
+          claimant: vestingClaimant.publicKey,
+          config,
+          distributor,
+          template: templatePda(program, leaf.template_id),
+          beneficiaryCounter,
+          vestingAccount: vestingAccount(grantIndex),
+          mint,
+          vault: vault(),
+          vestingTokenAccount: getAssociatedTokenAddressSync(mint, vestingAccount(grantIndex), true),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID
+        })
+        .signers([vestingClaimant])
+        .rpc()
+
+    await claim(0)
+    const vesting = await program.account.vestingAccount.fetch(vestingAccount(0))
+    assert.strictEqual(vesting.totalAmount.toNumber(), leaf.amount)
+    assert.strictEqual(vesting.templateId, PRESALE1_TEMPLATE)
+    assert.strictEqual(vesting.schedule.length, 2)
+
+    await assert.rejects(claim(1), /AllocationAlreadyClaimed/)
+  })
+
+  it('pays a fully unlocked allocation directly', async () => {
+    const leaf = tree.leaves[1]
+    await getOrCreateAssociatedTokenAccount(provider.connection, admin, mint, directClaimant.publicKey)
+
+    await assert.rejects(claimDirect(leaf, directClaimant, leaf.amount + 1), /InvalidMerkleProof/)
+    await claimDirect(leaf, directClaimant)
+
+    const received = await getAccount(provider.connection, getAssociatedTokenAddressSync(mint, directClaimant.publicKey))
+    assert.strictEqual(received.amount.toString(), leaf.amount.toString())
+  })
+
+  it('refuses a direct claim while the allocation is still vesting', async () => {
+    const leaf = tree.leaves[2]
+    await getOrCreateAssociatedTokenAccount(provider.connection, admin, mint, lockedClaimant.publicKey)
+    await assert.rejects(claimDirect(leaf, lockedClaimant), /AllocationStillVesting/)
+  })
+})
@@ -0,0 +1,112 @@
+import * as anchor from '@coral-xyz/anchor'
+import BN from 'bn.js'
+import assert from 'assert'
+import * as web3 from '@solana/web3.js'
+import { getOrCreateAssociatedTokenAccount } from '@solana/spl-token'
+import type { Team } from '../target/types/team'
+import {
+  MARKETING_CLIFFS,
+  MARKETING_TEMPLATE,
+  claimAccounts,
+  configPda,
+  createFundedMint,
+  ensureConfigAndLaunch,
+  initializeGrant,
+  templateSchedule
+} from './setup'
+
+const TOTAL_AMOUNT = new BN(1_000_000_000_000)
+
+describe('vesting events', () => {
+  const provider = anchor.AnchorProvider.env()
+  anchor.setProvider(provider)
+
+  const program = anchor.workspace.Team as anchor.Program<Team>
+  const admin = (provider.wallet as anchor.Wallet).payer
+  const [beneficiary, newWallet] = [web3.Keypair.generate(), web3.Keypair.generate()]
+  const parser = new anchor.EventParser(program.programId, new anchor.BorshCoder(program.idl))
+  let mint: web3.PublicKey
+  let vestingAccount: web3.PublicKey
+
+  // The events emitted in the given program logs.
+  const parseEvents = (logs: string[]) => [...parser.parseLogs(logs)]
+  const eventsOf = async (signature: string) => {
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: 'confirmed',
+      maxSupportedTransactionVersion: 0
+    })
+    return parseEvents(tx!.meta!.logMessages!)
+  }
+  const claim = () =>
+    program.methods
+      .claimUnlocked()
+      .accounts(claimAccounts(program, mint, vestingAccount, beneficiary.publicKey))
+      .signers([beneficiary])
+      .rpc({ commitment: 'confirmed' })
+
+  before(async () => {
+    await ensureConfigAndLaunch(program, admin)
+    mint = await createFundedMint(program, admin)
+    await getOrCreateAssociatedTokenAccount(provider.connection, admin, mint, beneficiary.publicKey)
+    vestingAccount = await initializeGrant(
+      program, admin, mint, beneficiary.publicKey, MARKETING_TEMPLATE, templateSchedule(MARKETING_CLIFFS, TOTAL_AMOUNT), true
+    )
+  })
+
+  it('emits VestingInitialized with the grant details', async () => {
+    const [{ signature }] = await provider.connection.getSignaturesForAddress(vestingAccount, {}, 'confirmed')
+    const [event] = await eventsOf(signature)
+
+    assert.strictEqual(event.name, 'VestingInitialized')
+    assert.ok(event.data.vestingAccount.equals(vestingAccount))
+    assert.ok(event.data.beneficiary.equals(beneficiary.publicKey))
+    assert.strictEqual(event.data.templateId, MARKETING_TEMPLATE)
+    assert.strictEqual(event.data.grantIndex, 0)
+    assert.strictEqual(event.data.totalAmount.toString(), TOTAL_AMOUNT.toString())
+    assert.strictEqual(event.data.revocable, true)
+  })
+
+  it('emits TokensClaimed with the amount released and the running total', async () => {
+    const [event] = await eventsOf(await claim())
+
+    const half = TOTAL_AMOUNT.divn(2).toString()
+    assert.strictEqual(event.name, 'TokensClaimed')
+    assert.strictEqual(event.data.amount.toString(), half)
+    assert.strictEqual(event.data.receivedAmount.toString(), half)
+    assert.strictEqual(event.data.claimedAmount.toString(), half)
+  })
+
+  it('emits no event from a rejected claim', async () => {
+    const err = await claim().then(
+      () => assert.fail('the claim should fail'),
+      (e) => e
+    )
+
+    assert.match(String(err), /NothingToClaim/)
+    assert.deepStrictEqual(parseEvents(err.logs), [])
+  })
+
+  it('emits AuthorityTransferProposed and AuthorityTransferred', async () => {
+    const config = configPda(program)
+    const proposed = await eventsOf(
+      await program.methods
+        .proposeAuthorityTransfer(newWallet.publicKey)
+        .accounts({ vestingAccount, authority: beneficiary.publicKey, config })
+        .signers([beneficiary])
+        .rpc({ commitment: 'confirmed' })
+    )
+    assert.strictEqual(proposed[0].name, 'AuthorityTransferProposed')
+    assert.ok(proposed[0].data.newAuthority.equals(newWallet.publicKey))
+
+    const transferred = await eventsOf(
+      await program.methods
+        .acceptAuthorityTransfer()
+        .accounts({ vestingAccount, newAuthority: newWallet.publicKey, config })
+        .signers([newWallet])
+        .rpc({ commitment: 'confirmed' })
+    )
+    assert.strictEqual(transferred[0].name, 'AuthorityTransferred')
+    assert.ok(transferred[0].data.previousAuthority.equals(beneficiary.publicKey))
+    assert.ok(transferred[0].data.authority.equals(newWallet.publicKey))
+  })
+})
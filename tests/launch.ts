@@ -0,0 +1,150 @@
+import * as anchor from '@coral-xyz/anchor'
+import BN from 'bn.js'
+import assert from 'assert'
+import * as web3 from '@solana/web3.js'
+import {
+  TOKEN_PROGRAM_ID,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount
+} from '@solana/spl-token'
+import type { Team } from '../target/types/team'
+import {
+  MARKETING_CLIFFS,
+  MARKETING_TEMPLATE,
+  PRESALE2_TEMPLATE,
+  claimAccounts,
+  cliffEntry,
+  configPda,
+  configTreasury,
+  createFundedMint,
+  ensureConfig,
+  initializeGrant,
+  launchConfigPda,
+  templateSchedule,
+  testLaunchTime
+} from './setup'
+
+const TOTAL_AMOUNT = new BN(1_000_000_000_000)
+
+// The launch time is global and frozen once set, so this file must run before any other sets it:
+// `anchor test` loads it first (`--file tests/launch.ts` in Anchor.toml).
+describe('launch time', () => {
+  const provider = anchor.AnchorProvider.env()
+  anchor.setProvider(provider)
+
+  const program = anchor.workspace.Team as anchor.Program<Team>
+  const admin = (provider.wallet as anchor.Wallet).payer
+  const config = configPda(program)
+  const launchConfig = launchConfigPda(program)
+  const beneficiary = web3.Keypair.generate()
+  let mint: web3.PublicKey
+  let vestingAccount: web3.PublicKey
+  let treasury: web3.PublicKey
+
+  const ata = (owner: web3.PublicKey, offCurve = false) => getAssociatedTokenAddressSync(mint, owner, offCurve)
+  const balance = async (owner: web3.PublicKey) => (await getAccount(provider.connection, ata(owner))).amount.toString()
+
+  const setLaunchTime = (launchTime: BN) =>
+    program.methods.setLaunchTime(launchTime).accounts({ admin: admin.publicKey, config, launchConfig }).rpc()
+  const claim = () =>
+    program.methods
+      .claimUnlocked()
+      .accounts(claimAccounts(program, mint, vestingAccount, beneficiary.publicKey))
+      .signers([beneficiary])
+      .rpc()
+
+  // Initializes a revocable Marketing grant to a fresh beneficiary.
+  const initializeRevocableGrant = async () => {
+    const owner = web3.Keypair.generate().publicKey
+    await getOrCreateAssociatedTokenAccount(provider.connection, admin, mint, owner)
+    const grant = await initializeGrant(
+      program, admin, mint, owner, MARKETING_TEMPLATE, templateSchedule(MARKETING_CLIFFS, TOTAL_AMOUNT), true
+    )
+    return { owner, grant }
+  }
+  const revoke = ({ owner, grant }: { owner: web3.PublicKey; grant: web3.PublicKey }) =>
+    program.methods
+      .revokeVesting()
+      .accounts({
+        admin: admin.publicKey,
+        config,
+        vestingAccount: grant,
+        vestingTokenAccount: ata(grant, true),
+        destinationTokenAccount: ata(owner),
+        treasuryTokenAccount: ata(treasury),
+        vestingSigner: grant,
+        launchConfig,
+        mint,
+        tokenProgram: TOKEN_PROGRAM_ID
+      })
+      .rpc()
+
+  before(async () => {
+    await ensureConfig(program, admin)
+    mint = await createFundedMint(program, admin)
+    await getOrCreateAssociatedTokenAccount(provider.connection, admin, mint, beneficiary.publicKey)
+    treasury = await configTreasury(program)
+    await getOrCreateAssociatedTokenAccount(provider.connection, admin, mint, treasury)
+    // Presale2 unlocks everything at week 12.
+    vestingAccount = await initializeGrant(
+      program, admin, mint, beneficiary.publicKey, PRESALE2_TEMPLATE, [cliffEntry(12, TOTAL_AMOUNT)]
+    )
+  })
+
+  it('initializes grants before the launch but rejects claims until it is set', async () => {
+    assert.ok((await program.account.launchConfig.fetch(launchConfig)).launchTime.isZero())
+    await assert.rejects(claim(), /LaunchTimeNotSet/)
+  })
+
+  it('returns a grant revoked before the launch entirely to the treasury', async () => {
+    const revoked = await initializeRevocableGrant()
+    const treasuryBefore = BigInt(await balance(treasury))
+    await revoke(revoked)
+
+    assert.strictEqual(await balance(revoked.owner), '0')
+    assert.strictEqual((BigInt(await balance(treasury)) - treasuryBefore).toString(), TOTAL_AMOUNT.toString())
+    assert.strictEqual((await program.account.vestingAccount.fetch(revoked.grant)).claimedAmount.toString(), '0')
+  })
+
+  it('rejects a zero launch time', async () => {
+    await assert.rejects(setLaunchTime(new BN(0)), /InvalidLaunchTime/)
+  })
+
+  it('rejects a launch time set by someone other than the admin', async () => {
+    const outsider = web3.Keypair.generate()
+    await assert.rejects(
+      program.methods
+        .setLaunchTime(testLaunchTime())
+        .accounts({ admin: outsider.publicKey, config, launchConfig })
+        .signers([outsider])
+        .rpc(),
+      /Unauthorized/
+    )
+  })
+
+  it('sets the launch time once and freezes it', async () => {
+    const launchTime = testLaunchTime()
+    await setLaunchTime(launchTime)
+    assert.strictEqual((await program.account.launchConfig.fetch(launchConfig)).launchTime.toString(), launchTime.toString())
+
+    await assert.rejects(setLaunchTime(launchTime.addn(1)), /LaunchTimeAlreadySet/)
+  })
+
+  it('resolves the schedule offsets against the launch time', async () => {
+    await claim()
+
+    assert.strictEqual(await balance(beneficiary.publicKey), TOTAL_AMOUNT.toString())
+  })
+
+  it('splits a grant revoked after the launch between the beneficiary and the treasury', async () => {
+    const revoked = await initializeRevocableGrant()
+    const treasuryBefore = BigInt(await balance(treasury))
+    await revoke(revoked)
+
+    // Half of the Marketing cliffs have unlocked 13 weeks after launch.
+    const half = TOTAL_AMOUNT.divn(2).toString()
+    assert.strictEqual(await balance(revoked.owner), half)
+    assert.strictEqual((BigInt(await balance(treasury)) - treasuryBefore).toString(), half)
+  })
+})
@@ -0,0 +1,90 @@
+import * as anchor from '@coral-xyz/anchor'
+import BN from 'bn.js'
+import assert from 'assert'
+import * as web3 from '@solana/web3.js'
+import { getAccount, getAssociatedTokenAddressSync, getOrCreateAssociatedTokenAccount } from '@solana/spl-token'
+import type { Team } from '../target/types/team'
+import {
+  CONTRIBUTOR_TEMPLATE,
+  PRESALE2_TEMPLATE,
+  SECONDS_PER_WEEK,
+  claimAccounts,
+  cliffEntry,
+  createFundedMint,
+  ensureConfigAndLaunch,
+  initializeGrant
+} from './setup'
+
+const SEGMENT_AMOUNT = new BN(1_000_000_000_000)
+
+// A linear segment unlocking from `startWeek` to `endWeek` after launch, with nothing unlocked
+// before `cliffWeek`.
+function segment(startWeek: number, endWeek: number, cliffWeek = startWeek) {
+  return {
+    releaseOffset: new BN(startWeek * SECONDS_PER_WEEK),
+    amount: SEGMENT_AMOUNT,
+    endOffset: new BN(endWeek * SECONDS_PER_WEEK),
+    cliffOffset: new BN(cliffWeek * SECONDS_PER_WEEK)
+  }
+}
+
+describe('linear vesting', () => {
+  const provider = anchor.AnchorProvider.env()
+  anchor.setProvider(provider)
+
+  const program = anchor.workspace.Team as anchor.Program<Team>
+  const admin = (provider.wallet as anchor.Wallet).payer
+  let mint: web3.PublicKey
+
+  before(async () => {
+    await ensureConfigAndLaunch(program, admin)
+    mint = await createFundedMint(program, admin)
+  })
+
+  it('unlocks a segment in proportion to the time elapsed and nothing before its cliff', async () => {
+    const beneficiary = web3.Keypair.generate()
+    await getOrCreateAssociatedTokenAccount(provider.connection, admin, mint, beneficiary.publicKey)
+    // Launch was 13 weeks ago: the first segment is halfway through, the second is before its cliff.
+    const vestingAccount = await initializeGrant(
+      program, admin, mint, beneficiary.publicKey, CONTRIBUTOR_TEMPLATE, [segment(1, 25), segment(2, 30, 20)]
+    )
+
+    await program.methods
+      .claimUnlocked()
+      .accounts(claimAccounts(program, mint, vestingAccount, beneficiary.publicKey))
+      .signers([beneficiary])
+      .rpc()
+
+    const received = new BN(
+      (await getAccount(provider.connection, getAssociatedTokenAddressSync(mint, beneficiary.publicKey))).amount.toString()
+    )
+    // Half of the first segment, give or take the seconds elapsed since the launch time was set.
+    const tolerance = SEGMENT_AMOUNT.divn(100)
+    assert.ok(received.sub(SEGMENT_AMOUNT.divn(2)).abs().lte(tolerance), `received ${received.toString()}`)
+  })
+
+  it('rejects a cliff entry in a linear template', async () => {
+    await assert.rejects(
+      initializeGrant(program, admin, mint, web3.Keypair.generate().publicKey, CONTRIBUTOR_TEMPLATE, [
+        cliffEntry(4, SEGMENT_AMOUNT)
+      ]),
+      /InvalidScheduleKind/
+    )
+  })
+
+  it('rejects a segment whose cliff is after its end', async () => {
+    await assert.rejects(
+      initializeGrant(program, admin, mint, web3.Keypair.generate().publicKey, CONTRIBUTOR_TEMPLATE, [
+        segment(4, 8, 9)
+      ]),
+      /InvalidScheduleKind/
+    )
+  })
+
+  it('rejects a linear segment in a cliff template', async () => {
+    await assert.rejects(
+      initializeGrant(program, admin, mint, web3.Keypair.generate().publicKey, PRESALE2_TEMPLATE, [segment(12, 16)]),
+      /InvalidScheduleKind/
+    )
+  })
+})
@@ -0,0 +1,101 @@
+import * as anchor from '@coral-xyz/anchor'
+import BN from 'bn.js'
+import assert from 'assert'
+import * as web3 from '@solana/web3.js'
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount
+} from '@solana/spl-token'
+import type { Team } from '../target/types/team'
+import {
+  PRESALE1_TEMPLATE,
+  PRESALE2_TEMPLATE,
+  beneficiaryCounterPda,
+  claimAccounts,
+  cliffEntry,
+  configPda,
+  createFundedMint,
+  ensureConfigAndLaunch,
+  initializeGrant,
+  templatePda,
+  vestingPda
+} from './setup'
+
+const FIRST_AMOUNT = new BN(1_000_000_000_000)
+const SECOND_AMOUNT = new BN(3_000_000_000_000)
+
+describe('multiple grants per beneficiary', () => {
+  const provider = anchor.AnchorProvider.env()
+  anchor.setProvider(provider)
+
+  const program = anchor.workspace.Team as anchor.Program<Team>
+  const admin = (provider.wallet as anchor.Wallet).payer
+  const beneficiary = web3.Keypair.generate()
+  let mint: web3.PublicKey
+
+  const ata = (owner: web3.PublicKey, offCurve = false) => getAssociatedTokenAddressSync(mint, owner, offCurve)
+  const claim = (vestingAccount: web3.PublicKey) =>
+    program.methods
+      .claimUnlocked()
+      .accounts(claimAccounts(program, mint, vestingAccount, beneficiary.publicKey))
+      .signers([beneficiary])
+      .rpc()
+
+  before(async () => {
+    await ensureConfigAndLaunch(program, admin)
+    mint = await createFundedMint(program, admin)
+    await getOrCreateAssociatedTokenAccount(provider.connection, admin, mint, beneficiary.publicKey)
+  })
+
+  it('gives each grant its own vesting account at the next index', async () => {
+    const first = await initializeGrant(
+      program, admin, mint, beneficiary.publicKey, PRESALE2_TEMPLATE, [cliffEntry(12, FIRST_AMOUNT)]
+    )
+    const half = SECOND_AMOUNT.divn(2)
+    const second = await initializeGrant(
+      program, admin, mint, beneficiary.publicKey, PRESALE1_TEMPLATE, [cliffEntry(4, half), cliffEntry(8, half)]
+    )
+
+    assert.ok(first.equals(vestingPda(program, beneficiary.publicKey, 0)))
+    assert.ok(second.equals(vestingPda(program, beneficiary.publicKey, 1)))
+    const counter = await program.account.beneficiaryCounter.fetch(beneficiaryCounterPda(program, beneficiary.publicKey))
+    assert.strictEqual(counter.grantCount, 2)
+    assert.strictEqual((await program.account.vestingAccount.fetch(second)).grantIndex, 1)
+
+    // Each grant is claimed on its own.
+    await claim(first)
+    assert.strictEqual((await getAccount(provider.connection, ata(beneficiary.publicKey))).amount.toString(), FIRST_AMOUNT.toString())
+    await claim(second)
+    assert.strictEqual(
+      (await getAccount(provider.connection, ata(beneficiary.publicKey))).amount.toString(),
+      FIRST_AMOUNT.add(SECOND_AMOUNT).toString()
+    )
+  })
+
+  it('rejects a vesting account that is not at the next grant index', async () => {
+    const stale = vestingPda(program, beneficiary.publicKey, 0)
+    await assert.rejects(
+      program.methods
+        .initializeVesting(PRESALE2_TEMPLATE, FIRST_AMOUNT, [cliffEntry(12, FIRST_AMOUNT)], false)
+        .accounts({
+          admin: admin.publicKey,
+          config: configPda(program),
+          template: templatePda(program, PRESALE2_TEMPLATE),
+          adminTokenAccount: ata(admin.publicKey),
+          beneficiary: beneficiary.publicKey,
+          beneficiaryCounter: beneficiaryCounterPda(program, beneficiary.publicKey),
+          vestingAccount: stale,
+          mint,
+          vestingTokenAccount: ata(stale, true),
+          systemProgram: web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID
+        })
+        .rpc(),
+      /ConstraintSeeds/
+    )
+  })
+})
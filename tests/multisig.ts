@@ -0,0 +1,201 @@
+import * as anchor from '@coral-xyz/anchor'
+import BN from 'bn.js'
+import assert from 'assert'
+import * as web3 from '@solana/web3.js'
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  mintTo
+} from '@solana/spl-token'
+import type { Team } from '../target/types/team'
+import {
+  MARKETING_CLIFFS,
+  MARKETING_TEMPLATE,
+  beneficiaryCounterPda,
+  configPda,
+  createFundedMint,
+  ensureConfigAndLaunch,
+  launchConfigPda,
+  templatePda,
+  templateSchedule,
+  vestingPda
+} from './setup'
+
+const TOTAL_AMOUNT = new BN(1_000_000_000_000)
+const THRESHOLD = 2
+
+// A 2-of-3 multisig takes over the config admin key, initializes a grant and distributes it.
+describe('multisig admin', () => {
+  const provider = anchor.AnchorProvider.env()
+  anchor.setProvider(provider)
+
+  const program = anchor.workspace.Team as anchor.Program<Team>
+  const admin = (provider.wallet as anchor.Wallet).payer
+  const config = configPda(program)
+  const [member2, member3] = [web3.Keypair.generate(), web3.Keypair.generate()]
+  const beneficiary = web3.Keypair.generate()
+  const [multisig] = web3.PublicKey.findProgramAddressSync(
+    [Buffer.from('multisig'), admin.publicKey.toBuffer()],
+    program.programId
+  )
+  const [multisigSigner] = web3.PublicKey.findProgramAddressSync(
+    [Buffer.from('multisig_signer'), multisig.toBuffer()],
+    program.programId
+  )
+  const vestingAccount = vestingPda(program, beneficiary.publicKey)
+  let mint: web3.PublicKey
+
+  const ata = (owner: web3.PublicKey, offCurve = false) => getAssociatedTokenAddressSync(mint, owner, offCurve)
+
+  // Creates a proposal for `ix`, approved by its proposer (the provider wallet, member 1).
+  const propose = async (ix: web3.TransactionInstruction) => {
+    const { proposalCount } = await program.account.multisig.fetch(multisig)
+    const [proposal] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from('proposal'), multisig.toBuffer(), proposalCount.toArrayLike(Buffer, 'le', 8)],
+      program.programId
+    )
+    await program.methods
+      .createProposal(
+        ix.programId,
+        ix.keys.map((k) => ({ pubkey: k.pubkey, isSigner: k.isSigner, isWritable: k.isWritable })),
+        ix.data
+      )
+      .accounts({ multisig, proposal, proposer: admin.publicKey, systemProgram: web3.SystemProgram.programId })
+      .rpc()
+    return proposal
+  }
+  const approve = (proposal: web3.PublicKey, member: web3.Keypair) =>
+    program.methods.approveProposal().accounts({ multisig, proposal, member: member.publicKey }).signers([member]).rpc()
+  const execute = async (proposal: web3.PublicKey) => {
+    const proposalData = await program.account.proposal.fetch(proposal)
+    // The multisig signer signs via the program, not the transaction.
+    const remainingAccounts = proposalData.accounts.map((a) => ({
+      pubkey: a.pubkey,
+      isSigner: false,
+      isWritable: a.isWritable
+    }))
+    remainingAccounts.push({ pubkey: proposalData.programId, isSigner: false, isWritable: false })
+    return program.methods
+      .executeProposal()
+      .accounts({ multisig, proposal, multisigSigner })
+      .remainingAccounts(remainingAccounts)
+      .preInstructions([web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 })])
+      .rpc()
+  }
+  const distributeIx = () =>
+    program.methods
+      .adminDistributeUnlocked()
+      .accounts({
+        admin: multisigSigner,
+        config,
+        vestingAccount,
+        authority: beneficiary.publicKey,
+        vestingTokenAccount: ata(vestingAccount, true),
+        destinationTokenAccount: ata(beneficiary.publicKey),
+        vestingSigner: vestingAccount,
+        launchConfig: launchConfigPda(program),
+        mint,
+        tokenProgram: TOKEN_PROGRAM_ID
+      })
+      .instruction()
+
+  before(async () => {
+    await ensureConfigAndLaunch(program, admin)
+    mint = await createFundedMint(program, admin)
+    await getOrCreateAssociatedTokenAccount(provider.connection, admin, mint, beneficiary.publicKey)
+
+    await program.methods
+      .createMultisig([admin.publicKey, member2.publicKey, member3.publicKey], THRESHOLD)
+      .accounts({ creator: admin.publicKey, multisig, multisigSigner, systemProgram: web3.SystemProgram.programId })
+      .rpc()
+    // As admin, the signer PDA pays the rent of the grants it initializes and funds them.
+    await provider.sendAndConfirm(
+      new web3.Transaction().add(
+        web3.SystemProgram.transfer({ fromPubkey: admin.publicKey, toPubkey: multisigSigner, lamports: web3.LAMPORTS_PER_SOL })
+      )
+    )
+    const signerAta = await getOrCreateAssociatedTokenAccount(provider.connection, admin, mint, multisigSigner, true)
+    await mintTo(provider.connection, admin, mint, signerAta.address, admin, BigInt(TOTAL_AMOUNT.toString()))
+
+    // Hand the admin key to the multisig.
+    await program.methods.proposeAdmin(multisigSigner).accounts({ admin: admin.publicKey, config }).rpc()
+    const accept = await propose(
+      await program.methods.acceptAdmin().accounts({ newAdmin: multisigSigner, config }).instruction()
+    )
+    await approve(accept, member2)
+    await execute(accept)
+    assert.ok((await program.account.config.fetch(config)).admin.equals(multisigSigner))
+  })
+
+  // The config is shared by every test file: hand the admin key back to the provider wallet.
+  after(async () => {
+    const rotateBack = await propose(
+      await program.methods.proposeAdmin(admin.publicKey).accounts({ admin: multisigSigner, config }).instruction()
+    )
+    await approve(rotateBack, member2)
+    await execute(rotateBack)
+    await program.methods.acceptAdmin().accounts({ newAdmin: admin.publicKey, config }).rpc()
+  })
+
+  it('initializes a grant once the proposal reaches the threshold', async () => {
+    const ix = await program.methods
+      .initializeVesting(MARKETING_TEMPLATE, TOTAL_AMOUNT, templateSchedule(MARKETING_CLIFFS, TOTAL_AMOUNT), false)
+      .accounts({
+        admin: multisigSigner,
+        config,
+        template: templatePda(program, MARKETING_TEMPLATE),
+        adminTokenAccount: ata(multisigSigner, true),
+        beneficiary: beneficiary.publicKey,
+        beneficiaryCounter: beneficiaryCounterPda(program, beneficiary.publicKey),
+        vestingAccount,
+        mint,
+        vestingTokenAccount: ata(vestingAccount, true),
+        systemProgram: web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID
+      })
+      .instruction()
+    const proposal = await propose(ix)
+
+    // Only the proposer has approved: 1 of 2.
+    await assert.rejects(execute(proposal), /NotEnoughApprovals/)
+
+    await approve(proposal, member3)
+    await execute(proposal)
+    assert.strictEqual(
+      (await getAccount(provider.connection, ata(vestingAccount, true))).amount.toString(),
+      TOTAL_AMOUNT.toString()
+    )
+
+    await assert.rejects(execute(proposal), /ProposalAlreadyExecuted/)
+  })
+
+  it('distributes unlocked tokens through a proposal', async () => {
+    const proposal = await propose(await distributeIx())
+    await approve(proposal, member2)
+    await execute(proposal)
+
+    const half = TOTAL_AMOUNT.divn(2).toString()
+    assert.strictEqual((await getAccount(provider.connection, ata(beneficiary.publicKey))).amount.toString(), half)
+  })
+
+  it('invalidates pending proposals when the members change', async () => {
+    const pending = await propose(await distributeIx())
+
+    const members = [admin.publicKey, member2.publicKey, member3.publicKey]
+    const update = await propose(
+      await program.methods
+        .setMultisigMembers(members, THRESHOLD)
+        .accounts({ multisig, multisigSigner })
+        .instruction()
+    )
+    await approve(update, member2)
+    await execute(update)
+
+    await assert.rejects(approve(pending, member2), /StaleProposal/)
+    await assert.rejects(execute(pending), /StaleProposal/)
+  })
+})
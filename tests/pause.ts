@@ -0,0 +1,127 @@
+import * as anchor from '@coral-xyz/anchor'
+import BN from 'bn.js'
+import assert from 'assert'
+import * as web3 from '@solana/web3.js'
+import { TOKEN_PROGRAM_ID, getAssociatedTokenAddressSync, getOrCreateAssociatedTokenAccount } from '@solana/spl-token'
+import type { Team } from '../target/types/team'
+import {
+  PRESALE2_TEMPLATE,
+  claimAccounts,
+  cliffEntry,
+  configPda,
+  configTreasury,
+  createFundedMint,
+  ensureConfigAndLaunch,
+  initializeGrant,
+  launchConfigPda,
+  templatePda
+} from './setup'
+
+const TOTAL_AMOUNT = new BN(1_000_000_000_000)
+const PAUSE_TEMPLATE = 100
+
+describe('emergency pause', () => {
+  const provider = anchor.AnchorProvider.env()
+  anchor.setProvider(provider)
+
+  const program = anchor.workspace.Team as anchor.Program<Team>
+  const admin = (provider.wallet as anchor.Wallet).payer
+  const config = configPda(program)
+  const [guardian, outsider, beneficiary] = [web3.Keypair.generate(), web3.Keypair.generate(), web3.Keypair.generate()]
+  let mint: web3.PublicKey
+  let vestingAccount: web3.PublicKey
+
+  const ata = (owner: web3.PublicKey, offCurve = false) => getAssociatedTokenAddressSync(mint, owner, offCurve)
+  const pause = (signer: web3.Keypair) =>
+    program.methods.pause().accounts({ signer: signer.publicKey, config }).signers([signer]).rpc()
+  const unpause = (signer: web3.Keypair) =>
+    program.methods.unpause().accounts({ admin: signer.publicKey, config }).signers([signer]).rpc()
+  const claim = () =>
+    program.methods
+      .claimUnlocked()
+      .accounts(claimAccounts(program, mint, vestingAccount, beneficiary.publicKey))
+      .signers([beneficiary])
+      .rpc()
+  const paused = async () => (await program.account.config.fetch(config)).paused
+
+  before(async () => {
+    await ensureConfigAndLaunch(program, admin)
+    mint = await createFundedMint(program, admin)
+    await getOrCreateAssociatedTokenAccount(provider.connection, admin, mint, beneficiary.publicKey)
+    await getOrCreateAssociatedTokenAccount(provider.connection, admin, mint, await configTreasury(program))
+    vestingAccount = await initializeGrant(
+      program, admin, mint, beneficiary.publicKey, PRESALE2_TEMPLATE, [cliffEntry(12, TOTAL_AMOUNT)], true
+    )
+    await program.methods.setGuardian(guardian.publicKey).accounts({ admin: admin.publicKey, config }).rpc()
+  })
+
+  // The config is shared by every test file: never leave the program paused.
+  after(async () => {
+    if (await paused()) {
+      await unpause(admin)
+    }
+    await program.methods.setGuardian(web3.PublicKey.default).accounts({ admin: admin.publicKey, config }).rpc()
+  })
+
+  it('lets only the guardian or the admin pause', async () => {
+    await assert.rejects(pause(outsider), /Unauthorized/)
+
+    await pause(guardian)
+    assert.ok(await paused())
+  })
+
+  it('rejects claims, initializations, revocations and template changes while paused', async () => {
+    await assert.rejects(claim(), /ProgramPaused/)
+    await assert.rejects(
+      initializeGrant(program, admin, mint, web3.Keypair.generate().publicKey, PRESALE2_TEMPLATE, [
+        cliffEntry(12, TOTAL_AMOUNT)
+      ]),
+      /ProgramPaused/
+    )
+    await assert.rejects(
+      program.methods
+        .revokeVesting()
+        .accounts({
+          admin: admin.publicKey,
+          config,
+          vestingAccount,
+          vestingTokenAccount: ata(vestingAccount, true),
+          destinationTokenAccount: ata(beneficiary.publicKey),
+          treasuryTokenAccount: ata(await configTreasury(program)),
+          vestingSigner: vestingAccount,
+          launchConfig: launchConfigPda(program),
+          mint,
+          tokenProgram: TOKEN_PROGRAM_ID
+        })
+        .rpc(),
+      /ProgramPaused/
+    )
+    await assert.rejects(
+      program.methods
+        .createTemplate(PAUSE_TEMPLATE, 'Paused', { cliff: {} }, [{ bps: 10_000, week: new BN(4) }])
+        .accounts({
+          admin: admin.publicKey,
+          config,
+          template: templatePda(program, PAUSE_TEMPLATE),
+          systemProgram: web3.SystemProgram.programId
+        })
+        .rpc(),
+      /ProgramPaused/
+    )
+    await assert.rejects(
+      program.methods
+        .retireTemplate()
+        .accounts({ admin: admin.publicKey, config, template: templatePda(program, PRESALE2_TEMPLATE) })
+        .rpc(),
+      /ProgramPaused/
+    )
+  })
+
+  it('lets only the admin unpause', async () => {
+    await assert.rejects(unpause(guardian), /Unauthorized/)
+
+    await unpause(admin)
+    assert.ok(!(await paused()))
+    await claim()
+  })
+})
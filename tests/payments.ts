@@ -0,0 +1,172 @@
+import * as anchor from '@coral-xyz/anchor'
+import BN from 'bn.js'
+import assert from 'assert'
+import { createHash } from 'crypto'
+import * as web3 from '@solana/web3.js'
+import { createMint, getAccount, getOrCreateAssociatedTokenAccount, mintTo, TOKEN_PROGRAM_ID } from '@solana/spl-token'
+import type { Team } from '../target/types/team'
+import { configPda, configTreasury, createTransferFeeMint, ensureConfigAndLaunch } from './setup'
+
+const FEE_BPS = 100 // 1%
+const PREMIUM_FEE_BPS = 50
+
+describe('merchant payments', () => {
+  const provider = anchor.AnchorProvider.env()
+  anchor.setProvider(provider)
+
+  const program = anchor.workspace.Team as anchor.Program<Team>
+  const admin = (provider.wallet as anchor.Wallet).payer
+  const config = configPda(program)
+  const merchantOwner = web3.Keypair.generate()
+  const attestor = web3.Keypair.generate()
+  const [paymentConfig] = web3.PublicKey.findProgramAddressSync([Buffer.from('payment_config')], program.programId)
+  const [merchant] = web3.PublicKey.findProgramAddressSync(
+    [Buffer.from('merchant'), merchantOwner.publicKey.toBuffer()],
+    program.programId
+  )
+  let mint: web3.PublicKey
+  let buyerTokenAccount: web3.PublicKey
+  let settlementAccount: web3.PublicKey
+  let treasuryTokenAccount: web3.PublicKey
+
+  const pay = (amount: BN, orderId: string, reference: web3.PublicKey, settlement = settlementAccount) =>
+    program.methods
+      .payMerchant(amount, orderId)
+      .accounts({
+        buyer: admin.publicKey,
+        config,
+        paymentConfig,
+        merchant,
+        buyerTokenAccount,
+        settlementAccount: settlement,
+        treasuryTokenAccount,
+        reference,
+        mint,
+        tokenProgram: TOKEN_PROGRAM_ID
+      })
+      .rpc()
+
+  const verifyMerchant = (signer = attestor) =>
+    program.methods
+      .setMerchantKycStatus({ verified: {} })
+      .accounts({ attestor: signer.publicKey, config, paymentConfig, merchant })
+      .signers([signer])
+      .rpc()
+
+  before(async () => {
+    await ensureConfigAndLaunch(program, admin)
+    mint = await createMint(provider.connection, admin, admin.publicKey, null, 9)
+    buyerTokenAccount = (await getOrCreateAssociatedTokenAccount(provider.connection, admin, mint, admin.publicKey)).address
+    settlementAccount = (await getOrCreateAssociatedTokenAccount(provider.connection, admin, mint, merchantOwner.publicKey)).address
+    const treasury = await configTreasury(program)
+    treasuryTokenAccount = (await getOrCreateAssociatedTokenAccount(provider.connection, admin, mint, treasury)).address
+    await mintTo(provider.connection, admin, mint, buyerTokenAccount, admin, 1_000_000_000_000n)
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(merchantOwner.publicKey, web3.LAMPORTS_PER_SOL)
+    )
+
+    // The payment config can only be created once, so check the transfer-fee rejection first.
+    const feeMint = await createTransferFeeMint(provider, admin, FEE_BPS)
+    const initialize = (paymentMint: web3.PublicKey) =>
+      program.methods
+        .initializePaymentConfig(FEE_BPS, attestor.publicKey)
+        .accounts({ admin: admin.publicKey, config, paymentConfig, mint: paymentMint })
+        .rpc()
+    await assert.rejects(initialize(feeMint), /TransferFeeNotSupported/)
+    await initialize(mint)
+    await program.methods
+      .registerMerchant(Array.from(createHash('sha256').update('Team556 Store').digest()))
+      .accounts({ owner: merchantOwner.publicKey, config, merchant, paymentConfig, settlementAccount })
+      .signers([merchantOwner])
+      .rpc()
+  })
+
+  it('rejects fees above the maximum', async () => {
+    await assert.rejects(
+      program.methods.updatePaymentFee(0, 1_001).accounts({ admin: admin.publicKey, config, paymentConfig }).rpc(),
+      /InvalidPaymentFee/
+    )
+  })
+
+  it('rejects registering a merchant while the program is paused', async () => {
+    const owner = web3.Keypair.generate()
+    const [pausedMerchant] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from('merchant'), owner.publicKey.toBuffer()],
+      program.programId
+    )
+    await program.methods.pause().accounts({ signer: admin.publicKey, config }).rpc()
+    try {
+      await assert.rejects(
+        program.methods
+          .registerMerchant(Array.from(createHash('sha256').update('Paused Store').digest()))
+          .accounts({ owner: owner.publicKey, config, merchant: pausedMerchant, paymentConfig, settlementAccount })
+          .signers([owner])
+          .rpc(),
+        /ProgramPaused/
+      )
+    } finally {
+      await program.methods.unpause().accounts({ admin: admin.publicKey, config }).rpc()
+    }
+  })
+
+  it('rejects payments to a merchant pending KYC', async () => {
+    const state = await program.account.merchant.fetch(merchant)
+    assert.deepStrictEqual(state.kycStatus, { unverified: {} })
+    await assert.rejects(pay(new BN(1_000), 'POS-0000', web3.Keypair.generate().publicKey), /MerchantNotVerified/)
+  })
+
+  it('lets only the attestor set the KYC status', async () => {
+    await assert.rejects(verifyMerchant(merchantOwner), /Unauthorized/)
+    await verifyMerchant()
+    assert.deepStrictEqual((await program.account.merchant.fetch(merchant)).kycStatus, { verified: {} })
+  })
+
+  it('splits a payment between the merchant and the treasury', async () => {
+    const reference = web3.Keypair.generate().publicKey
+    const amount = new BN(250_000_000_000)
+    const signature = await pay(amount, 'POS-0001', reference)
+
+    const fee = amount.muln(FEE_BPS).divn(10_000)
+    assert.strictEqual((await getAccount(provider.connection, settlementAccount)).amount.toString(), amount.sub(fee).toString())
+    assert.strictEqual((await getAccount(provider.connection, treasuryTokenAccount)).amount.toString(), fee.toString())
+
+    // Solana Pay finds the payment through its reference.
+    await provider.connection.confirmTransaction(signature, 'confirmed')
+    const found = await provider.connection.getSignaturesForAddress(reference, {}, 'confirmed')
+    assert.deepStrictEqual(found.map((s) => s.signature), [signature])
+  })
+
+  it('rejects a settlement account the merchant did not register', async () => {
+    await assert.rejects(
+      pay(new BN(1_000), 'POS-0002', web3.Keypair.generate().publicKey, buyerTokenAccount),
+      /InvalidSettlementAccount/
+    )
+  })
+
+  it('rejects order IDs longer than 64 bytes', async () => {
+    await assert.rejects(pay(new BN(1_000), 'x'.repeat(65), web3.Keypair.generate().publicKey), /OrderIdTooLong/)
+  })
+
+  it('charges the fee of the merchant\'s tier', async () => {
+    await program.methods.updatePaymentFee(1, PREMIUM_FEE_BPS).accounts({ admin: admin.publicKey, config, paymentConfig }).rpc()
+    await program.methods.setMerchantFeeTier(1).accounts({ admin: admin.publicKey, config, merchant }).rpc()
+
+    const before = (await getAccount(provider.connection, treasuryTokenAccount)).amount
+    const amount = new BN(100_000_000_000)
+    await pay(amount, 'POS-0003', web3.Keypair.generate().publicKey)
+    const after = (await getAccount(provider.connection, treasuryTokenAccount)).amount
+    assert.strictEqual((after - before).toString(), amount.muln(PREMIUM_FEE_BPS).divn(10_000).toString())
+  })
+
+  it('rejects payments while the merchant is suspended', async () => {
+    await program.methods.suspendMerchant().accounts({ admin: admin.publicKey, config, merchant }).rpc()
+    await assert.rejects(pay(new BN(1_000), 'POS-0004', web3.Keypair.generate().publicKey), /MerchantSuspended/)
+
+    await program.methods.reinstateMerchant().accounts({ admin: admin.publicKey, config, merchant }).rpc()
+    await pay(new BN(1_000), 'POS-0005', web3.Keypair.generate().publicKey)
+    await assert.rejects(
+      program.methods.reinstateMerchant().accounts({ admin: admin.publicKey, config, merchant }).rpc(),
+      /MerchantNotSuspended/
+    )
+  })
+})
@@ -0,0 +1,166 @@
+import * as anchor from '@coral-xyz/anchor'
+import BN from 'bn.js'
+import assert from 'assert'
+import * as web3 from '@solana/web3.js'
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createMint,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  mintTo
+} from '@solana/spl-token'
+import type { Team } from '../target/types/team'
+import {
+  PRESALE1_TEMPLATE,
+  PRESALE2_TEMPLATE,
+  SECONDS_PER_WEEK,
+  configPda,
+  ensureConfigAndLaunch,
+  templatePda
+} from './setup'
+
+const DECIMALS = 9
+const UNIT = new BN(10).pow(new BN(DECIMALS))
+// The primary cap fits two batches, so the second buyer sells it out.
+const PRIMARY = {
+  tokensPerBatch: new BN(1_000_000).mul(UNIT),
+  cap: new BN(2_000_000).mul(UNIT),
+  duration: new BN(4 * SECONDS_PER_WEEK),
+  priceLamports: new BN(web3.LAMPORTS_PER_SOL),
+  priceStable: new BN(250_000_000),
+  templateId: PRESALE1_TEMPLATE
+}
+const SECONDARY = {
+  tokensPerBatch: new BN(500_000).mul(UNIT),
+  cap: new BN(50_000_000).mul(UNIT),
+  duration: new BN(2 * SECONDS_PER_WEEK),
+  priceLamports: new BN(web3.LAMPORTS_PER_SOL / 2),
+  priceStable: new BN(150_000_000),
+  templateId: PRESALE2_TEMPLATE
+}
+
+describe('presale', () => {
+  const provider = anchor.AnchorProvider.env()
+  anchor.setProvider(provider)
+
+  const program = anchor.workspace.Team as anchor.Program<Team>
+  const admin = (provider.wallet as anchor.Wallet).payer
+  const config = configPda(program)
+  const [presale] = web3.PublicKey.findProgramAddressSync([Buffer.from('presale')], program.programId)
+  const [solVault] = web3.PublicKey.findProgramAddressSync([Buffer.from('presale_vault')], program.programId)
+  const [buyer, secondBuyer, lateBuyer] = [web3.Keypair.generate(), web3.Keypair.generate(), web3.Keypair.generate()]
+  let mint: web3.PublicKey
+  let tokenVault: web3.PublicKey
+
+  before(async () => {
+    await ensureConfigAndLaunch(program, admin)
+    mint = await createMint(provider.connection, admin, admin.publicKey, null, DECIMALS)
+    tokenVault = (await getOrCreateAssociatedTokenAccount(provider.connection, admin, mint, presale, true)).address
+    await mintTo(provider.connection, admin, mint, tokenVault, admin, BigInt(PRIMARY.tokensPerBatch.muln(10).toString()))
+    for (const wallet of [buyer, secondBuyer, lateBuyer]) {
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(wallet.publicKey, 5 * web3.LAMPORTS_PER_SOL)
+      )
+    }
+
+    await program.methods
+      .initializePresale(web3.PublicKey.default, PRIMARY, SECONDARY)
+      .accounts({ admin: admin.publicKey, config, presale, mint, solVault })
+      .rpc()
+    // Open the primary phase a minute ago, so it is already running.
+    await openPhase(0, Math.floor(Date.now() / 1000) - 60)
+  })
+
+  const openPhase = (phase: number, startTime: number) =>
+    program.methods
+      .openPresalePhase(phase === 0 ? { primary: {} } : { secondary: {} }, new BN(startTime))
+      .accounts({ admin: admin.publicKey, config, presale })
+      .rpc()
+
+  // Buys a batch of `phase` (0 = primary, 1 = secondary) as the wallet's grant `grantIndex`.
+  const buy = (phase: number, grantIndex: number, wallet = buyer) => {
+    const vestingAccount = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from('vesting'), wallet.publicKey.toBuffer(), Buffer.from([grantIndex, 0, 0, 0])],
+      program.programId
+    )[0]
+    return {
+      vestingAccount,
+      rpc: () =>
+        program.methods
+          .buyPresaleBatch(phase === 0 ? { primary: {} } : { secondary: {} })
+          .accounts({
+            buyer: wallet.publicKey,
+            config,
+            presale,
+            template: templatePda(program, phase === 0 ? PRESALE1_TEMPLATE : PRESALE2_TEMPLATE),
+            receipt: web3.PublicKey.findProgramAddressSync(
+              [Buffer.from('receipt'), Buffer.from([phase]), wallet.publicKey.toBuffer()],
+              program.programId
+            )[0],
+            beneficiaryCounter: web3.PublicKey.findProgramAddressSync(
+              [Buffer.from('counter'), wallet.publicKey.toBuffer()],
+              program.programId
+            )[0],
+            vestingAccount,
+            mint,
+            tokenVault,
+            vestingTokenAccount: getAssociatedTokenAddressSync(mint, vestingAccount, true),
+            solVault,
+            stableMint: null,
+            buyerStableAccount: null,
+            stableVault: null,
+            stableTokenProgram: null,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID
+          })
+          .signers([wallet])
+          .rpc()
+    }
+  }
+
+  it('sells a primary batch for SOL and opens a Presale1 vesting account', async () => {
+    const { vestingAccount, rpc } = buy(0, 0)
+    const vaultBefore = await provider.connection.getBalance(solVault)
+    await rpc()
+
+    assert.strictEqual(
+      (await provider.connection.getBalance(solVault)) - vaultBefore,
+      PRIMARY.priceLamports.toNumber()
+    )
+    const vesting = await program.account.vestingAccount.fetch(vestingAccount)
+    assert.ok(vesting.authority.equals(buyer.publicKey))
+    assert.strictEqual(vesting.templateId, PRESALE1_TEMPLATE)
+    assert.strictEqual(vesting.totalAmount.toString(), PRIMARY.tokensPerBatch.toString())
+    assert.strictEqual(vesting.schedule.length, 2)
+    const vestingAta = await getAccount(provider.connection, getAssociatedTokenAddressSync(mint, vestingAccount, true))
+    assert.strictEqual(vestingAta.amount.toString(), PRIMARY.tokensPerBatch.toString())
+  })
+
+  it('rejects a second primary batch for the same wallet', async () => {
+    await assert.rejects(buy(0, 1).rpc())
+  })
+
+  it('keeps the secondary phase closed while the primary is still selling', async () => {
+    await assert.rejects(openPhase(1, Math.floor(Date.now() / 1000)), /PrimaryPresaleActive/)
+  })
+
+  it('rejects primary batches beyond the cap', async () => {
+    await buy(0, 0, secondBuyer).rpc()
+    await assert.rejects(buy(0, 0, lateBuyer).rpc(), /PresaleCapReached/)
+
+    const state = await program.account.presale.fetch(presale)
+    assert.strictEqual(state.primaryState.sold.toString(), PRIMARY.cap.toString())
+  })
+
+  it('opens the secondary phase once the primary sells out', async () => {
+    await openPhase(1, Math.floor(Date.now() / 1000) - 60)
+    const { vestingAccount, rpc } = buy(1, 1)
+    await rpc()
+
+    const vesting = await program.account.vestingAccount.fetch(vestingAccount)
+    assert.strictEqual(vesting.templateId, PRESALE2_TEMPLATE)
+    assert.strictEqual(vesting.totalAmount.toString(), SECONDARY.tokensPerBatch.toString())
+  })
+})
@@ -0,0 +1,117 @@
+import * as anchor from '@coral-xyz/anchor'
+import BN from 'bn.js'
+import assert from 'assert'
+import * as web3 from '@solana/web3.js'
+import {
+  TOKEN_PROGRAM_ID,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount
+} from '@solana/spl-token'
+import type { Team } from '../target/types/team'
+import {
+  MARKETING_CLIFFS,
+  MARKETING_TEMPLATE,
+  configPda,
+  configTreasury,
+  createFundedMint,
+  ensureConfigAndLaunch,
+  initializeGrant,
+  launchConfigPda,
+  templateSchedule
+} from './setup'
+
+const TOTAL_AMOUNT = new BN(1_000_000_000_000)
+
+describe('revoke vesting', () => {
+  const provider = anchor.AnchorProvider.env()
+  anchor.setProvider(provider)
+
+  const program = anchor.workspace.Team as anchor.Program<Team>
+  const admin = (provider.wallet as anchor.Wallet).payer
+  const config = configPda(program)
+  let mint: web3.PublicKey
+  let treasury: web3.PublicKey
+
+  const ata = (owner: web3.PublicKey, offCurve = false) => getAssociatedTokenAddressSync(mint, owner, offCurve)
+  const balance = async (account: web3.PublicKey) => (await getAccount(provider.connection, account)).amount.toString()
+
+  // Initializes a Marketing grant to a fresh beneficiary, half of which has unlocked.
+  const initializeMarketingGrant = async (revocable: boolean) => {
+    const beneficiary = web3.Keypair.generate().publicKey
+    await getOrCreateAssociatedTokenAccount(provider.connection, admin, mint, beneficiary)
+    const vestingAccount = await initializeGrant(
+      program, admin, mint, beneficiary, MARKETING_TEMPLATE, templateSchedule(MARKETING_CLIFFS, TOTAL_AMOUNT), revocable
+    )
+    return { beneficiary, vestingAccount }
+  }
+
+  const revoke = (vestingAccount: web3.PublicKey, beneficiary: web3.PublicKey, treasuryTokenAccount = ata(treasury)) =>
+    program.methods
+      .revokeVesting()
+      .accounts({
+        admin: admin.publicKey,
+        config,
+        vestingAccount,
+        vestingTokenAccount: ata(vestingAccount, true),
+        destinationTokenAccount: ata(beneficiary),
+        treasuryTokenAccount,
+        vestingSigner: vestingAccount,
+        launchConfig: launchConfigPda(program),
+        mint,
+        tokenProgram: TOKEN_PROGRAM_ID
+      })
+      .rpc()
+
+  before(async () => {
+    await ensureConfigAndLaunch(program, admin)
+    mint = await createFundedMint(program, admin)
+    treasury = await configTreasury(program)
+    await getOrCreateAssociatedTokenAccount(provider.connection, admin, mint, treasury)
+  })
+
+  it('pays out the unlocked half and returns the rest to the config treasury', async () => {
+    const { beneficiary, vestingAccount } = await initializeMarketingGrant(true)
+    await revoke(vestingAccount, beneficiary)
+
+    const half = TOTAL_AMOUNT.divn(2).toString()
+    assert.strictEqual(await balance(ata(beneficiary)), half)
+    assert.strictEqual(await balance(ata(treasury)), half)
+    const vesting = await program.account.vestingAccount.fetch(vestingAccount)
+    assert.ok(vesting.terminated)
+    assert.strictEqual(vesting.claimedAmount.toString(), half)
+  })
+
+  it('rejects a treasury token account not owned by the config treasury', async () => {
+    const { beneficiary, vestingAccount } = await initializeMarketingGrant(true)
+    await assert.rejects(revoke(vestingAccount, beneficiary, ata(admin.publicKey)), /InvalidTreasury/)
+  })
+
+  it('rejects revoking a non-revocable grant', async () => {
+    const { beneficiary, vestingAccount } = await initializeMarketingGrant(false)
+    await assert.rejects(revoke(vestingAccount, beneficiary), /NotRevocable/)
+  })
+
+  it('rejects revoking a grant twice', async () => {
+    const { beneficiary, vestingAccount } = await initializeMarketingGrant(true)
+    await revoke(vestingAccount, beneficiary)
+    await assert.rejects(revoke(vestingAccount, beneficiary), /VestingTerminated/)
+  })
+
+  it('lets only the admin move the treasury', async () => {
+    const outsider = web3.Keypair.generate()
+    await assert.rejects(
+      program.methods.setTreasury(outsider.publicKey).accounts({ admin: outsider.publicKey, config }).signers([outsider]).rpc(),
+      /Unauthorized/
+    )
+
+    const newTreasury = web3.Keypair.generate().publicKey
+    await program.methods.setTreasury(newTreasury).accounts({ admin: admin.publicKey, config }).rpc()
+    assert.ok((await configTreasury(program)).equals(newTreasury))
+    // The previous treasury no longer receives revoked tokens.
+    const { beneficiary, vestingAccount } = await initializeMarketingGrant(true)
+    await assert.rejects(revoke(vestingAccount, beneficiary), /InvalidTreasury/)
+
+    await program.methods.setTreasury(treasury).accounts({ admin: admin.publicKey, config }).rpc()
+  })
+})
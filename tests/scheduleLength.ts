@@ -0,0 +1,135 @@
+import * as anchor from '@coral-xyz/anchor'
+import BN from 'bn.js'
+import assert from 'assert'
+import * as web3 from '@solana/web3.js'
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createMint,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  mintTo
+} from '@solana/spl-token'
+import type { Team } from '../target/types/team'
+import {
+  CONTRIBUTOR_TEMPLATE,
+  SECONDS_PER_WEEK,
+  configPda,
+  ensureConfigAndLaunch,
+  launchConfigPda,
+  templatePda
+} from './setup'
+
+// Must match `VestingAccount::MAX_SCHEDULES`.
+const MAX_SCHEDULES = 48
+// Longest schedule that fits in a single legacy `initialize_vesting` transaction.
+const MAX_INIT_SCHEDULES = 22
+// Tranches appended per `top_up_vesting` transaction.
+const TOP_UP_SCHEDULES = 13
+// Default compute budget of a single instruction.
+const DEFAULT_COMPUTE_UNITS = 200_000
+const AMOUNT_PER_ENTRY = new BN(1_000_000)
+
+describe('schedule length', () => {
+  const provider = anchor.AnchorProvider.env()
+  anchor.setProvider(provider)
+
+  const program = anchor.workspace.Team as anchor.Program<Team>
+  const admin = (provider.wallet as anchor.Wallet).payer
+  const config = configPda(program)
+  const launchConfig = launchConfigPda(program)
+  let mint: web3.PublicKey
+
+  before(async () => {
+    await ensureConfigAndLaunch(program, admin)
+    mint = await createMint(provider.connection, admin, admin.publicKey, null, 9)
+    const adminAta = await getOrCreateAssociatedTokenAccount(provider.connection, admin, mint, admin.publicKey)
+    await mintTo(provider.connection, admin, mint, adminAta.address, admin, 1_000_000_000_000)
+  })
+
+  // Initializes a Contributor grant of `entries` linear weekly segments, the first of which
+  // have started unlocking, and returns the compute units consumed by a full claim. Entries
+  // beyond what fits in `initialize_vesting` are appended with `top_up_vesting`.
+  async function measureClaim(entries: number): Promise<number> {
+    const beneficiary = web3.Keypair.generate()
+    const [beneficiaryCounter] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from('counter'), beneficiary.publicKey.toBuffer()],
+      program.programId
+    )
+    const [vestingAccount] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from('vesting'), beneficiary.publicKey.toBuffer(), Buffer.from([0, 0, 0, 0])],
+      program.programId
+    )
+    const vestingTokenAccount = getAssociatedTokenAddressSync(mint, vestingAccount, true)
+    const adminTokenAccount = getAssociatedTokenAddressSync(mint, admin.publicKey)
+    const destination = await getOrCreateAssociatedTokenAccount(provider.connection, admin, mint, beneficiary.publicKey)
+
+    // Launch was 13 weeks ago, so every segment added by a top-up still lies in the future.
+    const schedule = Array.from({ length: entries }, (_, i) => {
+      const start = new BN((i + 1) * SECONDS_PER_WEEK)
+      return { releaseOffset: start, amount: AMOUNT_PER_ENTRY, endOffset: start.addn(SECONDS_PER_WEEK), cliffOffset: start }
+    })
+    const initial = schedule.slice(0, MAX_INIT_SCHEDULES)
+    await program.methods
+      .initializeVesting(CONTRIBUTOR_TEMPLATE, AMOUNT_PER_ENTRY.muln(initial.length), initial, false)
+      .accounts({
+        admin: admin.publicKey,
+        config,
+        template: templatePda(program, CONTRIBUTOR_TEMPLATE),
+        adminTokenAccount,
+        beneficiary: beneficiary.publicKey,
+        beneficiaryCounter,
+        vestingAccount,
+        mint,
+        vestingTokenAccount,
+        systemProgram: web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID
+      })
+      .rpc()
+    for (let i = MAX_INIT_SCHEDULES; i < entries; i += TOP_UP_SCHEDULES) {
+      await program.methods
+        .topUpVesting(schedule.slice(i, i + TOP_UP_SCHEDULES))
+        .accounts({
+          admin: admin.publicKey,
+          config,
+          vestingAccount,
+          adminTokenAccount,
+          vestingTokenAccount,
+          mint,
+          launchConfig,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: web3.SystemProgram.programId
+        })
+        .rpc()
+    }
+
+    const info = await provider.connection.getAccountInfo(vestingAccount)
+    assert.strictEqual(info!.data.length, 8 + 193 + entries * 32, 'account space follows the schedule length')
+
+    const tx = await program.methods
+      .claimUnlocked()
+      .accounts({
+        vestingAccount,
+        authority: beneficiary.publicKey,
+        vestingTokenAccount,
+        destinationTokenAccount: destination.address,
+        vestingSigner: vestingAccount,
+        launchConfig,
+        config,
+        mint,
+        tokenProgram: TOKEN_PROGRAM_ID
+      })
+      .transaction()
+    tx.feePayer = admin.publicKey
+    const simulation = await provider.connection.simulateTransaction(tx, [admin, beneficiary])
+    assert.strictEqual(simulation.value.err, null, JSON.stringify(simulation.value.logs))
+    return simulation.value.unitsConsumed!
+  }
+
+  it('keeps a claim over MAX_SCHEDULES entries inside the default compute budget', async () => {
+    const unitsConsumed = await measureClaim(MAX_SCHEDULES)
+
+    assert(unitsConsumed < DEFAULT_COMPUTE_UNITS / 2, 'MAX_SCHEDULES leaves at least half the compute budget unused')
+  })
+})
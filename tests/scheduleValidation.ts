@@ -0,0 +1,62 @@
+import * as anchor from '@coral-xyz/anchor'
+import BN from 'bn.js'
+import assert from 'assert'
+import * as web3 from '@solana/web3.js'
+import type { Team } from '../target/types/team'
+import {
+  PRESALE1_TEMPLATE,
+  cliffEntry,
+  createFundedMint,
+  ensureConfigAndLaunch,
+  initializeGrant
+} from './setup'
+
+const TOTAL_AMOUNT = new BN(1_000_000_000_000)
+const HALF = TOTAL_AMOUNT.divn(2)
+
+// The Presale1 template unlocks 50% at week 4 and 50% at week 8.
+describe('schedule validation against templates', () => {
+  const provider = anchor.AnchorProvider.env()
+  anchor.setProvider(provider)
+
+  const program = anchor.workspace.Team as anchor.Program<Team>
+  const admin = (provider.wallet as anchor.Wallet).payer
+  let mint: web3.PublicKey
+
+  const initialize = (schedule: ReturnType<typeof cliffEntry>[]) =>
+    initializeGrant(program, admin, mint, web3.Keypair.generate().publicKey, PRESALE1_TEMPLATE, schedule)
+
+  before(async () => {
+    await ensureConfigAndLaunch(program, admin)
+    mint = await createFundedMint(program, admin)
+  })
+
+  it('accepts a schedule matching the template shares and offsets', async () => {
+    const vestingAccount = await initialize([cliffEntry(4, HALF), cliffEntry(8, HALF)])
+
+    const vesting = await program.account.vestingAccount.fetch(vestingAccount)
+    assert.strictEqual(vesting.templateId, PRESALE1_TEMPLATE)
+    assert.strictEqual(vesting.totalAmount.toString(), TOTAL_AMOUNT.toString())
+  })
+
+  it('rejects shares that differ from the template', async () => {
+    const sixty = TOTAL_AMOUNT.muln(6).divn(10)
+    await assert.rejects(
+      initialize([cliffEntry(4, sixty), cliffEntry(8, TOTAL_AMOUNT.sub(sixty))]),
+      /InvalidTrancheAmount/
+    )
+  })
+
+  it('rejects offsets that differ from the template', async () => {
+    await assert.rejects(initialize([cliffEntry(4, HALF), cliffEntry(9, HALF)]), /InvalidTrancheOffset/)
+  })
+
+  it('checks offsets from launch, not from the first release', async () => {
+    // Correctly spaced four weeks apart, but starting a week late.
+    await assert.rejects(initialize([cliffEntry(5, HALF), cliffEntry(9, HALF)]), /InvalidTrancheOffset/)
+  })
+
+  it('rejects a schedule with a different number of cliffs', async () => {
+    await assert.rejects(initialize([cliffEntry(8, TOTAL_AMOUNT)]), /InvalidSchedule/)
+  })
+})
@@ -0,0 +1,231 @@
+import * as anchor from '@coral-xyz/anchor'
+import BN from 'bn.js'
+import * as web3 from '@solana/web3.js'
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  ExtensionType,
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createInitializeMintInstruction,
+  createInitializeTransferFeeConfigInstruction,
+  createMint,
+  getAssociatedTokenAddressSync,
+  getMintLen,
+  getOrCreateAssociatedTokenAccount,
+  mintTo
+} from '@solana/spl-token'
+import type { Team } from '../target/types/team'
+
+export const SECONDS_PER_WEEK = 7 * 24 * 60 * 60
+
+// Launch time shared by all test files: 13 weeks ago, so every built-in template has cliffs
+// in the past (Presale2's only cliff is at 12 weeks).
+export const LAUNCH_WEEKS_AGO = 13
+
+export function configPda(program: anchor.Program<Team>): web3.PublicKey {
+  return web3.PublicKey.findProgramAddressSync([Buffer.from('config')], program.programId)[0]
+}
+
+// The treasury wallet recorded in the program config, receiving revoked tokens, presale
+// proceeds and merchant payment fees.
+export async function configTreasury(program: anchor.Program<Team>): Promise<web3.PublicKey> {
+  return (await program.account.config.fetch(configPda(program))).treasury
+}
+
+export function templatePda(program: anchor.Program<Team>, templateId: number): web3.PublicKey {
+  const id = Buffer.alloc(4)
+  id.writeUInt32LE(templateId)
+  return web3.PublicKey.findProgramAddressSync([Buffer.from('template'), id], program.programId)[0]
+}
+
+// IDs of the built-in schedule templates, mirroring `BUILTIN_TEMPLATES` in the program.
+export const DEV_TEMPLATE = 0
+export const MARKETING_TEMPLATE = 1
+export const PRESALE1_TEMPLATE = 2
+export const PRESALE2_TEMPLATE = 3
+export const CONTRIBUTOR_TEMPLATE = 4
+
+export function launchConfigPda(program: anchor.Program<Team>): web3.PublicKey {
+  return web3.PublicKey.findProgramAddressSync([Buffer.from('launch_config')], program.programId)[0]
+}
+
+export function beneficiaryCounterPda(program: anchor.Program<Team>, beneficiary: web3.PublicKey): web3.PublicKey {
+  return web3.PublicKey.findProgramAddressSync([Buffer.from('counter'), beneficiary.toBuffer()], program.programId)[0]
+}
+
+export function vestingPda(program: anchor.Program<Team>, beneficiary: web3.PublicKey, grantIndex = 0): web3.PublicKey {
+  const index = Buffer.alloc(4)
+  index.writeUInt32LE(grantIndex)
+  return web3.PublicKey.findProgramAddressSync(
+    [Buffer.from('vesting'), beneficiary.toBuffer(), index],
+    program.programId
+  )[0]
+}
+
+// A schedule entry unlocking `amount` at once, `weeks` after launch.
+export function cliffEntry(weeks: number, amount: BN) {
+  const offset = new BN(weeks * SECONDS_PER_WEEK)
+  return { releaseOffset: offset, amount, endOffset: offset, cliffOffset: offset }
+}
+
+// The cliffs of the Marketing template: 50% has unlocked (launch was 13 weeks ago), the rest
+// unlocks at week 14.
+export const MARKETING_CLIFFS = [
+  { bps: 1_000, week: 2 },
+  { bps: 1_500, week: 6 },
+  { bps: 2_500, week: 10 },
+  { bps: 5_000, week: 14 }
+]
+
+// Builds the cliff schedule of a template for `total`: each cliff gets its share (rounded down)
+// and the last cliff the remainder, as `Template::validate_schedule` expects.
+export function templateSchedule(cliffs: { bps: number; week: number }[], total: BN) {
+  let allocated = new BN(0)
+  return cliffs.map(({ bps, week }, i) => {
+    const amount = i === cliffs.length - 1 ? total.sub(allocated) : total.muln(bps).divn(10_000)
+    allocated = allocated.add(amount)
+    return cliffEntry(week, amount)
+  })
+}
+
+// Creates an SPL Token mint and mints `amount` to the admin's associated token account.
+export async function createFundedMint(
+  program: anchor.Program<Team>,
+  admin: web3.Keypair,
+  amount: bigint = 1_000_000_000_000_000n
+): Promise<web3.PublicKey> {
+  const connection = program.provider.connection
+  const mint = await createMint(connection, admin, admin.publicKey, null, 9)
+  const adminAta = await getOrCreateAssociatedTokenAccount(connection, admin, mint, admin.publicKey)
+  await mintTo(connection, admin, mint, adminAta.address, admin, amount)
+  return mint
+}
+
+// Initializes the beneficiary's next grant, funded from the admin's associated token account,
+// and returns its vesting account PDA.
+export async function initializeGrant(
+  program: anchor.Program<Team>,
+  admin: web3.Keypair,
+  mint: web3.PublicKey,
+  beneficiary: web3.PublicKey,
+  templateId: number,
+  schedule: { releaseOffset: BN; amount: BN; endOffset: BN; cliffOffset: BN }[],
+  revocable = false
+): Promise<web3.PublicKey> {
+  const beneficiaryCounter = beneficiaryCounterPda(program, beneficiary)
+  const counter = await program.account.beneficiaryCounter.fetchNullable(beneficiaryCounter)
+  const vestingAccount = vestingPda(program, beneficiary, counter ? counter.grantCount : 0)
+  const totalAmount = schedule.reduce((sum, entry) => sum.add(entry.amount), new BN(0))
+  await program.methods
+    .initializeVesting(templateId, totalAmount, schedule, revocable)
+    .accounts({
+      admin: admin.publicKey,
+      config: configPda(program),
+      template: templatePda(program, templateId),
+      adminTokenAccount: getAssociatedTokenAddressSync(mint, admin.publicKey),
+      beneficiary,
+      beneficiaryCounter,
+      vestingAccount,
+      mint,
+      vestingTokenAccount: getAssociatedTokenAddressSync(mint, vestingAccount, true),
+      systemProgram: web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID
+    })
+    .rpc()
+  return vestingAccount
+}
+
+// Accounts of `claim_unlocked` and `claim_amount` for a grant of an SPL Token mint, paying out to
+// the authority's associated token account.
+export function claimAccounts(
+  program: anchor.Program<Team>,
+  mint: web3.PublicKey,
+  vestingAccount: web3.PublicKey,
+  authority: web3.PublicKey
+) {
+  return {
+    vestingAccount,
+    authority,
+    vestingTokenAccount: getAssociatedTokenAddressSync(mint, vestingAccount, true),
+    destinationTokenAccount: getAssociatedTokenAddressSync(mint, authority),
+    vestingSigner: vestingAccount,
+    launchConfig: launchConfigPda(program),
+    config: configPda(program),
+    mint,
+    tokenProgram: TOKEN_PROGRAM_ID
+  }
+}
+
+// Launch time shared by all test files, LAUNCH_WEEKS_AGO weeks before now.
+export function testLaunchTime(): BN {
+  return new BN(Math.floor(Date.now() / 1000) - LAUNCH_WEEKS_AGO * SECONDS_PER_WEEK)
+}
+
+// Creates the program config (admin = provider wallet, treasury = a fresh wallet), the built-in
+// schedule templates and the launch config, unless an earlier test file already did. All are
+// global; the launch time is left as it is.
+export async function ensureConfig(program: anchor.Program<Team>, admin: web3.Keypair): Promise<void> {
+  const connection = program.provider.connection
+  const config = configPda(program)
+  const launchConfig = launchConfigPda(program)
+
+  if ((await connection.getAccountInfo(config)) === null) {
+    const [programData] = web3.PublicKey.findProgramAddressSync(
+      [program.programId.toBuffer()],
+      new web3.PublicKey('BPFLoaderUpgradeab1e11111111111111111111111')
+    )
+    await program.methods
+      .initializeConfig(admin.publicKey, web3.Keypair.generate().publicKey)
+      .accounts({ upgradeAuthority: admin.publicKey, config, program: program.programId, programData })
+      .rpc()
+  }
+  for (let id = 0; id <= CONTRIBUTOR_TEMPLATE; id++) {
+    const template = templatePda(program, id)
+    if ((await connection.getAccountInfo(template)) === null) {
+      await program.methods.createBuiltinTemplate(id).accounts({ admin: admin.publicKey, config, template }).rpc()
+    }
+  }
+  if ((await connection.getAccountInfo(launchConfig)) === null) {
+    await program.methods.initializeLaunchConfig().accounts({ admin: admin.publicKey, config, launchConfig }).rpc()
+  }
+}
+
+// Runs `ensureConfig` and sets the launch time to `testLaunchTime()` unless an earlier test file
+// already set it. The launch time is frozen once set.
+export async function ensureConfigAndLaunch(program: anchor.Program<Team>, admin: web3.Keypair): Promise<void> {
+  await ensureConfig(program, admin)
+  const launchConfig = launchConfigPda(program)
+  if ((await program.account.launchConfig.fetch(launchConfig)).launchTime.isZero()) {
+    await program.methods
+      .setLaunchTime(testLaunchTime())
+      .accounts({ admin: admin.publicKey, config: configPda(program), launchConfig })
+      .rpc()
+  }
+}
+
+// Creates a Token-2022 mint with the transfer-fee extension charging `feeBps` on every transfer.
+export async function createTransferFeeMint(
+  provider: anchor.AnchorProvider,
+  payer: web3.Keypair,
+  feeBps: number
+): Promise<web3.PublicKey> {
+  const mint = web3.Keypair.generate()
+  const space = getMintLen([ExtensionType.TransferFeeConfig])
+  const lamports = await provider.connection.getMinimumBalanceForRentExemption(space)
+  const tx = new web3.Transaction().add(
+    web3.SystemProgram.createAccount({
+      fromPubkey: payer.publicKey,
+      newAccountPubkey: mint.publicKey,
+      space,
+      lamports,
+      programId: TOKEN_2022_PROGRAM_ID
+    }),
+    createInitializeTransferFeeConfigInstruction(
+      mint.publicKey, payer.publicKey, payer.publicKey, feeBps, BigInt('18446744073709551615'), TOKEN_2022_PROGRAM_ID
+    ),
+    createInitializeMintInstruction(mint.publicKey, 9, payer.publicKey, null, TOKEN_2022_PROGRAM_ID)
+  )
+  await provider.sendAndConfirm(tx, [payer, mint])
+  return mint.publicKey
+}
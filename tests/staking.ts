@@ -0,0 +1,167 @@
+import * as anchor from '@coral-xyz/anchor'
+import BN from 'bn.js'
+import assert from 'assert'
+import * as web3 from '@solana/web3.js'
+import {
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createMint,
+  getAccount,
+  getOrCreateAssociatedTokenAccount,
+  mintTo
+} from '@solana/spl-token'
+import type { Staking } from '../target/types/staking'
+import { createTransferFeeMint } from './setup'
+
+const DECIMALS = 9
+const UNIT = new BN(10).pow(new BN(DECIMALS))
+const TIERS = [
+  { lockDays: 30, aprBps: 250 },
+  { lockDays: 240, aprBps: 1_500 }
+]
+const SECONDS_PER_YEAR = 365 * 24 * 60 * 60
+
+// Reward of a position over its whole lock, mirroring `reward_for` in the program.
+const rewardFor = (amount: BN, tier: { lockDays: number; aprBps: number }) =>
+  amount
+    .muln(tier.aprBps)
+    .mul(new BN(tier.lockDays * 24 * 60 * 60))
+    .div(new BN(10_000).mul(new BN(SECONDS_PER_YEAR)))
+
+describe('staking', () => {
+  const provider = anchor.AnchorProvider.env()
+  anchor.setProvider(provider)
+
+  const program = anchor.workspace.Staking as anchor.Program<Staking>
+  const admin = (provider.wallet as anchor.Wallet).payer
+  const staker = web3.Keypair.generate()
+  let mint: web3.PublicKey
+  let pool: web3.PublicKey
+  let stakeVault: web3.PublicKey
+  let rewardVault: web3.PublicKey
+  let adminTokenAccount: web3.PublicKey
+  let stakerTokenAccount: web3.PublicKey
+
+  const positionPda = (positionId: number) =>
+    web3.PublicKey.findProgramAddressSync(
+      [Buffer.from('position'), pool.toBuffer(), staker.publicKey.toBuffer(), Buffer.from([positionId, 0, 0, 0])],
+      program.programId
+    )[0]
+
+  const positionAccounts = (positionId: number) => ({
+    payer: staker.publicKey,
+    owner: staker.publicKey,
+    pool,
+    position: positionPda(positionId),
+    ownerTokenAccount: stakerTokenAccount,
+    rewardTokenAccount: stakerTokenAccount,
+    stakeVault,
+    rewardVault,
+    mint,
+    tokenProgram: TOKEN_PROGRAM_ID
+  })
+
+  const poolPdas = (poolMint: web3.PublicKey) => {
+    const [poolPda] = web3.PublicKey.findProgramAddressSync([Buffer.from('pool'), poolMint.toBuffer()], program.programId)
+    const vault = (seed: string) =>
+      web3.PublicKey.findProgramAddressSync([Buffer.from(seed), poolPda.toBuffer()], program.programId)[0]
+    return { pool: poolPda, stakeVault: vault('stake_vault'), rewardVault: vault('reward_vault') }
+  }
+
+  const initializePool = (poolMint: web3.PublicKey, tokenProgram = TOKEN_PROGRAM_ID) => {
+    const [programData] = web3.PublicKey.findProgramAddressSync(
+      [program.programId.toBuffer()],
+      new web3.PublicKey('BPFLoaderUpgradeab1e11111111111111111111111')
+    )
+    return program.methods
+      .initializePool(admin.publicKey)
+      .accounts({
+        upgradeAuthority: admin.publicKey,
+        ...poolPdas(poolMint),
+        mint: poolMint,
+        program: program.programId,
+        programData,
+        tokenProgram
+      })
+      .rpc()
+  }
+
+  const stake = (positionId: number, tierIndex: number, amount: BN) =>
+    program.methods
+      .stake(positionId, tierIndex, amount)
+      .accounts(positionAccounts(positionId))
+      .signers([staker])
+      .rpc()
+
+  before(async () => {
+    mint = await createMint(provider.connection, admin, admin.publicKey, null, DECIMALS)
+    const pdas = poolPdas(mint)
+    pool = pdas.pool
+    stakeVault = pdas.stakeVault
+    rewardVault = pdas.rewardVault
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(staker.publicKey, 2 * web3.LAMPORTS_PER_SOL)
+    )
+    adminTokenAccount = (await getOrCreateAssociatedTokenAccount(provider.connection, admin, mint, admin.publicKey)).address
+    stakerTokenAccount = (await getOrCreateAssociatedTokenAccount(provider.connection, admin, mint, staker.publicKey)).address
+    await mintTo(provider.connection, admin, mint, adminTokenAccount, admin, BigInt(new BN(1_000_000).mul(UNIT).toString()))
+    await mintTo(provider.connection, admin, mint, stakerTokenAccount, admin, BigInt(new BN(10_000_000).mul(UNIT).toString()))
+
+    await initializePool(mint)
+    await program.methods.setTiers(TIERS).accounts({ admin: admin.publicKey, pool }).rpc()
+  })
+
+  it('rejects transfer-fee mints, whose vault would hold less than the recorded stakes', async () => {
+    const feeMint = await createTransferFeeMint(provider, admin, 100)
+    await assert.rejects(initializePool(feeMint, TOKEN_2022_PROGRAM_ID), /TransferFeeNotSupported/)
+  })
+
+  it('rejects tiers that are not sorted by lock duration', async () => {
+    await assert.rejects(
+      program.methods.setTiers([TIERS[1], TIERS[0]]).accounts({ admin: admin.publicKey, pool }).rpc(),
+      /InvalidTiers/
+    )
+  })
+
+  it('rejects stakes whose reward the vault cannot cover', async () => {
+    await assert.rejects(stake(0, 1, new BN(1_000).mul(UNIT)), /InsufficientRewards/)
+  })
+
+  it('stakes and reserves the full reward', async () => {
+    await program.methods
+      .fundRewards(new BN(100_000).mul(UNIT))
+      .accounts({ funder: admin.publicKey, pool, funderTokenAccount: adminTokenAccount, rewardVault, mint, tokenProgram: TOKEN_PROGRAM_ID })
+      .rpc()
+
+    const amount = new BN(1_000_000).mul(UNIT)
+    await stake(0, 1, amount)
+
+    const position = await program.account.stakePosition.fetch(positionPda(0))
+    const state = await program.account.stakingPool.fetch(pool)
+    assert.strictEqual(position.totalReward.toString(), rewardFor(amount, TIERS[1]).toString())
+    assert.strictEqual(state.reservedRewards.toString(), position.totalReward.toString())
+    assert.strictEqual(state.totalStaked.toString(), amount.toString())
+    assert.strictEqual((await getAccount(provider.connection, stakeVault)).amount.toString(), amount.toString())
+  })
+
+  it('never reserves more rewards than the vault holds', async () => {
+    // 100_000 tokens fund 1_000_000 tokens for 240 days at 15% (98_630 tokens), but not 30 more days.
+    await assert.rejects(stake(1, 0, new BN(1_000_000).mul(UNIT)), /InsufficientRewards/)
+  })
+
+  it('claims rewards as they accrue', async () => {
+    await new Promise((resolve) => setTimeout(resolve, 2_000))
+    const before = (await getAccount(provider.connection, stakerTokenAccount)).amount
+    await program.methods.claimRewards().accounts(positionAccounts(0)).signers([staker]).rpc()
+    const after = (await getAccount(provider.connection, stakerTokenAccount)).amount
+
+    const position = await program.account.stakePosition.fetch(positionPda(0))
+    assert.ok(after > before)
+    assert.strictEqual((after - before).toString(), position.claimedReward.toString())
+    assert.ok(position.claimedReward.lt(position.totalReward))
+  })
+
+  it('keeps the stake locked until the lock ends', async () => {
+    await assert.rejects(program.methods.unstake().accounts(positionAccounts(0)).signers([staker]).rpc(), /StillLocked/)
+  })
+})
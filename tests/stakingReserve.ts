@@ -0,0 +1,137 @@
+import * as anchor from '@coral-xyz/anchor'
+import BN from 'bn.js'
+import assert from 'assert'
+import * as web3 from '@solana/web3.js'
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createMint,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  mintTo
+} from '@solana/spl-token'
+import type { Team } from '../target/types/team'
+import type { Staking } from '../target/types/staking'
+import { configPda, ensureConfigAndLaunch } from './setup'
+
+const RESERVE_AMOUNT = new BN(5_000_000_000_000)
+const NINE_MONTHS = 273 * 24 * 60 * 60
+
+describe('staking reserve', () => {
+  const provider = anchor.AnchorProvider.env()
+  anchor.setProvider(provider)
+
+  const program = anchor.workspace.Team as anchor.Program<Team>
+  const stakingProgram = anchor.workspace.Staking as anchor.Program<Staking>
+  const admin = (provider.wallet as anchor.Wallet).payer
+  const config = configPda(program)
+  const community = web3.Keypair.generate()
+  const [stakingReserve] = web3.PublicKey.findProgramAddressSync([Buffer.from('staking_reserve')], program.programId)
+  let mint: web3.PublicKey
+  let vault: web3.PublicKey
+  let releaseAccount: web3.PublicKey
+  let pool: web3.PublicKey
+  let rewardVault: web3.PublicKey
+
+  const release = () =>
+    program.methods
+      .releaseStakingReserve()
+      .accounts({ config, stakingReserve, vault, releaseAccount, mint, tokenProgram: TOKEN_PROGRAM_ID })
+      .rpc()
+
+  const moveToStaking = () =>
+    program.methods
+      .fundStakingFromReserve()
+      .accounts({
+        admin: admin.publicKey,
+        config,
+        stakingReserve,
+        vault,
+        pool,
+        rewardVault,
+        mint,
+        stakingProgram: stakingProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID
+      })
+      .rpc()
+
+  before(async () => {
+    await ensureConfigAndLaunch(program, admin)
+    mint = await createMint(provider.connection, admin, admin.publicKey, null, 9)
+    vault = getAssociatedTokenAddressSync(mint, stakingReserve, true)
+    const adminAta = await getOrCreateAssociatedTokenAccount(provider.connection, admin, mint, admin.publicKey)
+    await mintTo(provider.connection, admin, mint, adminAta.address, admin, BigInt(RESERVE_AMOUNT.toString()))
+    releaseAccount = (await getOrCreateAssociatedTokenAccount(provider.connection, admin, mint, community.publicKey)).address
+
+    pool = web3.PublicKey.findProgramAddressSync([Buffer.from('pool'), mint.toBuffer()], stakingProgram.programId)[0]
+    rewardVault = web3.PublicKey.findProgramAddressSync([Buffer.from('reward_vault'), pool.toBuffer()], stakingProgram.programId)[0]
+    const [programData] = web3.PublicKey.findProgramAddressSync(
+      [stakingProgram.programId.toBuffer()],
+      new web3.PublicKey('BPFLoaderUpgradeab1e11111111111111111111111')
+    )
+    await stakingProgram.methods
+      .initializePool(admin.publicKey)
+      .accounts({
+        upgradeAuthority: admin.publicKey,
+        pool,
+        mint,
+        stakeVault: web3.PublicKey.findProgramAddressSync([Buffer.from('stake_vault'), pool.toBuffer()], stakingProgram.programId)[0],
+        rewardVault,
+        program: stakingProgram.programId,
+        programData,
+        tokenProgram: TOKEN_PROGRAM_ID
+      })
+      .rpc()
+
+    await program.methods
+      .initializeStakingReserve(RESERVE_AMOUNT)
+      .accounts({
+        admin: admin.publicKey,
+        config,
+        stakingReserve,
+        adminTokenAccount: adminAta.address,
+        releaseAccount,
+        mint,
+        vault,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID
+      })
+      .rpc()
+  })
+
+  it('records a deadline nine months out', async () => {
+    const reserve = await program.account.stakingReserve.fetch(stakingReserve)
+    const now = Math.floor(Date.now() / 1000)
+    assert.ok(Math.abs(reserve.deadline.toNumber() - (now + NINE_MONTHS)) < 60)
+    assert.ok(reserve.releaseAccount.equals(releaseAccount))
+    assert.strictEqual((await getAccount(provider.connection, vault)).amount.toString(), RESERVE_AMOUNT.toString())
+  })
+
+  it('cannot be released before the deadline', async () => {
+    await assert.rejects(release(), /StakingReserveDeadlineNotReached/)
+  })
+
+  it('cannot be released while the program is paused', async () => {
+    await program.methods.pause().accounts({ signer: admin.publicKey, config }).rpc()
+    try {
+      await assert.rejects(release(), /ProgramPaused/)
+    } finally {
+      await program.methods.unpause().accounts({ admin: admin.publicKey, config }).rpc()
+    }
+  })
+
+  it('moves the reserve into the staking reward vault', async () => {
+    await moveToStaking()
+
+    const reserve = await program.account.stakingReserve.fetch(stakingReserve)
+    assert.deepStrictEqual(reserve.status, { movedToStaking: {} })
+    assert.strictEqual((await getAccount(provider.connection, rewardVault)).amount.toString(), RESERVE_AMOUNT.toString())
+    assert.strictEqual((await getAccount(provider.connection, vault)).amount.toString(), '0')
+  })
+
+  it('settles only once', async () => {
+    await assert.rejects(moveToStaking(), /StakingReserveSettled/)
+    await assert.rejects(release(), /StakingReserveSettled/)
+  })
+})
@@ -0,0 +1,120 @@
+import * as anchor from '@coral-xyz/anchor'
+import BN from 'bn.js'
+import assert from 'assert'
+import * as web3 from '@solana/web3.js'
+import type { Team } from '../target/types/team'
+import {
+  PRESALE2_TEMPLATE,
+  configPda,
+  createFundedMint,
+  ensureConfigAndLaunch,
+  initializeGrant,
+  templatePda,
+  templateSchedule
+} from './setup'
+
+const TOTAL_AMOUNT = new BN(1_000_000_000_000)
+const ADVISOR_TEMPLATE = 10
+const ADVISOR_CLIFFS = [
+  { bps: 2_500, week: 4 },
+  { bps: 7_500, week: 20 }
+]
+
+describe('schedule templates', () => {
+  const provider = anchor.AnchorProvider.env()
+  anchor.setProvider(provider)
+
+  const program = anchor.workspace.Team as anchor.Program<Team>
+  const admin = (provider.wallet as anchor.Wallet).payer
+  const config = configPda(program)
+  let mint: web3.PublicKey
+
+  const createTemplate = (
+    templateId: number,
+    name: string,
+    cliffs: { bps: number; week: number }[],
+    signer = admin
+  ) =>
+    program.methods
+      .createTemplate(
+        templateId,
+        name,
+        { cliff: {} },
+        cliffs.map(({ bps, week }) => ({ bps, week: new BN(week) }))
+      )
+      .accounts({
+        admin: signer.publicKey,
+        config,
+        template: templatePda(program, templateId),
+        systemProgram: web3.SystemProgram.programId
+      })
+      .signers([signer])
+      .rpc()
+  const retire = (templateId: number) =>
+    program.methods.retireTemplate().accounts({ admin: admin.publicKey, config, template: templatePda(program, templateId) }).rpc()
+  const grantAdvisor = () =>
+    initializeGrant(
+      program, admin, mint, web3.Keypair.generate().publicKey, ADVISOR_TEMPLATE, templateSchedule(ADVISOR_CLIFFS, TOTAL_AMOUNT)
+    )
+
+  before(async () => {
+    await ensureConfigAndLaunch(program, admin)
+    mint = await createFundedMint(program, admin)
+  })
+
+  it('creates a template that grants are validated against', async () => {
+    await createTemplate(ADVISOR_TEMPLATE, 'Advisor', ADVISOR_CLIFFS)
+    const template = await program.account.scheduleTemplate.fetch(templatePda(program, ADVISOR_TEMPLATE))
+    assert.strictEqual(template.name, 'Advisor')
+    assert.strictEqual(template.tranches.length, ADVISOR_CLIFFS.length)
+
+    const vestingAccount = await grantAdvisor()
+    assert.strictEqual((await program.account.vestingAccount.fetch(vestingAccount)).templateId, ADVISOR_TEMPLATE)
+  })
+
+  it('rejects template IDs reserved for the built-in templates', async () => {
+    await assert.rejects(createTemplate(PRESALE2_TEMPLATE, 'Shadow', ADVISOR_CLIFFS), /InvalidTemplate/)
+    await assert.rejects(
+      program.methods
+        .createBuiltinTemplate(ADVISOR_TEMPLATE + 1)
+        .accounts({ admin: admin.publicKey, config, template: templatePda(program, ADVISOR_TEMPLATE + 1) })
+        .rpc(),
+      /InvalidTemplate/
+    )
+  })
+
+  it('rejects cliffs that do not add up to 100%', async () => {
+    await assert.rejects(
+      createTemplate(ADVISOR_TEMPLATE + 1, 'Short', [{ bps: 2_500, week: 4 }, { bps: 7_000, week: 20 }]),
+      /InvalidTemplate/
+    )
+  })
+
+  it('rejects cliffs later than ten years after launch', async () => {
+    await assert.rejects(
+      createTemplate(ADVISOR_TEMPLATE + 1, 'Late', [{ bps: 2_500, week: 4 }, { bps: 7_500, week: 521 }]),
+      /InvalidTemplate/
+    )
+  })
+
+  it('rejects an empty or overlong name', async () => {
+    await assert.rejects(createTemplate(ADVISOR_TEMPLATE + 1, '', ADVISOR_CLIFFS), /InvalidTemplateName/)
+    await assert.rejects(createTemplate(ADVISOR_TEMPLATE + 1, 'x'.repeat(33), ADVISOR_CLIFFS), /InvalidTemplateName/)
+  })
+
+  it('rejects a template created by someone other than the admin', async () => {
+    const outsider = web3.Keypair.generate()
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(outsider.publicKey, web3.LAMPORTS_PER_SOL)
+    )
+    await assert.rejects(createTemplate(ADVISOR_TEMPLATE + 1, 'Rogue', ADVISOR_CLIFFS, outsider), /Unauthorized/)
+  })
+
+  it('rejects new grants from a retired template', async () => {
+    await retire(ADVISOR_TEMPLATE)
+    assert.ok((await program.account.scheduleTemplate.fetch(templatePda(program, ADVISOR_TEMPLATE))).retired)
+
+    await assert.rejects(grantAdvisor(), /TemplateRetired/)
+    await assert.rejects(retire(ADVISOR_TEMPLATE), /TemplateRetired/)
+  })
+})
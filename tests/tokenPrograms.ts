@@ -0,0 +1,158 @@
+import * as anchor from '@coral-xyz/anchor'
+import BN from 'bn.js'
+import assert from 'assert'
+import * as web3 from '@solana/web3.js'
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createMint,
+  getAccount,
+  getOrCreateAssociatedTokenAccount,
+  getAssociatedTokenAddressSync,
+  mintTo
+} from '@solana/spl-token'
+import type { Team } from '../target/types/team'
+import {
+  PRESALE2_TEMPLATE,
+  SECONDS_PER_WEEK,
+  configPda,
+  createTransferFeeMint,
+  ensureConfigAndLaunch,
+  launchConfigPda,
+  templatePda
+} from './setup'
+
+const DECIMALS = 9
+const TOTAL_AMOUNT = new BN(1_000_000_000_000)
+const FEE_BPS = 100 // 1%
+
+// The same vesting flow (initialize, claim, close) runs once per token program.
+const CASES = [
+  { name: 'SPL Token', programId: TOKEN_PROGRAM_ID, feeBps: 0 },
+  { name: 'Token-2022', programId: TOKEN_2022_PROGRAM_ID, feeBps: 0 },
+  { name: 'Token-2022 with transfer fee', programId: TOKEN_2022_PROGRAM_ID, feeBps: FEE_BPS }
+]
+
+describe('vesting across token programs', () => {
+  const provider = anchor.AnchorProvider.env()
+  anchor.setProvider(provider)
+
+  const program = anchor.workspace.Team as anchor.Program<Team>
+  const admin = (provider.wallet as anchor.Wallet).payer
+
+  const config = configPda(program)
+  const launchConfig = launchConfigPda(program)
+
+  before(async () => {
+    await ensureConfigAndLaunch(program, admin)
+  })
+
+  for (const { name, programId, feeBps } of CASES) {
+    describe(name, () => {
+      let mint: web3.PublicKey
+      const beneficiary = web3.Keypair.generate()
+
+      before(async () => {
+        mint = feeBps > 0 ? await createTransferFeeMint(provider, admin, feeBps) : await createMint(
+          provider.connection, admin, admin.publicKey, null, DECIMALS, undefined, undefined, programId
+        )
+        const adminAta = await getOrCreateAssociatedTokenAccount(
+          provider.connection, admin, mint, admin.publicKey, false, undefined, undefined, programId
+        )
+        // Enough to cover the vested amount plus any transfer fee on funding.
+        await mintTo(
+          provider.connection, admin, mint, adminAta.address, admin, BigInt(TOTAL_AMOUNT.muln(2).toString()),
+          [], undefined, programId
+        )
+        await getOrCreateAssociatedTokenAccount(
+          provider.connection, admin, mint, beneficiary.publicKey, false, undefined, undefined, programId
+        )
+      })
+
+      const vestingPda = () =>
+        web3.PublicKey.findProgramAddressSync(
+          [Buffer.from('vesting'), beneficiary.publicKey.toBuffer(), Buffer.from([0, 0, 0, 0])],
+          program.programId
+        )[0]
+      const ata = (owner: web3.PublicKey, offCurve = false) =>
+        getAssociatedTokenAddressSync(mint, owner, offCurve, programId)
+
+      it('funds the vesting token account with exactly the total amount', async () => {
+        const offset = new BN(12 * SECONDS_PER_WEEK)
+        const [beneficiaryCounter] = web3.PublicKey.findProgramAddressSync(
+          [Buffer.from('counter'), beneficiary.publicKey.toBuffer()],
+          program.programId
+        )
+        await program.methods
+          .initializeVesting(
+            PRESALE2_TEMPLATE,
+            TOTAL_AMOUNT,
+            [{ releaseOffset: offset, amount: TOTAL_AMOUNT, endOffset: offset, cliffOffset: offset }],
+            false
+          )
+          .accounts({
+            admin: admin.publicKey,
+            config,
+            template: templatePda(program, PRESALE2_TEMPLATE),
+            adminTokenAccount: ata(admin.publicKey),
+            beneficiary: beneficiary.publicKey,
+            beneficiaryCounter,
+            vestingAccount: vestingPda(),
+            mint,
+            vestingTokenAccount: ata(vestingPda(), true),
+            systemProgram: web3.SystemProgram.programId,
+            tokenProgram: programId,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID
+          })
+          .rpc()
+
+        const vestingAta = await getAccount(provider.connection, ata(vestingPda(), true), undefined, programId)
+        assert.strictEqual(vestingAta.amount.toString(), TOTAL_AMOUNT.toString())
+      })
+
+      it('claims everything and records the fee withheld from the beneficiary', async () => {
+        await program.methods
+          .claimUnlocked()
+          .accounts({
+            vestingAccount: vestingPda(),
+            authority: beneficiary.publicKey,
+            vestingTokenAccount: ata(vestingPda(), true),
+            destinationTokenAccount: ata(beneficiary.publicKey),
+            vestingSigner: vestingPda(),
+            launchConfig,
+            config,
+            mint,
+            tokenProgram: programId
+          })
+          .signers([beneficiary])
+          .rpc()
+
+        const vesting = await program.account.vestingAccount.fetch(vestingPda())
+        assert.strictEqual(vesting.claimedAmount.toString(), TOTAL_AMOUNT.toString())
+
+        const fee = TOTAL_AMOUNT.muln(feeBps).divn(10_000)
+        const received = await getAccount(provider.connection, ata(beneficiary.publicKey), undefined, programId)
+        assert.strictEqual(received.amount.toString(), TOTAL_AMOUNT.sub(fee).toString())
+      })
+
+      it('closes the exhausted vesting account', async () => {
+        await program.methods
+          .closeVesting()
+          .accounts({
+            closer: admin.publicKey,
+            config,
+            vestingAccount: vestingPda(),
+            vestingTokenAccount: ata(vestingPda(), true),
+            payer: admin.publicKey,
+            mint,
+            tokenProgram: programId
+          })
+          .rpc()
+
+        assert.strictEqual(await provider.connection.getAccountInfo(vestingPda()), null)
+        assert.strictEqual(await provider.connection.getAccountInfo(ata(vestingPda(), true)), null)
+      })
+    })
+  }
+})
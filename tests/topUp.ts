@@ -0,0 +1,109 @@
+import * as anchor from '@coral-xyz/anchor'
+import BN from 'bn.js'
+import assert from 'assert'
+import * as web3 from '@solana/web3.js'
+import { TOKEN_PROGRAM_ID, getAccount, getAssociatedTokenAddressSync } from '@solana/spl-token'
+import type { Team } from '../target/types/team'
+import {
+  CONTRIBUTOR_TEMPLATE,
+  PRESALE2_TEMPLATE,
+  SECONDS_PER_WEEK,
+  cliffEntry,
+  configPda,
+  createFundedMint,
+  ensureConfigAndLaunch,
+  initializeGrant,
+  launchConfigPda
+} from './setup'
+
+// Must match `VestingAccount::MAX_SCHEDULES`.
+const MAX_SCHEDULES = 48
+const AMOUNT = new BN(1_000_000_000)
+
+// A linear segment of AMOUNT from `startWeek` to `endWeek` after launch.
+function segment(startWeek: number, endWeek: number) {
+  const start = new BN(startWeek * SECONDS_PER_WEEK)
+  return { releaseOffset: start, amount: AMOUNT, endOffset: new BN(endWeek * SECONDS_PER_WEEK), cliffOffset: start }
+}
+
+describe('top up vesting', () => {
+  const provider = anchor.AnchorProvider.env()
+  anchor.setProvider(provider)
+
+  const program = anchor.workspace.Team as anchor.Program<Team>
+  const admin = (provider.wallet as anchor.Wallet).payer
+  let mint: web3.PublicKey
+
+  const ata = (owner: web3.PublicKey, offCurve = false) => getAssociatedTokenAddressSync(mint, owner, offCurve)
+  const grant = (templateId: number, schedule: ReturnType<typeof cliffEntry>[]) =>
+    initializeGrant(program, admin, mint, web3.Keypair.generate().publicKey, templateId, schedule)
+  const topUp = (vestingAccount: web3.PublicKey, tranches: ReturnType<typeof cliffEntry>[], signer = admin) =>
+    program.methods
+      .topUpVesting(tranches)
+      .accounts({
+        admin: signer.publicKey,
+        config: configPda(program),
+        vestingAccount,
+        adminTokenAccount: ata(admin.publicKey),
+        vestingTokenAccount: ata(vestingAccount, true),
+        mint,
+        launchConfig: launchConfigPda(program),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: web3.SystemProgram.programId
+      })
+      .signers([signer])
+      .rpc()
+
+  before(async () => {
+    await ensureConfigAndLaunch(program, admin)
+    mint = await createFundedMint(program, admin)
+  })
+
+  it('appends future tranches and keeps the total equal to the schedule sum', async () => {
+    const vestingAccount = await grant(PRESALE2_TEMPLATE, [cliffEntry(12, AMOUNT)])
+    await topUp(vestingAccount, [cliffEntry(20, AMOUNT), cliffEntry(24, AMOUNT.muln(2))])
+
+    const vesting = await program.account.vestingAccount.fetch(vestingAccount)
+    const sum = vesting.schedule.reduce((total, entry) => total.add(entry.amount), new BN(0))
+    assert.strictEqual(vesting.schedule.length, 3)
+    assert.strictEqual(vesting.totalAmount.toString(), AMOUNT.muln(4).toString())
+    assert.strictEqual(sum.toString(), vesting.totalAmount.toString())
+    assert.strictEqual((await getAccount(provider.connection, ata(vestingAccount, true))).amount.toString(), sum.toString())
+    const info = await provider.connection.getAccountInfo(vestingAccount)
+    assert.strictEqual(info!.data.length, 8 + 193 + 3 * 32)
+  })
+
+  it('rejects a top-up from someone other than the admin', async () => {
+    const vestingAccount = await grant(PRESALE2_TEMPLATE, [cliffEntry(12, AMOUNT)])
+    await assert.rejects(topUp(vestingAccount, [cliffEntry(20, AMOUNT)], web3.Keypair.generate()), /Unauthorized/)
+  })
+
+  it('rejects a cliff at the offset of the last cliff', async () => {
+    const vestingAccount = await grant(PRESALE2_TEMPLATE, [cliffEntry(12, AMOUNT)])
+    await assert.rejects(topUp(vestingAccount, [cliffEntry(12, AMOUNT)]), /InvalidSchedule/)
+  })
+
+  it('rejects a segment starting before the last segment ends', async () => {
+    const vestingAccount = await grant(CONTRIBUTOR_TEMPLATE, [segment(14, 30)])
+    await assert.rejects(topUp(vestingAccount, [segment(20, 40)]), /InvalidSchedule/)
+
+    await topUp(vestingAccount, [segment(30, 40)])
+  })
+
+  it('rejects tranches that are not in the future', async () => {
+    // Launch was 13 weeks ago.
+    const vestingAccount = await grant(CONTRIBUTOR_TEMPLATE, [segment(1, 2)])
+    await assert.rejects(topUp(vestingAccount, [segment(3, 4)]), /TrancheNotInFuture/)
+  })
+
+  it('rejects growing the schedule past MAX_SCHEDULES entries', async () => {
+    // 22 entries fit in `initialize_vesting`, top-ups of 13 bring the schedule to the bound.
+    const schedule = Array.from({ length: MAX_SCHEDULES + 1 }, (_, i) => segment(14 + i, 15 + i))
+    const vestingAccount = await grant(CONTRIBUTOR_TEMPLATE, schedule.slice(0, 22))
+    await topUp(vestingAccount, schedule.slice(22, 35))
+    await topUp(vestingAccount, schedule.slice(35, MAX_SCHEDULES))
+    assert.strictEqual((await program.account.vestingAccount.fetch(vestingAccount)).schedule.length, MAX_SCHEDULES)
+
+    await assert.rejects(topUp(vestingAccount, schedule.slice(MAX_SCHEDULES)), /InvalidSchedule/)
+  })
+})
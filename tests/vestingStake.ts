@@ -0,0 +1,232 @@
+import * as anchor from '@coral-xyz/anchor'
+import BN from 'bn.js'
+import assert from 'assert'
+import * as web3 from '@solana/web3.js'
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createMint,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  mintTo
+} from '@solana/spl-token'
+import type { Team } from '../target/types/team'
+import type { Staking } from '../target/types/staking'
+import { SECONDS_PER_WEEK, configPda, ensureConfigAndLaunch, launchConfigPda, templatePda } from './setup'
+
+const DECIMALS = 9
+const TOTAL_AMOUNT = new BN(1_000_000).mul(new BN(10).pow(new BN(DECIMALS)))
+// The Dev template: 5% at week 2 has unlocked (launch was 13 weeks ago), the rest is locked.
+const DEV_TEMPLATE = 0
+const DEV_CLIFFS = [
+  { bps: 500, week: 2 },
+  { bps: 1_500, week: 24 },
+  { bps: 3_000, week: 30 },
+  { bps: 5_000, week: 36 }
+]
+
+describe('staking vesting tokens', () => {
+  const provider = anchor.AnchorProvider.env()
+  anchor.setProvider(provider)
+
+  const program = anchor.workspace.Team as anchor.Program<Team>
+  const stakingProgram = anchor.workspace.Staking as anchor.Program<Staking>
+  const admin = (provider.wallet as anchor.Wallet).payer
+  const config = configPda(program)
+  const [beneficiary, revocableBeneficiary] = [web3.Keypair.generate(), web3.Keypair.generate()]
+  let mint: web3.PublicKey
+  let pool: web3.PublicKey
+  let stakeVault: web3.PublicKey
+  let rewardVault: web3.PublicKey
+
+  const ata = (owner: web3.PublicKey, offCurve = false) => getAssociatedTokenAddressSync(mint, owner, offCurve)
+  const vestingPda = (wallet: web3.Keypair) =>
+    web3.PublicKey.findProgramAddressSync(
+      [Buffer.from('vesting'), wallet.publicKey.toBuffer(), Buffer.from([0, 0, 0, 0])],
+      program.programId
+    )[0]
+  const positionPda = (vestingAccount: web3.PublicKey) =>
+    web3.PublicKey.findProgramAddressSync(
+      [Buffer.from('position'), pool.toBuffer(), vestingAccount.toBuffer(), Buffer.from([0, 0, 0, 0])],
+      stakingProgram.programId
+    )[0]
+
+  const initializeVesting = (wallet: web3.Keypair, revocable: boolean) => {
+    let allocated = new BN(0)
+    const schedule = DEV_CLIFFS.map(({ bps, week }, i) => {
+      const amount = i === DEV_CLIFFS.length - 1 ? TOTAL_AMOUNT.sub(allocated) : TOTAL_AMOUNT.muln(bps).divn(10_000)
+      allocated = allocated.add(amount)
+      const offset = new BN(week * SECONDS_PER_WEEK)
+      return { releaseOffset: offset, amount, endOffset: offset, cliffOffset: offset }
+    })
+    return program.methods
+      .initializeVesting(DEV_TEMPLATE, TOTAL_AMOUNT, schedule, revocable)
+      .accounts({
+        admin: admin.publicKey,
+        config,
+        template: templatePda(program, DEV_TEMPLATE),
+        adminTokenAccount: ata(admin.publicKey),
+        beneficiary: wallet.publicKey,
+        beneficiaryCounter: web3.PublicKey.findProgramAddressSync(
+          [Buffer.from('counter'), wallet.publicKey.toBuffer()],
+          program.programId
+        )[0],
+        vestingAccount: vestingPda(wallet),
+        mint,
+        vestingTokenAccount: ata(vestingPda(wallet), true),
+        systemProgram: web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID
+      })
+      .rpc()
+  }
+
+  const stakeVesting = (wallet: web3.Keypair, amount: BN) =>
+    program.methods
+      .stakeVesting(0, 0, amount)
+      .accounts({
+        authority: wallet.publicKey,
+        vestingAccount: vestingPda(wallet),
+        vestingTokenAccount: ata(vestingPda(wallet), true),
+        config,
+        pool,
+        position: positionPda(vestingPda(wallet)),
+        stakeVault,
+        rewardVault,
+        mint,
+        stakingProgram: stakingProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID
+      })
+      .signers([wallet])
+      .rpc()
+
+  const claimUnlocked = () =>
+    program.methods
+      .claimUnlocked()
+      .accounts({
+        vestingAccount: vestingPda(beneficiary),
+        authority: beneficiary.publicKey,
+        vestingTokenAccount: ata(vestingPda(beneficiary), true),
+        destinationTokenAccount: ata(beneficiary.publicKey),
+        vestingSigner: vestingPda(beneficiary),
+        launchConfig: launchConfigPda(program),
+        config,
+        mint,
+        tokenProgram: TOKEN_PROGRAM_ID
+      })
+      .signers([beneficiary])
+      .rpc()
+
+  before(async () => {
+    await ensureConfigAndLaunch(program, admin)
+    mint = await createMint(provider.connection, admin, admin.publicKey, null, DECIMALS)
+    pool = web3.PublicKey.findProgramAddressSync([Buffer.from('pool'), mint.toBuffer()], stakingProgram.programId)[0]
+    stakeVault = web3.PublicKey.findProgramAddressSync([Buffer.from('stake_vault'), pool.toBuffer()], stakingProgram.programId)[0]
+    rewardVault = web3.PublicKey.findProgramAddressSync([Buffer.from('reward_vault'), pool.toBuffer()], stakingProgram.programId)[0]
+    const adminAta = await getOrCreateAssociatedTokenAccount(provider.connection, admin, mint, admin.publicKey)
+    await mintTo(provider.connection, admin, mint, adminAta.address, admin, BigInt(TOTAL_AMOUNT.muln(3).toString()))
+    for (const wallet of [beneficiary, revocableBeneficiary]) {
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(wallet.publicKey, 2 * web3.LAMPORTS_PER_SOL)
+      )
+      await getOrCreateAssociatedTokenAccount(provider.connection, admin, mint, wallet.publicKey)
+    }
+
+    const [programData] = web3.PublicKey.findProgramAddressSync(
+      [stakingProgram.programId.toBuffer()],
+      new web3.PublicKey('BPFLoaderUpgradeab1e11111111111111111111111')
+    )
+    await stakingProgram.methods
+      .initializePool(admin.publicKey)
+      .accounts({ upgradeAuthority: admin.publicKey, pool, mint, stakeVault, rewardVault, program: stakingProgram.programId, programData, tokenProgram: TOKEN_PROGRAM_ID })
+      .rpc()
+    await stakingProgram.methods.setTiers([{ lockDays: 240, aprBps: 1_500 }]).accounts({ admin: admin.publicKey, pool }).rpc()
+    await stakingProgram.methods
+      .fundRewards(TOTAL_AMOUNT)
+      .accounts({ funder: admin.publicKey, pool, funderTokenAccount: adminAta.address, rewardVault, mint, tokenProgram: TOKEN_PROGRAM_ID })
+      .rpc()
+
+    await initializeVesting(beneficiary, false)
+    await initializeVesting(revocableBeneficiary, true)
+  })
+
+  it('rejects staking tokens of a revocable grant', async () => {
+    await assert.rejects(stakeVesting(revocableBeneficiary, TOTAL_AMOUNT.divn(2)), /RevocableVestingNotStakeable/)
+  })
+
+  it('stakes locked tokens without unlocking them', async () => {
+    // Leave 3% in the vesting account, less than the 5% already unlocked.
+    const amount = TOTAL_AMOUNT.muln(97).divn(100)
+    await stakeVesting(beneficiary, amount)
+
+    const vesting = await program.account.vestingAccount.fetch(vestingPda(beneficiary))
+    const position = await stakingProgram.account.stakePosition.fetch(positionPda(vestingPda(beneficiary)))
+    assert.strictEqual(vesting.stakedAmount.toString(), amount.toString())
+    assert.strictEqual(vesting.claimedAmount.toString(), '0')
+    assert.ok(position.owner.equals(vestingPda(beneficiary)))
+    assert.ok(position.payer.equals(beneficiary.publicKey))
+  })
+
+  it('rejects staking more than the unstaked balance', async () => {
+    await assert.rejects(stakeVesting(beneficiary, TOTAL_AMOUNT.divn(10)), /InvalidStakeAmount/)
+  })
+
+  it('caps claims at the tokens that are not staked', async () => {
+    await claimUnlocked()
+
+    const vesting = await program.account.vestingAccount.fetch(vestingPda(beneficiary))
+    assert.strictEqual(vesting.claimedAmount.toString(), TOTAL_AMOUNT.muln(3).divn(100).toString())
+    const vestingAta = await getAccount(provider.connection, ata(vestingPda(beneficiary), true))
+    assert.strictEqual(vestingAta.amount.toString(), '0')
+    await assert.rejects(claimUnlocked(), /NothingToClaim/)
+  })
+
+  it('pays staking rewards to the beneficiary', async () => {
+    await new Promise((resolve) => setTimeout(resolve, 2_000))
+    const before = (await getAccount(provider.connection, ata(beneficiary.publicKey))).amount
+    await program.methods
+      .claimVestingStakeRewards()
+      .accounts({
+        authority: beneficiary.publicKey,
+        vestingAccount: vestingPda(beneficiary),
+        config,
+        position: positionPda(vestingPda(beneficiary)),
+        pool,
+        destinationTokenAccount: ata(beneficiary.publicKey),
+        rewardVault,
+        mint,
+        stakingProgram: stakingProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID
+      })
+      .signers([beneficiary])
+      .rpc()
+    const after = (await getAccount(provider.connection, ata(beneficiary.publicKey))).amount
+    assert.ok(after > before)
+  })
+
+  it('keeps the stake locked until the position ends', async () => {
+    await assert.rejects(
+      program.methods
+        .unstakeVesting()
+        .accounts({
+          authority: beneficiary.publicKey,
+          vestingAccount: vestingPda(beneficiary),
+          vestingTokenAccount: ata(vestingPda(beneficiary), true),
+          config,
+          position: positionPda(vestingPda(beneficiary)),
+          positionPayer: beneficiary.publicKey,
+          pool,
+          destinationTokenAccount: ata(beneficiary.publicKey),
+          stakeVault,
+          rewardVault,
+          mint,
+          stakingProgram: stakingProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID
+        })
+        .signers([beneficiary])
+        .rpc(),
+      /StillLocked/
+    )
+  })
+})
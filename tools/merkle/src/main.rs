@@ -0,0 +1,188 @@
+//! Builds a distributor's Merkle tree from an allocations CSV and writes the root and each
+//! leaf's proof as JSON, for `create_distributor` and the claim instructions.
+//!
+//! Usage: team-merkle <allocations.csv> [output.json]
+//!
+//! The CSV has a header row and one allocation per row: `wallet,amount,template_id`, with
+//! `amount` in base units. Leaf indices follow the row order.
+
+use anchor_lang::prelude::Pubkey;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs::File;
+use std::str::FromStr;
+use team::merkle::{leaf_hash, node_hash, verify};
+
+/// One CSV row.
+#[derive(Deserialize)]
+struct Allocation {
+    wallet: String,
+    amount: u64,
+    template_id: u32,
+}
+
+/// A leaf and its proof, as written to the output.
+#[derive(Serialize)]
+struct LeafProof {
+    index: u32,
+    wallet: String,
+    amount: u64,
+    template_id: u32,
+    /// Hex-encoded sibling hashes from the leaf up to the root.
+    proof: Vec<String>,
+}
+
+/// The output file.
+#[derive(Serialize)]
+struct Tree {
+    /// Hex-encoded Merkle root.
+    root: String,
+    num_leaves: u32,
+    total_amount: u64,
+    leaves: Vec<LeafProof>,
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() < 2 || args.len() > 3 {
+        eprintln!("Usage: {} <allocations.csv> [output.json]", args[0]);
+        std::process::exit(2);
+    }
+    if let Err(err) = run(&args[1], args.get(2).map(String::as_str)) {
+        eprintln!("Error: {}", err);
+        std::process::exit(1);
+    }
+}
+
+fn run(csv_path: &str, output_path: Option<&str>) -> Result<(), Box<dyn Error>> {
+    // --- Read Allocations ---
+    let mut allocations = Vec::new();
+    for (row, record) in csv::Reader::from_path(csv_path)?.deserialize().enumerate() {
+        let allocation: Allocation = record.map_err(|e| format!("row {}: {}", row + 1, e))?;
+        let wallet = Pubkey::from_str(allocation.wallet.trim())
+            .map_err(|e| format!("row {}: invalid wallet {}: {}", row + 1, allocation.wallet, e))?;
+        if allocation.amount == 0 {
+            return Err(format!("row {}: amount must be positive", row + 1).into());
+        }
+        allocations.push((wallet, allocation));
+    }
+    if allocations.is_empty() || allocations.len() > team::Distributor::MAX_LEAVES {
+        return Err(format!("expected 1 to {} allocations", team::Distributor::MAX_LEAVES).into());
+    }
+
+    // --- Build Tree ---
+    let leaves: Vec<[u8; 32]> = allocations
+        .iter()
+        .enumerate()
+        .map(|(i, (wallet, a))| leaf_hash(i as u32, wallet, a.amount, a.template_id))
+        .collect();
+    let (root, proofs) = build_tree(&leaves);
+
+    let mut total_amount = 0u64;
+    let mut output = Vec::with_capacity(leaves.len());
+    for (i, ((wallet, a), proof)) in allocations.iter().zip(proofs).enumerate() {
+        // Check each proof the way the program will.
+        if !verify(&proof, &root, leaves[i]) {
+            return Err(format!("proof of leaf {} does not verify", i).into());
+        }
+        total_amount = total_amount.checked_add(a.amount).ok_or("total amount overflows u64")?;
+        output.push(LeafProof {
+            index: i as u32,
+            wallet: wallet.to_string(),
+            amount: a.amount,
+            template_id: a.template_id,
+            proof: proof.iter().map(hex).collect(),
+        });
+    }
+    let tree = Tree { root: hex(&root), num_leaves: leaves.len() as u32, total_amount, leaves: output };
+
+    // --- Write Output ---
+    match output_path {
+        Some(path) => serde_json::to_writer_pretty(File::create(path)?, &tree)?,
+        None => println!("{}", serde_json::to_string_pretty(&tree)?),
+    }
+    eprintln!("Root: {}", tree.root);
+    eprintln!("Leaves: {}, total amount: {}", tree.num_leaves, tree.total_amount);
+    Ok(())
+}
+
+/// Builds the tree bottom-up and returns the root and each leaf's proof.
+/// A node without a sibling is carried up to the next level unchanged.
+fn build_tree(leaves: &[[u8; 32]]) -> ([u8; 32], Vec<Vec<[u8; 32]>>) {
+    let mut proofs = vec![Vec::new(); leaves.len()];
+    // Position of each leaf's ancestor in the current level.
+    let mut positions: Vec<usize> = (0..leaves.len()).collect();
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        for (proof, position) in proofs.iter_mut().zip(positions.iter_mut()) {
+            let sibling = *position ^ 1;
+            if sibling < level.len() {
+                proof.push(level[sibling]);
+            }
+            *position /= 2;
+        }
+        level = level
+            .chunks(2)
+            .map(|pair| if pair.len() == 2 { node_hash(&pair[0], &pair[1]) } else { pair[0] })
+            .collect();
+    }
+    (level[0], proofs)
+}
+
+fn hex(bytes: &[u8; 32]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaves(count: u32) -> Vec<[u8; 32]> {
+        (0..count).map(|i| leaf_hash(i, &Pubkey::new_unique(), 1_000 + i as u64, 0)).collect()
+    }
+
+    #[test]
+    fn single_leaf_is_the_root() {
+        let leaves = leaves(1);
+        let (root, proofs) = build_tree(&leaves);
+        assert_eq!(root, leaves[0]);
+        assert!(proofs[0].is_empty());
+        assert!(verify(&proofs[0], &root, leaves[0]));
+    }
+
+    #[test]
+    fn every_proof_verifies() {
+        // Odd counts carry a node without a sibling up one or more levels.
+        for count in 2..=9 {
+            let leaves = leaves(count);
+            let (root, proofs) = build_tree(&leaves);
+            for (leaf, proof) in leaves.iter().zip(&proofs) {
+                assert!(verify(proof, &root, *leaf), "leaf of a {}-leaf tree", count);
+            }
+        }
+    }
+
+    #[test]
+    fn carried_node_has_a_shorter_proof() {
+        // With 5 leaves, leaf 4 has no sibling until the top level.
+        let leaves = leaves(5);
+        let (root, proofs) = build_tree(&leaves);
+        assert_eq!(proofs[0].len(), 3);
+        assert_eq!(proofs[4].len(), 1);
+        assert_eq!(root, node_hash(&proofs[4][0], &leaves[4]));
+    }
+
+    #[test]
+    fn tampered_proof_does_not_verify() {
+        let leaves = leaves(4);
+        let (root, proofs) = build_tree(&leaves);
+
+        let mut proof = proofs[1].clone();
+        proof[0][0] ^= 1;
+        assert!(!verify(&proof, &root, leaves[1]));
+        // A valid proof does not verify another leaf.
+        assert!(!verify(&proofs[1], &root, leaves[2]));
+        // Nor does a truncated one.
+        assert!(!verify(&proofs[1][..1], &root, leaves[1]));
+    }
+}